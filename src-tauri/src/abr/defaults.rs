@@ -31,6 +31,12 @@ impl AbrDefaults {
     /// Default opacity jitter
     pub const OPACITY_JITTER: f32 = 0.0;
 
+    /// Default flow jitter
+    pub const FLOW_JITTER: f32 = 0.0;
+
+    /// Default number of dabs for the Fade control
+    pub const FADE_STEPS: u32 = 25;
+
     /// Default scatter amount
     pub const SCATTER: f32 = 0.0;
 
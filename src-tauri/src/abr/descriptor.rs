@@ -0,0 +1,458 @@
+//! ActionDescriptor reader
+//!
+//! Modern ABR files (v6+) store brush settings in a `desc` 8BIM section
+//! serialized as a Photoshop ActionDescriptor: a recursive key/value
+//! structure with 4-character type codes.
+//!
+//! Reference: Adobe Photoshop File Formats Specification, "Descriptor structure"
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use super::error::AbrError;
+
+/// Maximum nesting depth accepted before the data is treated as corrupt
+const MAX_DEPTH: u32 = 32;
+
+/// A parsed ActionDescriptor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Descriptor {
+    /// Class ID (e.g. "brushPreset", "sampledBrush")
+    pub class_id: String,
+    /// Key/value items in file order
+    pub items: Vec<(String, DescriptorValue)>,
+}
+
+/// A single descriptor value
+#[derive(Debug, Clone, PartialEq)]
+pub enum DescriptorValue {
+    /// Nested descriptor (`Objc` / `GlbO`)
+    Descriptor(Descriptor),
+    /// List of values (`VlLs`)
+    List(Vec<DescriptorValue>),
+    /// Double (`doub`)
+    Double(f64),
+    /// Unit float (`UntF`), unit is e.g. "#Pxl", "#Prc", "#Ang"
+    UnitFloat { unit: String, value: f64 },
+    /// Unit float list (`UnFl`)
+    UnitFloats { unit: String, values: Vec<f64> },
+    /// Unicode string (`TEXT`)
+    Text(String),
+    /// Enumerated value (`enum`)
+    Enum { type_id: String, value: String },
+    /// 32-bit integer (`long`)
+    Integer(i32),
+    /// 64-bit integer (`comp`)
+    LargeInteger(i64),
+    /// Boolean (`bool`)
+    Boolean(bool),
+    /// Class reference (`type` / `GlbC`)
+    Class { name: String, class_id: String },
+    /// Opaque data (`alis`, `tdta`, `Pth `)
+    Raw(Vec<u8>),
+    /// Reference (`obj `), kept only as its item count
+    Reference(u32),
+}
+
+impl Descriptor {
+    /// Look up a value by key
+    pub fn get(&self, key: &str) -> Option<&DescriptorValue> {
+        self.items.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Look up a nested descriptor
+    pub fn get_descriptor(&self, key: &str) -> Option<&Descriptor> {
+        match self.get(key)? {
+            DescriptorValue::Descriptor(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Look up a list
+    pub fn get_list(&self, key: &str) -> Option<&[DescriptorValue]> {
+        match self.get(key)? {
+            DescriptorValue::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// Look up a numeric value (double, unit float, or integer)
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        match self.get(key)? {
+            DescriptorValue::Double(v) => Some(*v),
+            DescriptorValue::UnitFloat { value, .. } => Some(*value),
+            DescriptorValue::Integer(v) => Some(*v as f64),
+            DescriptorValue::LargeInteger(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    /// Look up a numeric value as f32
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        self.get_f64(key).map(|v| v as f32)
+    }
+
+    /// Look up a percentage value (`#Prc` unit float) as a 0-1 fraction
+    pub fn get_fraction(&self, key: &str) -> Option<f32> {
+        self.get_f32(key).map(|v| v / 100.0)
+    }
+
+    /// Look up an integer
+    pub fn get_i32(&self, key: &str) -> Option<i32> {
+        match self.get(key)? {
+            DescriptorValue::Integer(v) => Some(*v),
+            DescriptorValue::Double(v) => Some(*v as i32),
+            _ => None,
+        }
+    }
+
+    /// Look up a boolean
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            DescriptorValue::Boolean(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Look up a string
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            DescriptorValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Look up an enum value (the value ID, not the type ID)
+    pub fn get_enum(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            DescriptorValue::Enum { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Read a descriptor (name, class ID and items) at the cursor position
+pub fn read_descriptor(cursor: &mut Cursor<&[u8]>) -> Result<Descriptor, AbrError> {
+    read_descriptor_at_depth(cursor, 0)
+}
+
+fn read_descriptor_at_depth(
+    cursor: &mut Cursor<&[u8]>,
+    depth: u32,
+) -> Result<Descriptor, AbrError> {
+    if depth > MAX_DEPTH {
+        return Err(AbrError::Parse("Descriptor nesting too deep".into()));
+    }
+
+    // Display name is not needed
+    let _name = read_unicode_string(cursor)?;
+    let class_id = read_key(cursor)?;
+    let count = cursor.read_u32::<BigEndian>()?;
+
+    let mut items = Vec::with_capacity(count.min(256) as usize);
+    for _ in 0..count {
+        let key = read_key(cursor)?;
+        let value = read_value(cursor, depth)?;
+        items.push((key, value));
+    }
+
+    Ok(Descriptor { class_id, items })
+}
+
+/// Read a typed value (4-byte OSType followed by data)
+fn read_value(cursor: &mut Cursor<&[u8]>, depth: u32) -> Result<DescriptorValue, AbrError> {
+    let os_type = read_os_type(cursor)?;
+
+    let value = match &os_type {
+        b"Objc" | b"GlbO" => {
+            DescriptorValue::Descriptor(read_descriptor_at_depth(cursor, depth + 1)?)
+        }
+        b"VlLs" => {
+            let count = cursor.read_u32::<BigEndian>()?;
+            let mut list = Vec::with_capacity(count.min(1024) as usize);
+            for _ in 0..count {
+                list.push(read_value(cursor, depth + 1)?);
+            }
+            DescriptorValue::List(list)
+        }
+        b"doub" => DescriptorValue::Double(cursor.read_f64::<BigEndian>()?),
+        b"UntF" => {
+            let unit = os_type_string(read_os_type(cursor)?);
+            let value = cursor.read_f64::<BigEndian>()?;
+            DescriptorValue::UnitFloat { unit, value }
+        }
+        b"UnFl" => {
+            let unit = os_type_string(read_os_type(cursor)?);
+            let count = cursor.read_u32::<BigEndian>()?;
+            let mut values = Vec::with_capacity(count.min(1024) as usize);
+            for _ in 0..count {
+                values.push(cursor.read_f64::<BigEndian>()?);
+            }
+            DescriptorValue::UnitFloats { unit, values }
+        }
+        b"TEXT" => DescriptorValue::Text(read_unicode_string(cursor)?),
+        b"enum" => {
+            let type_id = read_key(cursor)?;
+            let value = read_key(cursor)?;
+            DescriptorValue::Enum { type_id, value }
+        }
+        b"long" => DescriptorValue::Integer(cursor.read_i32::<BigEndian>()?),
+        b"comp" => DescriptorValue::LargeInteger(cursor.read_i64::<BigEndian>()?),
+        b"bool" => DescriptorValue::Boolean(cursor.read_u8()? != 0),
+        b"type" | b"GlbC" => {
+            let name = read_unicode_string(cursor)?;
+            let class_id = read_key(cursor)?;
+            DescriptorValue::Class { name, class_id }
+        }
+        b"alis" | b"tdta" | b"Pth " => {
+            let length = cursor.read_u32::<BigEndian>()? as usize;
+            DescriptorValue::Raw(read_bytes(cursor, length)?)
+        }
+        b"obj " => {
+            let count = cursor.read_u32::<BigEndian>()?;
+            for _ in 0..count {
+                skip_reference_item(cursor)?;
+            }
+            DescriptorValue::Reference(count)
+        }
+        _ => {
+            return Err(AbrError::Parse(format!(
+                "Unsupported descriptor type '{}'",
+                os_type_string(os_type)
+            )))
+        }
+    };
+
+    Ok(value)
+}
+
+/// Skip one item of a reference (`obj `) structure
+fn skip_reference_item(cursor: &mut Cursor<&[u8]>) -> Result<(), AbrError> {
+    let os_type = read_os_type(cursor)?;
+    match &os_type {
+        b"prop" => {
+            read_unicode_string(cursor)?;
+            read_key(cursor)?;
+            read_key(cursor)?;
+        }
+        b"Clss" => {
+            read_unicode_string(cursor)?;
+            read_key(cursor)?;
+        }
+        b"Enmr" => {
+            read_unicode_string(cursor)?;
+            read_key(cursor)?;
+            read_key(cursor)?;
+            read_key(cursor)?;
+        }
+        b"rele" => {
+            read_unicode_string(cursor)?;
+            read_key(cursor)?;
+            cursor.read_i32::<BigEndian>()?;
+        }
+        b"Idnt" | b"indx" => {
+            cursor.read_i32::<BigEndian>()?;
+        }
+        b"name" => {
+            read_unicode_string(cursor)?;
+            read_key(cursor)?;
+            read_unicode_string(cursor)?;
+        }
+        _ => {
+            return Err(AbrError::Parse(format!(
+                "Unsupported reference type '{}'",
+                os_type_string(os_type)
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Read a key / class ID: length-prefixed string, or a 4-byte ID when length is 0
+fn read_key(cursor: &mut Cursor<&[u8]>) -> Result<String, AbrError> {
+    let length = cursor.read_u32::<BigEndian>()? as usize;
+    let length = if length == 0 { 4 } else { length };
+    let bytes = read_bytes(cursor, length)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Read a Unicode string (u32 length in UTF-16 code units, big-endian)
+pub(super) fn read_unicode_string(cursor: &mut Cursor<&[u8]>) -> Result<String, AbrError> {
+    let length = cursor.read_u32::<BigEndian>()? as usize;
+    ensure_remaining(cursor, length * 2)?;
+
+    let mut utf16_data = Vec::with_capacity(length);
+    for _ in 0..length {
+        utf16_data.push(cursor.read_u16::<BigEndian>()?);
+    }
+
+    // Strings are usually null-terminated
+    while utf16_data.last() == Some(&0) {
+        utf16_data.pop();
+    }
+
+    String::from_utf16(&utf16_data).map_err(|e| AbrError::StringDecode(e.to_string()))
+}
+
+fn read_os_type(cursor: &mut Cursor<&[u8]>) -> Result<[u8; 4], AbrError> {
+    let mut os_type = [0u8; 4];
+    cursor.read_exact(&mut os_type)?;
+    Ok(os_type)
+}
+
+fn os_type_string(os_type: [u8; 4]) -> String {
+    String::from_utf8_lossy(&os_type).into_owned()
+}
+
+fn read_bytes(cursor: &mut Cursor<&[u8]>, length: usize) -> Result<Vec<u8>, AbrError> {
+    ensure_remaining(cursor, length)?;
+    let mut bytes = vec![0u8; length];
+    cursor.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Guard against corrupt lengths before allocating
+fn ensure_remaining(cursor: &mut Cursor<&[u8]>, length: usize) -> Result<(), AbrError> {
+    let position = cursor.stream_position()?;
+    let total = cursor.get_ref().len() as u64;
+    if position + length as u64 > total {
+        // Move to the end so the caller sees a consistent EOF state
+        cursor.seek(SeekFrom::End(0))?;
+        return Err(AbrError::UnexpectedEof);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(super) mod test_util {
+    //! Helpers to build descriptor bytes in tests
+
+    pub fn key(out: &mut Vec<u8>, key: &str) {
+        if key.len() == 4 {
+            out.extend(0u32.to_be_bytes());
+        } else {
+            out.extend((key.len() as u32).to_be_bytes());
+        }
+        out.extend(key.as_bytes());
+    }
+
+    pub fn unicode(out: &mut Vec<u8>, s: &str) {
+        let units: Vec<u16> = s.encode_utf16().chain(std::iter::once(0)).collect();
+        out.extend((units.len() as u32).to_be_bytes());
+        for u in units {
+            out.extend(u.to_be_bytes());
+        }
+    }
+
+    pub fn descriptor_header(out: &mut Vec<u8>, class_id: &str, count: u32) {
+        unicode(out, "");
+        key(out, class_id);
+        out.extend(count.to_be_bytes());
+    }
+
+    pub fn unit_float(out: &mut Vec<u8>, name: &str, unit: &str, value: f64) {
+        key(out, name);
+        out.extend(b"UntF");
+        out.extend(unit.as_bytes());
+        out.extend(value.to_be_bytes());
+    }
+
+    pub fn boolean(out: &mut Vec<u8>, name: &str, value: bool) {
+        key(out, name);
+        out.extend(b"bool");
+        out.push(value as u8);
+    }
+
+    pub fn long(out: &mut Vec<u8>, name: &str, value: i32) {
+        key(out, name);
+        out.extend(b"long");
+        out.extend(value.to_be_bytes());
+    }
+
+    pub fn text(out: &mut Vec<u8>, name: &str, value: &str) {
+        key(out, name);
+        out.extend(b"TEXT");
+        unicode(out, value);
+    }
+
+    pub fn enumerated(out: &mut Vec<u8>, name: &str, type_id: &str, value: &str) {
+        key(out, name);
+        out.extend(b"enum");
+        self::key(out, type_id);
+        self::key(out, value);
+    }
+
+    /// Start a nested `Objc` item; the caller appends `count` items after it
+    pub fn object(out: &mut Vec<u8>, name: &str, class_id: &str, count: u32) {
+        key(out, name);
+        out.extend(b"Objc");
+        descriptor_header(out, class_id, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_read_flat_descriptor() {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "computedBrush", 4);
+        unit_float(&mut data, "Dmtr", "#Pxl", 42.0);
+        boolean(&mut data, "Intr", true);
+        text(&mut data, "Nm  ", "Round");
+        enumerated(&mut data, "BlnM", "BlnM", "Mltp");
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(desc) = read_descriptor(&mut cursor) else {
+            panic!("descriptor should parse");
+        };
+
+        assert_eq!(desc.class_id, "computedBrush");
+        assert_eq!(desc.get_f32("Dmtr"), Some(42.0));
+        assert_eq!(desc.get_bool("Intr"), Some(true));
+        assert_eq!(desc.get_text("Nm  "), Some("Round"));
+        assert_eq!(desc.get_enum("BlnM"), Some("Mltp"));
+        assert_eq!(desc.get("missing"), None);
+    }
+
+    #[test]
+    fn test_read_nested_descriptor_and_list() {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "null", 1);
+        key(&mut data, "Brsh");
+        data.extend(b"VlLs");
+        data.extend(1u32.to_be_bytes());
+        data.extend(b"Objc");
+        descriptor_header(&mut data, "brushPreset", 1);
+        object(&mut data, "szVr", "brVr", 2);
+        long(&mut data, "bVTy", 2);
+        unit_float(&mut data, "jitter", "#Prc", 25.0);
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(root) = read_descriptor(&mut cursor) else {
+            panic!("descriptor should parse");
+        };
+
+        let Some([DescriptorValue::Descriptor(preset)]) = root.get_list("Brsh") else {
+            panic!("expected one preset");
+        };
+        let Some(size) = preset.get_descriptor("szVr") else {
+            panic!("expected size variation");
+        };
+        assert_eq!(size.get_i32("bVTy"), Some(2));
+        assert_eq!(size.get_fraction("jitter"), Some(0.25));
+    }
+
+    #[test]
+    fn test_truncated_descriptor_errors() {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "null", 3);
+        unit_float(&mut data, "Dmtr", "#Pxl", 42.0);
+
+        let mut cursor = Cursor::new(data.as_slice());
+        assert!(read_descriptor(&mut cursor).is_err());
+    }
+}
@@ -26,13 +26,19 @@
 //! ```
 
 mod defaults;
+mod descriptor;
 mod error;
 mod parser;
+mod patt;
+mod preset;
 mod samp;
 mod types;
 
 pub use defaults::AbrDefaults;
+pub use descriptor::{Descriptor, DescriptorValue};
 pub use error::AbrError;
 pub use parser::AbrParser;
 pub use samp::normalize_brush_texture;
-pub use types::{AbrBrush, AbrDynamics, AbrFile, AbrVersion, BrushPreset, GrayscaleImage};
+pub use types::{
+    AbrBrush, AbrDynamics, AbrFile, AbrPattern, AbrVersion, BrushPreset, GrayscaleImage,
+};
@@ -10,9 +10,12 @@ use std::io::{Cursor, Read, Seek, SeekFrom};
 use byteorder::{BigEndian, ReadBytesExt};
 
 use super::defaults::AbrDefaults;
+use super::descriptor::{read_descriptor, Descriptor};
 use super::error::AbrError;
+use super::patt::parse_patterns;
+use super::preset::apply_presets;
 use super::samp::normalize_brush_texture;
-use super::types::{AbrBrush, AbrDynamics, AbrFile, AbrVersion, GrayscaleImage};
+use super::types::{AbrBrush, AbrDynamics, AbrFile, AbrPattern, AbrVersion, GrayscaleImage};
 
 /// ABR file header information
 #[derive(Debug, Clone)]
@@ -44,19 +47,21 @@ impl AbrParser {
             return Ok(AbrFile {
                 version: header.version,
                 brushes: Vec::new(),
+                patterns: Vec::new(),
             });
         }
 
         // Parse based on version
-        let brushes = if header.version.is_new_format() {
+        let (brushes, patterns) = if header.version.is_new_format() {
             Self::parse_v6(&mut cursor, &header)?
         } else {
-            Self::parse_v12(&mut cursor, &header)?
+            (Self::parse_v12(&mut cursor, &header)?, Vec::new())
         };
 
         Ok(AbrFile {
             version: header.version,
             brushes,
+            patterns,
         })
     }
 
@@ -283,12 +288,20 @@ impl AbrParser {
             roundness: AbrDefaults::ROUNDNESS,
             hardness: None,
             dynamics: None,
+            dual_brush: None,
+            texture: None,
+            wet_edges: false,
+            build_up: false,
+            noise: false,
             is_computed: false,
         })
     }
 
-    /// Parse v6+ format brushes
-    fn parse_v6(cursor: &mut Cursor<&[u8]>, header: &AbrHeader) -> Result<Vec<AbrBrush>, AbrError> {
+    /// Parse v6+ format brushes and patterns
+    fn parse_v6(
+        cursor: &mut Cursor<&[u8]>,
+        header: &AbrHeader,
+    ) -> Result<(Vec<AbrBrush>, Vec<AbrPattern>), AbrError> {
         let mut brushes = Vec::new();
 
         // Parse samp section (contains brush tip images)
@@ -312,11 +325,60 @@ impl AbrParser {
                     }
                 }
             }
-        } else {
-            cursor.seek(SeekFrom::Start(origin))?;
         }
 
-        Ok(brushes)
+        // Parse patt section (patterns for brush textures)
+        cursor.seek(SeekFrom::Start(origin))?;
+
+        let mut patterns = Vec::new();
+        match Self::reach_8bim_section(cursor, "patt") {
+            Ok(true) => {
+                let section_size = cursor.read_u32::<BigEndian>()?;
+                let section_end = cursor.position() + section_size as u64;
+                match parse_patterns(cursor, section_end) {
+                    Ok(parsed) => patterns = parsed,
+                    Err(e) => tracing::warn!("Failed to parse patterns: {}", e),
+                }
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to locate patt section: {}", e),
+        }
+
+        // Parse desc section (brush presets: names, dynamics, dual brush, texture)
+        cursor.seek(SeekFrom::Start(origin))?;
+
+        match Self::reach_8bim_section(cursor, "desc") {
+            Ok(true) => match Self::parse_desc_section(cursor) {
+                Ok(root) => {
+                    let brushes = apply_presets(&root, brushes, &patterns);
+                    return Ok((brushes, patterns));
+                }
+                Err(e) => tracing::warn!("Failed to parse brush descriptors: {}", e),
+            },
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to locate desc section: {}", e),
+        }
+
+        Ok((brushes, patterns))
+    }
+
+    /// Parse the desc section into its root descriptor
+    fn parse_desc_section(cursor: &mut Cursor<&[u8]>) -> Result<Descriptor, AbrError> {
+        let section_size = cursor.read_u32::<BigEndian>()?;
+        let section_end = cursor.position() + section_size as u64;
+
+        let version = cursor.read_u32::<BigEndian>()?;
+        if version != 16 {
+            return Err(AbrError::Parse(format!(
+                "Unsupported descriptor version: {}",
+                version
+            )));
+        }
+
+        let root = read_descriptor(cursor)?;
+        cursor.seek(SeekFrom::Start(section_end))?;
+
+        Ok(root)
     }
 
     /// Parse a single v6+ brush from samp section
@@ -329,8 +391,11 @@ impl AbrParser {
         let aligned_size = (brush_size + 3) & !3;
         let next_brush = cursor.position() + aligned_size as u64;
 
-        // Skip key (37 bytes)
-        cursor.seek(SeekFrom::Current(37))?;
+        // Key: pascal string holding the sample UUID (37 bytes)
+        let mut key = [0u8; 37];
+        cursor.read_exact(&mut key)?;
+        let key_len = (key[0] as usize).min(36);
+        let sample_id = String::from_utf8_lossy(&key[1..1 + key_len]).into_owned();
 
         // Skip additional bytes based on subversion
         if header.subversion == 1 {
@@ -374,7 +439,11 @@ impl AbrParser {
 
         Ok(AbrBrush {
             name: format!("Brush_{}", id + 1),
-            uuid: Some(format!("abr-{}", id)),
+            uuid: Some(if sample_id.is_empty() {
+                format!("abr-{}", id)
+            } else {
+                sample_id
+            }),
             tip_image: Some(normalized),
             diameter: width as f32,
             spacing: AbrDefaults::SPACING,
@@ -382,19 +451,28 @@ impl AbrParser {
             roundness: AbrDefaults::ROUNDNESS,
             hardness: None,
             dynamics: Some(AbrDynamics::default()),
+            dual_brush: None,
+            texture: None,
+            wet_edges: false,
+            build_up: false,
+            noise: false,
             is_computed: false,
         })
     }
 
     /// Read raw (uncompressed) image data
-    fn read_raw_image(
+    pub(super) fn read_raw_image(
         cursor: &mut Cursor<&[u8]>,
         width: u32,
         height: u32,
         depth: u16,
     ) -> Result<Vec<u8>, AbrError> {
         let bytes_per_pixel = (depth / 8) as u32;
-        let size = (width * height * bytes_per_pixel) as usize;
+        let size = (width as u64)
+            .checked_mul(height as u64)
+            .and_then(|pixels| pixels.checked_mul(bytes_per_pixel as u64))
+            .filter(|&size| size <= Self::remaining(cursor))
+            .ok_or(AbrError::UnexpectedEof)? as usize;
 
         let mut buffer = vec![0u8; size];
         cursor.read_exact(&mut buffer)?;
@@ -413,8 +491,15 @@ impl AbrParser {
     }
 
     /// Read RLE compressed image data (PackBits algorithm)
-    fn read_rle_image(cursor: &mut Cursor<&[u8]>, height: u32) -> Result<Vec<u8>, AbrError> {
-        // Read scanline sizes
+    pub(super) fn read_rle_image(
+        cursor: &mut Cursor<&[u8]>,
+        height: u32,
+    ) -> Result<Vec<u8>, AbrError> {
+        // Read scanline sizes (two bytes each, so a bogus height can't
+        // reserve more than the data could hold)
+        if height as u64 * 2 > Self::remaining(cursor) {
+            return Err(AbrError::UnexpectedEof);
+        }
         let mut scanline_sizes = Vec::with_capacity(height as usize);
         for _ in 0..height {
             scanline_sizes.push(cursor.read_u16::<BigEndian>()?);
@@ -448,6 +533,11 @@ impl AbrParser {
         Ok(data)
     }
 
+    /// Bytes left after the cursor position
+    pub(super) fn remaining(cursor: &Cursor<&[u8]>) -> u64 {
+        (cursor.get_ref().len() as u64).saturating_sub(cursor.position())
+    }
+
     /// Read UCS-2 (UTF-16 BE) string
     fn read_ucs2_string(cursor: &mut Cursor<&[u8]>) -> Result<String, AbrError> {
         let length = cursor.read_u32::<BigEndian>()? as usize;
@@ -531,6 +621,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_lingybrush_presets() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../abr/lingybrush.abr");
+
+        let Ok(data) = std::fs::read(&path) else {
+            eprintln!("Test file not found: {:?}, skipping test", path);
+            return;
+        };
+        let abr_file = match AbrParser::parse(&data) {
+            Ok(abr_file) => abr_file,
+            Err(e) => panic!("Failed to parse ABR file: {}", e),
+        };
+
+        // Presets from the desc section carry their names and computed tips
+        assert!(abr_file
+            .brushes
+            .iter()
+            .any(|b| b.name == "Hard Round 50 1" && b.is_computed));
+
+        // "Hard Round 50 1" uses a sampled dual tip with multiply
+        let Some(dual) = abr_file.brushes.iter().find_map(|b| b.dual_brush.as_ref()) else {
+            panic!("Should have a dual brush");
+        };
+        assert_eq!(dual.mode, crate::brush::DualBlendMode::Multiply);
+        assert!(dual.tip.is_some());
+    }
+
     #[test]
     fn test_parse_lingybrush_abr() {
         // Test with another ABR file
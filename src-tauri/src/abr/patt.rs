@@ -0,0 +1,374 @@
+//! 8BIMpatt section parser
+//!
+//! Patterns used by brush textures. Each pattern is stored as a header
+//! followed by a Virtual Memory Array List holding one image per channel.
+//! Patterns are converted to 8-bit grayscale.
+//!
+//! Reference: Adobe Photoshop File Formats Specification, "Patterns"
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use super::descriptor::read_unicode_string;
+use super::error::AbrError;
+use super::parser::AbrParser;
+use super::types::{AbrPattern, GrayscaleImage};
+
+/// Image modes used by patterns
+const MODE_GRAYSCALE: u32 = 1;
+const MODE_INDEXED: u32 = 2;
+const MODE_RGB: u32 = 3;
+
+/// Photoshop's channel limit
+const MAX_CHANNELS: u32 = 56;
+
+/// Parse all patterns up to `section_end`
+///
+/// Patterns that fail to parse are skipped with a warning.
+pub(super) fn parse_patterns(
+    cursor: &mut Cursor<&[u8]>,
+    section_end: u64,
+) -> Result<Vec<AbrPattern>, AbrError> {
+    let mut patterns = Vec::new();
+
+    while cursor.position() + 4 <= section_end {
+        let length = cursor.read_u32::<BigEndian>()?;
+        let aligned_length = (length + 3) & !3;
+        let next_pattern = cursor.position() + aligned_length as u64;
+
+        if next_pattern > section_end {
+            break;
+        }
+
+        match parse_pattern(cursor) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(e) => tracing::warn!("Failed to parse pattern #{}: {}", patterns.len(), e),
+        }
+
+        cursor.seek(SeekFrom::Start(next_pattern))?;
+    }
+
+    Ok(patterns)
+}
+
+/// Parse a single pattern (after its length field)
+fn parse_pattern(cursor: &mut Cursor<&[u8]>) -> Result<AbrPattern, AbrError> {
+    let version = cursor.read_u32::<BigEndian>()?;
+    if version != 1 {
+        return Err(AbrError::Parse(format!(
+            "Unsupported pattern version: {}",
+            version
+        )));
+    }
+
+    let mode = cursor.read_u32::<BigEndian>()?;
+    // Point: vertical, horizontal (dimensions are taken from the channel rect)
+    cursor.seek(SeekFrom::Current(4))?;
+
+    let name = read_unicode_string(cursor)?;
+    let id_len = cursor.read_u8()? as usize;
+    let mut id_bytes = vec![0u8; id_len];
+    cursor.read_exact(&mut id_bytes)?;
+    let id = String::from_utf8_lossy(&id_bytes).into_owned();
+
+    let palette = if mode == MODE_INDEXED {
+        let mut palette = [0u8; 256 * 3];
+        cursor.read_exact(&mut palette)?;
+        Some(palette)
+    } else {
+        None
+    };
+
+    let channels = read_virtual_memory_array_list(cursor)?;
+
+    let image = match (mode, channels.as_slice()) {
+        (MODE_RGB, [r, g, b, ..]) => luminance(r, g, b)?,
+        (MODE_INDEXED, [index, ..]) => {
+            let palette = palette.ok_or_else(|| AbrError::Parse("Missing palette".into()))?;
+            indexed_to_gray(index, &palette)
+        }
+        (MODE_GRAYSCALE, [gray, ..]) => gray.clone(),
+        (_, []) => return Err(AbrError::Parse("Pattern has no channel data".into())),
+        _ => {
+            return Err(AbrError::Parse(format!(
+                "Unsupported pattern image mode: {}",
+                mode
+            )))
+        }
+    };
+
+    Ok(AbrPattern { name, id, image })
+}
+
+/// Read the channels of a Virtual Memory Array List
+fn read_virtual_memory_array_list(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<Vec<GrayscaleImage>, AbrError> {
+    let version = cursor.read_u32::<BigEndian>()?;
+    if version != 3 {
+        return Err(AbrError::Parse(format!(
+            "Unsupported virtual memory array list version: {}",
+            version
+        )));
+    }
+
+    let length = cursor.read_u32::<BigEndian>()?;
+    let list_end = cursor.position() + length as u64;
+
+    // Bounding rect of the whole list
+    cursor.seek(SeekFrom::Current(16))?;
+    let channel_count = cursor.read_u32::<BigEndian>()?;
+    if channel_count > MAX_CHANNELS {
+        return Err(AbrError::InvalidFile(format!(
+            "Pattern has {} channels",
+            channel_count
+        )));
+    }
+
+    // Channels are followed by an optional user mask and sheet mask
+    let slots = channel_count
+        .checked_add(2)
+        .ok_or_else(|| AbrError::InvalidFile("Channel count out of range".into()))?;
+    let mut channels = Vec::new();
+    for _ in 0..slots {
+        if cursor.position() + 4 > list_end {
+            break;
+        }
+
+        let written = cursor.read_u32::<BigEndian>()?;
+        if written == 0 {
+            continue;
+        }
+
+        let length = cursor.read_u32::<BigEndian>()?;
+        if length == 0 {
+            continue;
+        }
+        let channel_end = cursor.position() + length as u64;
+
+        if channels.len() < channel_count as usize {
+            channels.push(read_channel(cursor, channel_end)?);
+        }
+
+        cursor.seek(SeekFrom::Start(channel_end))?;
+    }
+
+    Ok(channels)
+}
+
+/// Read a single channel image (after its length field) ending at `channel_end`
+fn read_channel(cursor: &mut Cursor<&[u8]>, channel_end: u64) -> Result<GrayscaleImage, AbrError> {
+    let _depth = cursor.read_u32::<BigEndian>()?;
+
+    let top = cursor.read_i32::<BigEndian>()?;
+    let left = cursor.read_i32::<BigEndian>()?;
+    let bottom = cursor.read_i32::<BigEndian>()?;
+    let right = cursor.read_i32::<BigEndian>()?;
+
+    let depth = cursor.read_u16::<BigEndian>()?;
+    let compression = cursor.read_u8()?;
+
+    let extent = |from: i32, to: i32| {
+        to.checked_sub(from)
+            .filter(|&size| size > 0)
+            .map(|size| size as u32)
+            .ok_or_else(|| {
+                AbrError::InvalidFile(format!(
+                    "Invalid pattern rect ({}, {}, {}, {})",
+                    top, left, bottom, right
+                ))
+            })
+    };
+    let width = extent(left, right)?;
+    let height = extent(top, bottom)?;
+    let pixel_count = (width as u64) * (height as u64);
+
+    // Check the rect against the channel length before allocating anything
+    let needed = if compression == 0 {
+        pixel_count.checked_mul((depth / 8) as u64)
+    } else {
+        // Scanline byte counts
+        Some(height as u64 * 2)
+    };
+    let available = channel_end.saturating_sub(cursor.position());
+    let fits = needed.is_some_and(|needed| needed <= available);
+    if !fits {
+        return Err(AbrError::InvalidFile(format!(
+            "Pattern rect {}x{} does not fit in {} channel bytes",
+            width, height, available
+        )));
+    }
+
+    let data = if compression == 0 {
+        AbrParser::read_raw_image(cursor, width, height, depth)?
+    } else {
+        AbrParser::read_rle_image(cursor, height)?
+    };
+
+    if (data.len() as u64) < pixel_count {
+        return Err(AbrError::UnexpectedEof);
+    }
+
+    Ok(GrayscaleImage::new(width, height, data))
+}
+
+/// Combine RGB channels into grayscale (Rec. 601 luma)
+fn luminance(
+    r: &GrayscaleImage,
+    g: &GrayscaleImage,
+    b: &GrayscaleImage,
+) -> Result<GrayscaleImage, AbrError> {
+    if r.data.len() != g.data.len() || r.data.len() != b.data.len() {
+        return Err(AbrError::Parse("Mismatched pattern channels".into()));
+    }
+
+    let data = r
+        .data
+        .iter()
+        .zip(&g.data)
+        .zip(&b.data)
+        .map(|((&r, &g), &b)| (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) as u8)
+        .collect();
+
+    Ok(GrayscaleImage::new(r.width, r.height, data))
+}
+
+/// Map palette indices to grayscale
+fn indexed_to_gray(index: &GrayscaleImage, palette: &[u8; 256 * 3]) -> GrayscaleImage {
+    let data = index
+        .data
+        .iter()
+        .map(|&i| {
+            let i = i as usize;
+            let (r, g, b) = (palette[i], palette[256 + i], palette[512 + i]);
+            (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) as u8
+        })
+        .collect();
+
+    GrayscaleImage::new(index.width, index.height, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::descriptor::test_util::unicode;
+    use super::*;
+
+    /// Build a grayscale pattern with one raw 8-bit channel
+    fn pattern_bytes(id: &str, width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+        let mut channel = Vec::new();
+        channel.extend(8u32.to_be_bytes());
+        for v in [0i32, 0, height as i32, width as i32] {
+            channel.extend(v.to_be_bytes());
+        }
+        channel.extend(8u16.to_be_bytes());
+        channel.push(0);
+        channel.extend(pixels);
+
+        let mut list = Vec::new();
+        for v in [0i32, 0, height as i32, width as i32] {
+            list.extend(v.to_be_bytes());
+        }
+        list.extend(1u32.to_be_bytes());
+        list.extend(1u32.to_be_bytes());
+        list.extend((channel.len() as u32).to_be_bytes());
+        list.extend(channel);
+        // User mask and sheet mask not written
+        list.extend(0u32.to_be_bytes());
+        list.extend(0u32.to_be_bytes());
+
+        let mut body = Vec::new();
+        body.extend(1u32.to_be_bytes());
+        body.extend(MODE_GRAYSCALE.to_be_bytes());
+        body.extend((height as u16).to_be_bytes());
+        body.extend((width as u16).to_be_bytes());
+        unicode(&mut body, "Dots");
+        body.push(id.len() as u8);
+        body.extend(id.as_bytes());
+        body.extend(3u32.to_be_bytes());
+        body.extend((list.len() as u32).to_be_bytes());
+        body.extend(list);
+
+        let mut out = Vec::new();
+        out.extend((body.len() as u32).to_be_bytes());
+        out.extend(&body);
+        out.resize(4 + ((body.len() + 3) & !3), 0);
+        out
+    }
+
+    #[test]
+    fn test_parse_grayscale_patterns() {
+        let mut data = pattern_bytes("pat-a", 2, 2, &[0, 64, 128, 255]);
+        data.extend(pattern_bytes("pat-b", 3, 1, &[1, 2, 3]));
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(patterns) = parse_patterns(&mut cursor, data.len() as u64) else {
+            panic!("patterns should parse");
+        };
+
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].name, "Dots");
+        assert_eq!(patterns[0].id, "pat-a");
+        assert_eq!(patterns[0].image.data, vec![0, 64, 128, 255]);
+        assert_eq!(patterns[1].id, "pat-b");
+        assert_eq!((patterns[1].image.width, patterns[1].image.height), (3, 1));
+    }
+
+    #[test]
+    fn test_rejects_invalid_rects_and_channel_counts() {
+        let channel = |rect: [i32; 4]| {
+            let mut bytes = 8u32.to_be_bytes().to_vec();
+            for v in rect {
+                bytes.extend(v.to_be_bytes());
+            }
+            bytes.extend(8u16.to_be_bytes());
+            bytes.push(0);
+            bytes
+        };
+        for rect in [
+            [0, i32::MIN, 10, i32::MAX],
+            [i32::MAX, 0, i32::MIN, 10],
+            [5, 0, 5, 10],
+        ] {
+            let data = channel(rect);
+            let result = read_channel(&mut Cursor::new(data.as_slice()), data.len() as u64);
+            assert!(
+                matches!(result, Err(AbrError::InvalidFile(_))),
+                "{:?}",
+                rect
+            );
+        }
+
+        // Oversized rect: rejected before anything is allocated
+        for compression in [0, 1] {
+            let mut data = channel([0, 0, 70_000, 70_000]);
+            if let Some(last) = data.last_mut() {
+                *last = compression;
+            }
+            data.extend([0u8; 64]);
+            let result = read_channel(&mut Cursor::new(data.as_slice()), data.len() as u64);
+            assert!(matches!(result, Err(AbrError::InvalidFile(_))));
+        }
+        let mut cursor = Cursor::new(&[0u8; 64][..]);
+        let result = AbrParser::read_raw_image(&mut cursor, 70_000, 70_000, 16);
+        assert!(matches!(result, Err(AbrError::UnexpectedEof)));
+        let result = AbrParser::read_rle_image(&mut cursor, u32::MAX);
+        assert!(matches!(result, Err(AbrError::UnexpectedEof)));
+
+        let mut list = 3u32.to_be_bytes().to_vec();
+        list.extend(24u32.to_be_bytes());
+        list.extend([0u8; 16]);
+        list.extend(u32::MAX.to_be_bytes());
+        let result = read_virtual_memory_array_list(&mut Cursor::new(list.as_slice()));
+        assert!(matches!(result, Err(AbrError::InvalidFile(_))));
+    }
+
+    #[test]
+    fn test_luminance() {
+        let channel = |v| GrayscaleImage::new(1, 1, vec![v]);
+        let Ok(gray) = luminance(&channel(255), &channel(255), &channel(255)) else {
+            panic!("channels match");
+        };
+        assert!(gray.data[0] >= 254);
+    }
+}
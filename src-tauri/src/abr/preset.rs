@@ -0,0 +1,397 @@
+//! Brush preset mapping
+//!
+//! Maps `brushPreset` descriptors from the `desc` section onto brushes.
+//! Sampled tips are matched to `samp` entries by their UUID (`sampledData`).
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::defaults::AbrDefaults;
+use super::descriptor::{Descriptor, DescriptorValue};
+use super::types::{AbrBrush, AbrDynamics, AbrPattern};
+use crate::brush::{ControlSource, DualBlendMode, DualBrush, TextureSettings, TransferChannel};
+
+/// Build the brush list from the root `desc` descriptor and parsed samples
+///
+/// Presets come first in file order. Samples not referenced by any preset
+/// are appended so no tip is lost.
+pub(super) fn apply_presets(
+    root: &Descriptor,
+    samples: Vec<AbrBrush>,
+    patterns: &[AbrPattern],
+) -> Vec<AbrBrush> {
+    let Some(list) = root.get_list("Brsh") else {
+        return samples;
+    };
+
+    let mut brushes = Vec::with_capacity(list.len());
+    let mut referenced = HashSet::new();
+
+    for (index, value) in list.iter().enumerate() {
+        let DescriptorValue::Descriptor(preset) = value else {
+            continue;
+        };
+
+        referenced.extend(sample_ids(preset));
+
+        match brush_from_preset(preset, &samples, patterns, index) {
+            Some(brush) => brushes.push(brush),
+            None => tracing::warn!("Skipped brush preset #{}: missing tip", index),
+        }
+    }
+
+    brushes.extend(
+        samples
+            .into_iter()
+            .filter(|sample| !matches!(&sample.uuid, Some(uuid) if referenced.contains(uuid))),
+    );
+
+    brushes
+}
+
+/// UUIDs of the samples used by a preset (primary and dual tips)
+fn sample_ids(preset: &Descriptor) -> Vec<String> {
+    let primary = preset.get_descriptor("Brsh");
+    let dual = preset
+        .get_descriptor("dualBrush")
+        .and_then(|d| d.get_descriptor("Brsh"));
+
+    [primary, dual]
+        .into_iter()
+        .flatten()
+        .filter_map(|tip| tip.get_text("sampledData"))
+        .map(str::to_owned)
+        .collect()
+}
+
+fn find_sample<'a>(samples: &'a [AbrBrush], uuid: &str) -> Option<&'a AbrBrush> {
+    samples.iter().find(|s| s.uuid.as_deref() == Some(uuid))
+}
+
+/// Build a brush from a single preset descriptor
+fn brush_from_preset(
+    preset: &Descriptor,
+    samples: &[AbrBrush],
+    patterns: &[AbrPattern],
+    index: usize,
+) -> Option<AbrBrush> {
+    let tip = preset.get_descriptor("Brsh")?;
+
+    let tip_image = match tip.get_text("sampledData") {
+        Some(uuid) => Some(find_sample(samples, uuid)?.tip_image.clone()?),
+        None => None,
+    };
+
+    let name = preset
+        .get_text("Nm  ")
+        .or_else(|| tip.get_text("Nm  "))
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("Brush_{}", index + 1));
+
+    Some(AbrBrush {
+        name,
+        uuid: Some(format!("abr-{}", index)),
+        is_computed: tip_image.is_none(),
+        tip_image,
+        diameter: tip.get_f32("Dmtr").unwrap_or(AbrDefaults::DIAMETER),
+        spacing: tip.get_fraction("Spcn").unwrap_or(AbrDefaults::SPACING),
+        angle: tip.get_f32("Angl").unwrap_or(AbrDefaults::ANGLE),
+        roundness: tip.get_fraction("Rndn").unwrap_or(AbrDefaults::ROUNDNESS),
+        hardness: tip.get_fraction("Hrdn"),
+        dynamics: Some(dynamics_from_preset(preset)),
+        dual_brush: dual_brush_from_preset(preset, samples),
+        texture: texture_from_preset(preset, patterns),
+        wet_edges: preset.get_bool("Wtdg").unwrap_or(false),
+        build_up: preset.get_bool("Rpt ").unwrap_or(false),
+        noise: preset.get_bool("Nose").unwrap_or(false),
+    })
+}
+
+/// Read a `brVr` variation: (control code, jitter, minimum)
+fn variation(desc: &Descriptor, key: &str) -> (u32, f32, f32) {
+    let Some(var) = desc.get_descriptor(key) else {
+        return (0, 0.0, 0.0);
+    };
+
+    (
+        var.get_i32("bVTy").unwrap_or(0).max(0) as u32,
+        var.get_fraction("jitter").unwrap_or(0.0),
+        var.get_fraction("Mnm ").unwrap_or(0.0),
+    )
+}
+
+fn dynamics_from_preset(preset: &Descriptor) -> AbrDynamics {
+    let (size_control, size_jitter, _) = variation(preset, "szVr");
+    let (angle_control, angle_jitter, _) = variation(preset, "angleDynamics");
+    let (_, scatter, _) = variation(preset, "scatterDynamics");
+    let (opacity_control, opacity_jitter, opacity_minimum) = variation(preset, "opVr");
+    let (flow_control, flow_jitter, flow_minimum) = variation(preset, "prVr");
+
+    AbrDynamics {
+        use_tip_dynamics: preset.get_bool("useTipDynamics").unwrap_or(false),
+        size_control,
+        size_jitter,
+        size_minimum: preset
+            .get_fraction("minimumDiameter")
+            .unwrap_or(AbrDefaults::SIZE_MINIMUM),
+        angle_control,
+        angle_jitter,
+        use_scatter: preset.get_bool("useScatter").unwrap_or(false),
+        scatter,
+        scatter_count: preset
+            .get_f64("Cnt ")
+            .map_or(AbrDefaults::SCATTER_COUNT, |c| c.max(1.0) as u32),
+        use_paint_dynamics: preset.get_bool("usePaintDynamics").unwrap_or(false),
+        opacity_control,
+        opacity_jitter,
+        opacity_minimum,
+        flow_control,
+        flow_jitter,
+        flow_minimum,
+    }
+}
+
+/// Build the dual brush, if the preset enables one
+fn dual_brush_from_preset(preset: &Descriptor, samples: &[AbrBrush]) -> Option<DualBrush> {
+    let dual = preset.get_descriptor("dualBrush")?;
+    if !dual.get_bool("useDualBrush").unwrap_or(false) {
+        return None;
+    }
+
+    let tip = dual.get_descriptor("Brsh");
+    let tip_image = tip
+        .and_then(|t| t.get_text("sampledData"))
+        .and_then(|uuid| find_sample(samples, uuid))
+        .and_then(|sample| sample.tip_image.clone())
+        .map(Arc::new);
+
+    let scatter = if dual.get_bool("useScatter").unwrap_or(false) {
+        variation(dual, "scatterDynamics").1
+    } else {
+        0.0
+    };
+
+    Some(DualBrush {
+        mode: dual
+            .get_enum("BlnM")
+            .and_then(DualBlendMode::from_abr)
+            .unwrap_or_default(),
+        size: tip
+            .and_then(|t| t.get_f32("Dmtr"))
+            .unwrap_or(AbrDefaults::DIAMETER),
+        spacing: dual
+            .get_fraction("Spcn")
+            .or_else(|| tip.and_then(|t| t.get_fraction("Spcn")))
+            .unwrap_or(AbrDefaults::SPACING),
+        scatter,
+        both_axes: dual.get_bool("bothAxes").unwrap_or(false),
+        count: dual.get_f64("Cnt ").map_or(1, |c| c.max(1.0) as u32),
+        hardness: tip
+            .and_then(|t| t.get_fraction("Hrdn"))
+            .unwrap_or(AbrDefaults::HARDNESS),
+        tip: tip_image,
+    })
+}
+
+/// Build the texture settings, if the preset enables a texture
+fn texture_from_preset(preset: &Descriptor, patterns: &[AbrPattern]) -> Option<TextureSettings> {
+    if !preset.get_bool("useTexture").unwrap_or(false) {
+        return None;
+    }
+
+    let pattern_id = preset
+        .get_descriptor("Txtr")
+        .and_then(|p| p.get_text("Idnt"))
+        .map(str::to_owned);
+    let pattern = pattern_id
+        .as_deref()
+        .and_then(|id| patterns.iter().find(|p| p.id == id))
+        .map(|p| Arc::new(p.image.clone()));
+
+    let (depth_control, depth_jitter, _) = variation(preset, "textureDepthDynamics");
+
+    Some(TextureSettings {
+        pattern_id,
+        scale: preset.get_fraction("textureScale").unwrap_or(1.0),
+        // Photoshop ranges: brightness -150..150, contrast -50..100
+        brightness: preset
+            .get_f32("textureBrightness")
+            .map_or(0.0, |b| b / 150.0),
+        contrast: preset.get_f32("textureContrast").map_or(0.0, |c| c / 100.0),
+        depth: preset.get_fraction("textureDepth").unwrap_or(1.0),
+        depth_dynamics: TransferChannel {
+            control: ControlSource::from_abr(depth_control, AbrDefaults::FADE_STEPS),
+            jitter: depth_jitter,
+            minimum: preset.get_fraction("minimumDepth").unwrap_or(0.0),
+        },
+        invert: preset.get_bool("InvT").unwrap_or(false),
+        each_tip: preset.get_bool("TxtC").unwrap_or(false),
+        pattern,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::descriptor::{read_descriptor, test_util::*};
+    use super::super::types::{BrushPreset, GrayscaleImage};
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample(uuid: &str) -> AbrBrush {
+        AbrBrush {
+            name: "Brush_1".into(),
+            uuid: Some(uuid.into()),
+            tip_image: Some(GrayscaleImage::new(2, 2, vec![255; 4])),
+            diameter: 2.0,
+            spacing: AbrDefaults::SPACING,
+            angle: AbrDefaults::ANGLE,
+            roundness: AbrDefaults::ROUNDNESS,
+            hardness: None,
+            dynamics: None,
+            dual_brush: None,
+            texture: None,
+            wet_edges: false,
+            build_up: false,
+            noise: false,
+            is_computed: false,
+        }
+    }
+
+    /// Root descriptor with one computed preset using a sampled dual tip
+    fn root_with_dual_brush() -> Descriptor {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "null", 1);
+        key(&mut data, "Brsh");
+        data.extend(b"VlLs");
+        data.extend(1u32.to_be_bytes());
+        data.extend(b"Objc");
+        descriptor_header(&mut data, "brushPreset", 5);
+        text(&mut data, "Nm  ", "Dual Round");
+        boolean(&mut data, "Wtdg", true);
+        boolean(&mut data, "Rpt ", true);
+        object(&mut data, "Brsh", "computedBrush", 2);
+        unit_float(&mut data, "Dmtr", "#Pxl", 50.0);
+        unit_float(&mut data, "Hrdn", "#Prc", 80.0);
+        object(&mut data, "dualBrush", "dualBrush", 6);
+        boolean(&mut data, "useDualBrush", true);
+        object(&mut data, "Brsh", "sampledBrush", 2);
+        unit_float(&mut data, "Dmtr", "#Pxl", 20.0);
+        text(&mut data, "sampledData", "tip-b");
+        enumerated(&mut data, "BlnM", "BlnM", "CBrn");
+        boolean(&mut data, "useScatter", true);
+        unit_float(&mut data, "Spcn", "#Prc", 50.0);
+        object(&mut data, "scatterDynamics", "brVr", 1);
+        unit_float(&mut data, "jitter", "#Prc", 40.0);
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(root) = read_descriptor(&mut cursor) else {
+            panic!("descriptor should parse");
+        };
+        root
+    }
+
+    #[test]
+    fn test_apply_presets_with_dual_brush() {
+        let brushes = apply_presets(
+            &root_with_dual_brush(),
+            vec![sample("tip-a"), sample("tip-b")],
+            &[],
+        );
+
+        // Preset first, then the unreferenced sample
+        assert_eq!(brushes.len(), 2);
+        let preset = &brushes[0];
+        assert_eq!(preset.name, "Dual Round");
+        assert!(preset.is_computed);
+        assert_eq!(preset.diameter, 50.0);
+        assert_eq!(preset.hardness, Some(0.8));
+        assert!(preset.wet_edges && preset.build_up && !preset.noise);
+        assert_eq!(brushes[1].uuid.as_deref(), Some("tip-a"));
+
+        let Some(dual) = &preset.dual_brush else {
+            panic!("expected dual brush");
+        };
+        assert_eq!(dual.mode, DualBlendMode::ColorBurn);
+        assert_eq!(dual.size, 20.0);
+        assert_eq!(dual.spacing, 0.5);
+        assert!((dual.scatter - 0.4).abs() < 1e-6);
+        assert!(dual.tip.is_some());
+    }
+
+    #[test]
+    fn test_missing_preset_list_keeps_samples() {
+        let root = Descriptor::default();
+        let brushes = apply_presets(&root, vec![sample("tip-a")], &[]);
+        assert_eq!(brushes.len(), 1);
+        assert!(brushes[0].dual_brush.is_none());
+    }
+
+    #[test]
+    fn test_texture_from_preset() {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "brushPreset", 6);
+        boolean(&mut data, "useTexture", true);
+        object(&mut data, "Txtr", "Ptrn", 1);
+        text(&mut data, "Idnt", "pat-a");
+        unit_float(&mut data, "textureScale", "#Prc", 200.0);
+        unit_float(&mut data, "textureDepth", "#Prc", 60.0);
+        boolean(&mut data, "InvT", true);
+        boolean(&mut data, "TxtC", true);
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(preset) = read_descriptor(&mut cursor) else {
+            panic!("descriptor should parse");
+        };
+        let patterns = [AbrPattern {
+            name: "Dots".into(),
+            id: "pat-a".into(),
+            image: GrayscaleImage::new(1, 1, vec![128]),
+        }];
+
+        let Some(texture) = texture_from_preset(&preset, &patterns) else {
+            panic!("expected texture");
+        };
+        assert_eq!(texture.pattern_id.as_deref(), Some("pat-a"));
+        assert!(texture.pattern.is_some());
+        assert_eq!(texture.scale, 2.0);
+        assert!((texture.depth - 0.6).abs() < 1e-6);
+        assert!(texture.invert && texture.each_tip);
+    }
+
+    #[test]
+    fn test_direction_angle_control_from_preset() {
+        let mut data = Vec::new();
+        descriptor_header(&mut data, "brushPreset", 3);
+        boolean(&mut data, "useTipDynamics", true);
+        object(&mut data, "angleDynamics", "brVr", 2);
+        long(&mut data, "bVTy", 6);
+        unit_float(&mut data, "jitter", "#Prc", 10.0);
+        object(&mut data, "szVr", "brVr", 1);
+        long(&mut data, "bVTy", 2);
+
+        let mut cursor = Cursor::new(data.as_slice());
+        let Ok(preset) = read_descriptor(&mut cursor) else {
+            panic!("descriptor should parse");
+        };
+
+        let Some(shape) = dynamics_from_preset(&preset).shape() else {
+            panic!("expected shape dynamics");
+        };
+        assert_eq!(shape.angle.control, ControlSource::Direction);
+        assert!((shape.angle.jitter - 0.1).abs() < 1e-6);
+        assert_eq!(shape.size.control, ControlSource::Pressure);
+
+        // The frontend applies pen pressure to size once, via size_pressure
+        let brush = AbrBrush {
+            dynamics: Some(dynamics_from_preset(&preset)),
+            ..sample("uuid-1")
+        };
+        let converted = BrushPreset::from(brush);
+        assert!(converted.size_pressure);
+        let Some(shape) = converted.shape else {
+            panic!("expected shape dynamics");
+        };
+        assert_eq!(shape.size.control, ControlSource::Off);
+        assert_eq!(shape.angle.control, ControlSource::Direction);
+    }
+}
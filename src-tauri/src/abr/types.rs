@@ -4,11 +4,19 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::defaults::AbrDefaults;
+use crate::brush::{
+    AngleChannel, ControlSource, DualBrush, ShapeDynamics, TextureSettings, TransferChannel,
+    TransferDynamics,
+};
+
 /// Parsed ABR file containing brushes
 #[derive(Debug, Clone)]
 pub struct AbrFile {
     pub version: AbrVersion,
     pub brushes: Vec<AbrBrush>,
+    /// Patterns from the patt section (used by brush textures)
+    pub patterns: Vec<AbrPattern>,
 }
 
 /// ABR file format version
@@ -54,12 +62,22 @@ pub struct AbrBrush {
     pub hardness: Option<f32>,
     /// Dynamic parameters
     pub dynamics: Option<AbrDynamics>,
+    /// Secondary tip (dual brush), if enabled
+    pub dual_brush: Option<DualBrush>,
+    /// Pattern texture, if enabled
+    pub texture: Option<TextureSettings>,
+    /// Wet edges
+    pub wet_edges: bool,
+    /// Airbrush-style build-up
+    pub build_up: bool,
+    /// Edge noise
+    pub noise: bool,
     /// Whether this is a computed (parametric) brush vs sampled
     pub is_computed: bool,
 }
 
 /// Grayscale image data for brush tips
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GrayscaleImage {
     pub width: u32,
     pub height: u32,
@@ -88,6 +106,17 @@ impl GrayscaleImage {
     }
 }
 
+/// A pattern from the ABR patt section
+#[derive(Debug, Clone)]
+pub struct AbrPattern {
+    /// Pattern name
+    pub name: String,
+    /// Unique identifier (referenced by brush textures)
+    pub id: String,
+    /// Pattern image (grayscale)
+    pub image: GrayscaleImage,
+}
+
 /// Brush dynamics (pressure/tilt sensitivity)
 #[derive(Debug, Clone, Default)]
 pub struct AbrDynamics {
@@ -115,6 +144,56 @@ pub struct AbrDynamics {
     pub opacity_control: u32,
     /// Opacity jitter
     pub opacity_jitter: f32,
+    /// Minimum opacity (0-1) when the control value is 0
+    pub opacity_minimum: f32,
+    /// Flow control
+    pub flow_control: u32,
+    /// Flow jitter (0-1)
+    pub flow_jitter: f32,
+    /// Minimum flow (0-1) when the control value is 0
+    pub flow_minimum: f32,
+}
+
+impl AbrDynamics {
+    /// Transfer dynamics (flow/opacity), if paint dynamics are enabled
+    pub fn transfer(&self) -> Option<TransferDynamics> {
+        if !self.use_paint_dynamics {
+            return None;
+        }
+
+        Some(TransferDynamics {
+            flow: TransferChannel {
+                control: ControlSource::from_abr(self.flow_control, AbrDefaults::FADE_STEPS),
+                jitter: self.flow_jitter,
+                minimum: self.flow_minimum,
+            },
+            opacity: TransferChannel {
+                control: ControlSource::from_abr(self.opacity_control, AbrDefaults::FADE_STEPS),
+                jitter: self.opacity_jitter,
+                minimum: self.opacity_minimum,
+            },
+        })
+    }
+
+    /// Shape dynamics (size/angle), if tip dynamics are enabled
+    pub fn shape(&self) -> Option<ShapeDynamics> {
+        if !self.use_tip_dynamics {
+            return None;
+        }
+
+        Some(ShapeDynamics {
+            size: TransferChannel {
+                control: ControlSource::from_abr(self.size_control, AbrDefaults::FADE_STEPS),
+                jitter: self.size_jitter,
+                minimum: self.size_minimum,
+            },
+            ink_thinning: 0.0,
+            angle: AngleChannel {
+                control: ControlSource::from_abr(self.angle_control, AbrDefaults::FADE_STEPS),
+                jitter: self.angle_jitter,
+            },
+        })
+    }
 }
 
 /// Brush preset for frontend consumption
@@ -146,11 +225,38 @@ pub struct BrushPreset {
     pub size_pressure: bool,
     /// Pressure affects opacity
     pub opacity_pressure: bool,
+    /// Flow/opacity transfer dynamics
+    pub transfer: Option<TransferDynamics>,
+    /// Size/angle shape dynamics
+    pub shape: Option<ShapeDynamics>,
+    /// Dual brush settings
+    pub dual_brush: Option<DualBrush>,
+    /// Dual brush tip texture (base64 encoded PNG, if sampled)
+    pub dual_texture_data: Option<String>,
+    /// Dual brush tip texture dimensions
+    pub dual_texture_width: Option<u32>,
+    pub dual_texture_height: Option<u32>,
+    /// Texture (pattern) settings
+    pub texture: Option<TextureSettings>,
+    /// Texture pattern (base64 encoded PNG, if available)
+    pub pattern_data: Option<String>,
+    /// Texture pattern dimensions
+    pub pattern_width: Option<u32>,
+    pub pattern_height: Option<u32>,
+    /// Wet edges
+    pub wet_edges: bool,
+    /// Airbrush-style build-up
+    pub build_up: bool,
+    /// Edge noise
+    pub noise: bool,
 }
 
 impl From<AbrBrush> for BrushPreset {
     fn from(brush: AbrBrush) -> Self {
         let dynamics = brush.dynamics.as_ref();
+        let dual_tip = brush.dual_brush.as_ref().and_then(|d| d.tip.as_deref());
+        let pattern = brush.texture.as_ref().and_then(|t| t.pattern.as_deref());
+        let size_pressure = dynamics.is_some_and(|d| d.size_control == 2);
 
         BrushPreset {
             id: brush
@@ -159,15 +265,34 @@ impl From<AbrBrush> for BrushPreset {
             name: brush.name,
             diameter: brush.diameter,
             spacing: brush.spacing * 100.0,
-            hardness: brush.hardness.unwrap_or(100.0),
+            hardness: brush.hardness.map_or(100.0, |h| h * 100.0),
             angle: brush.angle,
             roundness: brush.roundness * 100.0,
             has_texture: brush.tip_image.is_some(),
             texture_data: brush.tip_image.as_ref().map(encode_texture),
             texture_width: brush.tip_image.as_ref().map(|img| img.width),
             texture_height: brush.tip_image.as_ref().map(|img| img.height),
-            size_pressure: dynamics.map(|d| d.size_control == 2).unwrap_or(false),
+            size_pressure,
             opacity_pressure: dynamics.map(|d| d.opacity_control == 2).unwrap_or(false),
+            transfer: dynamics.and_then(AbrDynamics::transfer),
+            // Pressure-driven size is already covered by size_pressure
+            shape: dynamics.and_then(AbrDynamics::shape).map(|mut shape| {
+                if size_pressure {
+                    shape.size.control = ControlSource::Off;
+                }
+                shape
+            }),
+            dual_texture_data: dual_tip.map(encode_texture),
+            dual_texture_width: dual_tip.map(|img| img.width),
+            dual_texture_height: dual_tip.map(|img| img.height),
+            dual_brush: brush.dual_brush,
+            pattern_data: pattern.map(encode_texture),
+            pattern_width: pattern.map(|img| img.width),
+            pattern_height: pattern.map(|img| img.height),
+            texture: brush.texture,
+            wet_edges: brush.wet_edges,
+            build_up: brush.build_up,
+            noise: brush.noise,
         }
     }
 }
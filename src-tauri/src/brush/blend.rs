@@ -1,8 +1,23 @@
 //! Blend mode algorithms for brush rendering
 //!
 //! All functions work with premultiplied alpha format for correct compositing.
+//!
+//! Blending follows the W3C Compositing and Blending Level 1 spec: the blend
+//! function B(Cs, Cb) works on unpremultiplied colors and the result is
+//! composited source-over:
+//!
+//! - co = cs × (1 - αb) + αs × αb × B(Cs, Cb) + cb × (1 - αs)
+//! - αo = αs + αb × (1 - αs)
+//!
+//! Separable modes apply B per channel; Hue, Saturation, Color and
+//! Luminosity work on the whole RGB triple.
+//!
+//! Behind, Erase and Clear only change coverage (Porter-Duff destination-over
+//! and destination-out); the source alpha still carries flow and opacity.
 
 use super::stroke_buffer::Pixel;
+use super::BlendMode;
+use serde::{Deserialize, Serialize};
 
 /// Standard alpha blending (Porter-Duff "over" operator) for premultiplied alpha
 ///
@@ -23,45 +38,7 @@ pub fn blend_normal_premul(src: Pixel, dst: Pixel) -> Pixel {
 /// Formula: S × D (darkens image)
 #[inline]
 pub fn blend_multiply_premul(src: Pixel, dst: Pixel) -> Pixel {
-    if src.a < 0.001 {
-        return dst;
-    }
-    if dst.a < 0.001 {
-        return src;
-    }
-
-    // Unpremultiply for blend calculation
-    let src_r = src.r / src.a;
-    let src_g = src.g / src.a;
-    let src_b = src.b / src.a;
-
-    let dst_r = dst.r / dst.a;
-    let dst_g = dst.g / dst.a;
-    let dst_b = dst.b / dst.a;
-
-    // Multiply blend
-    let blend_r = src_r * dst_r;
-    let blend_g = src_g * dst_g;
-    let blend_b = src_b * dst_b;
-
-    // Composite with alpha
-    let out_a = src.a + dst.a * (1.0 - src.a);
-    if out_a < 0.001 {
-        return Pixel::transparent();
-    }
-
-    // Lerp between dst color and blended color based on src alpha
-    let result_r = dst_r + (blend_r - dst_r) * src.a;
-    let result_g = dst_g + (blend_g - dst_g) * src.a;
-    let result_b = dst_b + (blend_b - dst_b) * src.a;
-
-    // Premultiply result
-    Pixel {
-        r: result_r * out_a,
-        g: result_g * out_a,
-        b: result_b * out_a,
-        a: out_a,
-    }
+    blend_separable(src, dst, |s, d| s * d)
 }
 
 /// Screen blend mode
@@ -69,40 +46,7 @@ pub fn blend_multiply_premul(src: Pixel, dst: Pixel) -> Pixel {
 /// Formula: 1 - (1-S) × (1-D) (lightens image)
 #[inline]
 pub fn blend_screen_premul(src: Pixel, dst: Pixel) -> Pixel {
-    if src.a < 0.001 {
-        return dst;
-    }
-    if dst.a < 0.001 {
-        return src;
-    }
-
-    let src_r = src.r / src.a;
-    let src_g = src.g / src.a;
-    let src_b = src.b / src.a;
-
-    let dst_r = dst.r / dst.a;
-    let dst_g = dst.g / dst.a;
-    let dst_b = dst.b / dst.a;
-
-    let blend_r = 1.0 - (1.0 - src_r) * (1.0 - dst_r);
-    let blend_g = 1.0 - (1.0 - src_g) * (1.0 - dst_g);
-    let blend_b = 1.0 - (1.0 - src_b) * (1.0 - dst_b);
-
-    let out_a = src.a + dst.a * (1.0 - src.a);
-    if out_a < 0.001 {
-        return Pixel::transparent();
-    }
-
-    let result_r = dst_r + (blend_r - dst_r) * src.a;
-    let result_g = dst_g + (blend_g - dst_g) * src.a;
-    let result_b = dst_b + (blend_b - dst_b) * src.a;
-
-    Pixel {
-        r: result_r * out_a,
-        g: result_g * out_a,
-        b: result_b * out_a,
-        a: out_a,
-    }
+    blend_separable(src, dst, screen)
 }
 
 /// Overlay blend mode
@@ -110,6 +54,28 @@ pub fn blend_screen_premul(src: Pixel, dst: Pixel) -> Pixel {
 /// Formula: if D < 0.5: 2×S×D else: 1 - 2×(1-S)×(1-D)
 #[inline]
 pub fn blend_overlay_premul(src: Pixel, dst: Pixel) -> Pixel {
+    blend_separable(src, dst, |s, d| hard_light(d, s))
+}
+
+/// Darken blend mode
+///
+/// Formula: min(S, D)
+#[inline]
+pub fn blend_darken_premul(src: Pixel, dst: Pixel) -> Pixel {
+    blend_separable(src, dst, f32::min)
+}
+
+/// Lighten blend mode
+///
+/// Formula: max(S, D)
+#[inline]
+pub fn blend_lighten_premul(src: Pixel, dst: Pixel) -> Pixel {
+    blend_separable(src, dst, f32::max)
+}
+
+/// Composite with a blend function on unpremultiplied RGB (W3C general formula)
+#[inline]
+fn blend_with(src: Pixel, dst: Pixel, blend: impl Fn([f32; 3], [f32; 3]) -> [f32; 3]) -> Pixel {
     if src.a < 0.001 {
         return dst;
     }
@@ -117,127 +83,170 @@ pub fn blend_overlay_premul(src: Pixel, dst: Pixel) -> Pixel {
         return src;
     }
 
-    let src_r = src.r / src.a;
-    let src_g = src.g / src.a;
-    let src_b = src.b / src.a;
+    let cs = [src.r / src.a, src.g / src.a, src.b / src.a];
+    let cb = [dst.r / dst.a, dst.g / dst.a, dst.b / dst.a];
+    let mixed = blend(cs, cb);
 
-    let dst_r = dst.r / dst.a;
-    let dst_g = dst.g / dst.a;
-    let dst_b = dst.b / dst.a;
+    let both = src.a * dst.a;
+    let inv_src_a = 1.0 - src.a;
+    let inv_dst_a = 1.0 - dst.a;
 
-    let overlay = |s: f32, d: f32| -> f32 {
-        if d < 0.5 {
-            2.0 * s * d
-        } else {
-            1.0 - 2.0 * (1.0 - s) * (1.0 - d)
-        }
-    };
+    Pixel {
+        r: src.r * inv_dst_a + both * mixed[0] + dst.r * inv_src_a,
+        g: src.g * inv_dst_a + both * mixed[1] + dst.g * inv_src_a,
+        b: src.b * inv_dst_a + both * mixed[2] + dst.b * inv_src_a,
+        a: src.a + dst.a * inv_src_a,
+    }
+}
 
-    let blend_r = overlay(src_r, dst_r);
-    let blend_g = overlay(src_g, dst_g);
-    let blend_b = overlay(src_b, dst_b);
+/// Composite with a per-channel blend function `f(src, dst)`
+#[inline]
+fn blend_separable(src: Pixel, dst: Pixel, f: impl Fn(f32, f32) -> f32) -> Pixel {
+    blend_with(src, dst, |s, d| {
+        [f(s[0], d[0]), f(s[1], d[1]), f(s[2], d[2])]
+    })
+}
 
-    let out_a = src.a + dst.a * (1.0 - src.a);
-    if out_a < 0.001 {
-        return Pixel::transparent();
-    }
+// ----------------------------------------------------------------------------
+// Separable blend functions: f(source, backdrop), both unpremultiplied 0-1
+// ----------------------------------------------------------------------------
 
-    let result_r = dst_r + (blend_r - dst_r) * src.a;
-    let result_g = dst_g + (blend_g - dst_g) * src.a;
-    let result_b = dst_b + (blend_b - dst_b) * src.a;
+#[inline]
+fn screen(s: f32, d: f32) -> f32 {
+    s + d - s * d
+}
 
-    Pixel {
-        r: result_r * out_a,
-        g: result_g * out_a,
-        b: result_b * out_a,
-        a: out_a,
+#[inline]
+fn hard_light(s: f32, d: f32) -> f32 {
+    if s <= 0.5 {
+        d * 2.0 * s
+    } else {
+        screen(2.0 * s - 1.0, d)
     }
 }
 
-/// Darken blend mode
-///
-/// Formula: min(S, D)
 #[inline]
-pub fn blend_darken_premul(src: Pixel, dst: Pixel) -> Pixel {
-    if src.a < 0.001 {
-        return dst;
-    }
-    if dst.a < 0.001 {
-        return src;
+fn soft_light(s: f32, d: f32) -> f32 {
+    if s <= 0.5 {
+        d - (1.0 - 2.0 * s) * d * (1.0 - d)
+    } else {
+        let dd = if d <= 0.25 {
+            ((16.0 * d - 12.0) * d + 4.0) * d
+        } else {
+            d.sqrt()
+        };
+        d + (2.0 * s - 1.0) * (dd - d)
     }
+}
 
-    let src_r = src.r / src.a;
-    let src_g = src.g / src.a;
-    let src_b = src.b / src.a;
-
-    let dst_r = dst.r / dst.a;
-    let dst_g = dst.g / dst.a;
-    let dst_b = dst.b / dst.a;
-
-    let blend_r = src_r.min(dst_r);
-    let blend_g = src_g.min(dst_g);
-    let blend_b = src_b.min(dst_b);
-
-    let out_a = src.a + dst.a * (1.0 - src.a);
-    if out_a < 0.001 {
-        return Pixel::transparent();
+#[inline]
+fn color_dodge(s: f32, d: f32) -> f32 {
+    if d <= 0.0 {
+        0.0
+    } else if s >= 1.0 {
+        1.0
+    } else {
+        (d / (1.0 - s)).min(1.0)
     }
+}
 
-    let result_r = dst_r + (blend_r - dst_r) * src.a;
-    let result_g = dst_g + (blend_g - dst_g) * src.a;
-    let result_b = dst_b + (blend_b - dst_b) * src.a;
+#[inline]
+fn color_burn(s: f32, d: f32) -> f32 {
+    if d >= 1.0 {
+        1.0
+    } else if s <= 0.0 {
+        0.0
+    } else {
+        1.0 - ((1.0 - d) / s).min(1.0)
+    }
+}
 
-    Pixel {
-        r: result_r * out_a,
-        g: result_g * out_a,
-        b: result_b * out_a,
-        a: out_a,
+#[inline]
+fn vivid_light(s: f32, d: f32) -> f32 {
+    if s <= 0.5 {
+        color_burn(2.0 * s, d)
+    } else {
+        color_dodge(2.0 * s - 1.0, d)
     }
 }
 
-/// Lighten blend mode
-///
-/// Formula: max(S, D)
 #[inline]
-pub fn blend_lighten_premul(src: Pixel, dst: Pixel) -> Pixel {
-    if src.a < 0.001 {
-        return dst;
+fn pin_light(s: f32, d: f32) -> f32 {
+    if s <= 0.5 {
+        d.min(2.0 * s)
+    } else {
+        d.max(2.0 * s - 1.0)
     }
-    if dst.a < 0.001 {
-        return src;
+}
+
+#[inline]
+fn divide(s: f32, d: f32) -> f32 {
+    if s <= 0.0 {
+        if d > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    } else {
+        (d / s).min(1.0)
     }
+}
 
-    let src_r = src.r / src.a;
-    let src_g = src.g / src.a;
-    let src_b = src.b / src.a;
+// ----------------------------------------------------------------------------
+// Non-separable helpers (W3C Lum / ClipColor / SetLum / Sat / SetSat)
+// ----------------------------------------------------------------------------
 
-    let dst_r = dst.r / dst.a;
-    let dst_g = dst.g / dst.a;
-    let dst_b = dst.b / dst.a;
+#[inline]
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
 
-    let blend_r = src_r.max(dst_r);
-    let blend_g = src_g.max(dst_g);
-    let blend_b = src_b.max(dst_b);
+fn clip_color(c: [f32; 3]) -> [f32; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
 
-    let out_a = src.a + dst.a * (1.0 - src.a);
-    if out_a < 0.001 {
-        return Pixel::transparent();
+    let mut out = c;
+    if n < 0.0 && l - n > f32::EPSILON {
+        out = out.map(|v| l + (v - l) * l / (l - n));
+    }
+    if x > 1.0 && x - l > f32::EPSILON {
+        out = out.map(|v| l + (v - l) * (1.0 - l) / (x - l));
     }
+    out
+}
 
-    let result_r = dst_r + (blend_r - dst_r) * src.a;
-    let result_g = dst_g + (blend_g - dst_g) * src.a;
-    let result_b = dst_b + (blend_b - dst_b) * src.a;
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let delta = l - lum(c);
+    clip_color(c.map(|v| v + delta))
+}
 
-    Pixel {
-        r: result_r * out_a,
-        g: result_g * out_a,
-        b: result_b * out_a,
-        a: out_a,
-    }
+#[inline]
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let max = c[0].max(c[1]).max(c[2]);
+    let min = c[0].min(c[1]).min(c[2]);
+    let range = max - min;
+
+    c.map(|v| {
+        if range <= 0.0 {
+            0.0
+        } else if v >= max {
+            s
+        } else if v <= min {
+            0.0
+        } else {
+            (v - min) * s / range
+        }
+    })
 }
 
 /// Blend mode enum matching the existing BlendMode
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum BlendFunc {
     #[default]
     Normal,
@@ -246,9 +255,70 @@ pub enum BlendFunc {
     Overlay,
     Darken,
     Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    LinearBurn,
+    LinearDodge,
+    VividLight,
+    LinearLight,
+    PinLight,
+    HardMix,
+    Subtract,
+    Divide,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    /// Paint only where the layer is transparent (destination-over)
+    Behind,
+    /// Remove layer alpha by the stroke alpha (destination-out)
+    Erase,
+    /// Photoshop's Clear brush mode; within the stroke coverage this is
+    /// destination-out, so it composites like Erase
+    Clear,
 }
 
 impl BlendFunc {
+    /// Every blend function, in declaration order
+    pub const ALL: [BlendFunc; 27] = [
+        BlendFunc::Normal,
+        BlendFunc::Multiply,
+        BlendFunc::Screen,
+        BlendFunc::Overlay,
+        BlendFunc::Darken,
+        BlendFunc::Lighten,
+        BlendFunc::ColorDodge,
+        BlendFunc::ColorBurn,
+        BlendFunc::HardLight,
+        BlendFunc::SoftLight,
+        BlendFunc::Difference,
+        BlendFunc::Exclusion,
+        BlendFunc::LinearBurn,
+        BlendFunc::LinearDodge,
+        BlendFunc::VividLight,
+        BlendFunc::LinearLight,
+        BlendFunc::PinLight,
+        BlendFunc::HardMix,
+        BlendFunc::Subtract,
+        BlendFunc::Divide,
+        BlendFunc::Hue,
+        BlendFunc::Saturation,
+        BlendFunc::Color,
+        BlendFunc::Luminosity,
+        BlendFunc::Behind,
+        BlendFunc::Erase,
+        BlendFunc::Clear,
+    ];
+
+    /// Whether this mode removes paint instead of adding it
+    pub fn is_erase(&self) -> bool {
+        matches!(self, BlendFunc::Erase | BlendFunc::Clear)
+    }
+
     /// Apply blend function to source and destination pixels
     pub fn apply(&self, src: Pixel, dst: Pixel) -> Pixel {
         match self {
@@ -258,6 +328,59 @@ impl BlendFunc {
             BlendFunc::Overlay => blend_overlay_premul(src, dst),
             BlendFunc::Darken => blend_darken_premul(src, dst),
             BlendFunc::Lighten => blend_lighten_premul(src, dst),
+            BlendFunc::ColorDodge => blend_separable(src, dst, color_dodge),
+            BlendFunc::ColorBurn => blend_separable(src, dst, color_burn),
+            BlendFunc::HardLight => blend_separable(src, dst, hard_light),
+            BlendFunc::SoftLight => blend_separable(src, dst, soft_light),
+            BlendFunc::Difference => blend_separable(src, dst, |s, d| (s - d).abs()),
+            BlendFunc::Exclusion => blend_separable(src, dst, |s, d| s + d - 2.0 * s * d),
+            BlendFunc::LinearBurn => blend_separable(src, dst, |s, d| (s + d - 1.0).max(0.0)),
+            BlendFunc::LinearDodge => blend_separable(src, dst, |s, d| (s + d).min(1.0)),
+            BlendFunc::VividLight => blend_separable(src, dst, vivid_light),
+            BlendFunc::LinearLight => {
+                blend_separable(src, dst, |s, d| (d + 2.0 * s - 1.0).clamp(0.0, 1.0))
+            }
+            BlendFunc::PinLight => blend_separable(src, dst, pin_light),
+            BlendFunc::HardMix => {
+                blend_separable(src, dst, |s, d| if s + d >= 1.0 { 1.0 } else { 0.0 })
+            }
+            BlendFunc::Subtract => blend_separable(src, dst, |s, d| (d - s).max(0.0)),
+            BlendFunc::Divide => blend_separable(src, dst, divide),
+            BlendFunc::Hue => blend_with(src, dst, |s, d| set_lum(set_sat(s, sat(d)), lum(d))),
+            BlendFunc::Saturation => {
+                blend_with(src, dst, |s, d| set_lum(set_sat(d, sat(s)), lum(d)))
+            }
+            BlendFunc::Color => blend_with(src, dst, |s, d| set_lum(s, lum(d))),
+            BlendFunc::Luminosity => blend_with(src, dst, |s, d| set_lum(d, lum(s))),
+            BlendFunc::Behind => blend_normal_premul(dst, src),
+            BlendFunc::Erase | BlendFunc::Clear => {
+                let keep = 1.0 - src.a.clamp(0.0, 1.0);
+                Pixel {
+                    r: dst.r * keep,
+                    g: dst.g * keep,
+                    b: dst.b * keep,
+                    a: dst.a * keep,
+                }
+            }
+        }
+    }
+}
+
+impl From<BlendMode> for BlendFunc {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Normal => BlendFunc::Normal,
+            BlendMode::Multiply => BlendFunc::Multiply,
+            BlendMode::Screen => BlendFunc::Screen,
+            BlendMode::Overlay => BlendFunc::Overlay,
+            BlendMode::Darken => BlendFunc::Darken,
+            BlendMode::Lighten => BlendFunc::Lighten,
+            BlendMode::ColorDodge => BlendFunc::ColorDodge,
+            BlendMode::ColorBurn => BlendFunc::ColorBurn,
+            BlendMode::HardLight => BlendFunc::HardLight,
+            BlendMode::SoftLight => BlendFunc::SoftLight,
+            BlendMode::Difference => BlendFunc::Difference,
+            BlendMode::Exclusion => BlendFunc::Exclusion,
         }
     }
 }
@@ -339,4 +462,165 @@ mod tests {
         let result = BlendFunc::Normal.apply(src, dst);
         assert!(approx_eq(result.r, 1.0));
     }
+
+    fn gray(v: f32) -> Pixel {
+        Pixel::new(v, v, v, 1.0)
+    }
+
+    /// Separable reference values from the W3C formulas (opaque src and dst)
+    #[test]
+    fn test_separable_modes_match_w3c() {
+        let cases = [
+            (BlendFunc::ColorDodge, 0.5, 0.25, 0.5),
+            (BlendFunc::ColorDodge, 1.0, 0.3, 1.0),
+            (BlendFunc::ColorDodge, 0.4, 0.0, 0.0),
+            (BlendFunc::ColorBurn, 0.5, 0.75, 0.5),
+            (BlendFunc::ColorBurn, 0.0, 0.5, 0.0),
+            (BlendFunc::ColorBurn, 0.3, 1.0, 1.0),
+            (BlendFunc::HardLight, 0.25, 0.6, 0.3),
+            (BlendFunc::HardLight, 0.75, 0.6, 0.8),
+            (BlendFunc::Overlay, 0.6, 0.25, 0.3),
+            (BlendFunc::SoftLight, 0.25, 0.5, 0.375),
+            (BlendFunc::SoftLight, 0.75, 0.16, 0.279168),
+            (BlendFunc::SoftLight, 0.75, 0.64, 0.72),
+            (BlendFunc::Difference, 0.2, 0.7, 0.5),
+            (BlendFunc::Exclusion, 0.2, 0.7, 0.62),
+            (BlendFunc::LinearBurn, 0.4, 0.3, 0.0),
+            (BlendFunc::LinearBurn, 0.8, 0.7, 0.5),
+            (BlendFunc::LinearDodge, 0.4, 0.3, 0.7),
+            (BlendFunc::LinearDodge, 0.8, 0.7, 1.0),
+            (BlendFunc::VividLight, 0.25, 0.6, 0.2),
+            (BlendFunc::VividLight, 0.75, 0.3, 0.6),
+            (BlendFunc::LinearLight, 0.75, 0.3, 0.8),
+            (BlendFunc::LinearLight, 0.1, 0.3, 0.0),
+            (BlendFunc::PinLight, 0.25, 0.7, 0.5),
+            (BlendFunc::PinLight, 0.75, 0.3, 0.5),
+            (BlendFunc::HardMix, 0.6, 0.5, 1.0),
+            (BlendFunc::HardMix, 0.4, 0.5, 0.0),
+            (BlendFunc::Subtract, 0.3, 0.5, 0.2),
+            (BlendFunc::Divide, 0.5, 0.25, 0.5),
+            (BlendFunc::Divide, 0.0, 0.2, 1.0),
+        ];
+
+        for (mode, s, d, expected) in cases {
+            let result = mode.apply(gray(s), gray(d));
+            assert!(
+                (result.r - expected).abs() < 1e-4,
+                "{:?}({}, {}) = {}, expected {}",
+                mode,
+                s,
+                d,
+                result.r,
+                expected
+            );
+            assert!(approx_eq(result.a, 1.0));
+        }
+    }
+
+    #[test]
+    fn test_non_separable_modes_match_w3c() {
+        let red = Pixel::new(1.0, 0.0, 0.0, 1.0);
+        let blue = Pixel::new(0.0, 0.0, 1.0, 1.0);
+        let orange = Pixel::new(0.8, 0.4, 0.2, 1.0);
+        let check = |result: Pixel, expected: [f32; 3]| {
+            assert!(approx_eq(result.r, expected[0]), "{:?}", result);
+            assert!(approx_eq(result.g, expected[1]), "{:?}", result);
+            assert!(approx_eq(result.b, expected[2]), "{:?}", result);
+        };
+
+        // SetLum(red, 0.5) clips into gamut: (1, 2/7, 2/7)
+        check(
+            BlendFunc::Luminosity.apply(gray(0.5), red),
+            [1.0, 0.2857, 0.2857],
+        );
+        check(
+            BlendFunc::Color.apply(red, gray(0.5)),
+            [1.0, 0.2857, 0.2857],
+        );
+        // Gray backdrop has no saturation to give the source hue
+        check(BlendFunc::Hue.apply(red, gray(0.5)), [0.5, 0.5, 0.5]);
+        check(BlendFunc::Hue.apply(blue, orange), [0.436, 0.436, 1.0]);
+        // Gray source removes saturation, keeping backdrop luminosity
+        check(
+            BlendFunc::Saturation.apply(gray(0.5), orange),
+            [0.498, 0.498, 0.498],
+        );
+    }
+
+    #[test]
+    fn test_general_formula_with_translucent_backdrop() {
+        // cs = 0.6, αs = 0.5; cb = 0.4, αb = 0.5
+        let src = Pixel::new(0.3, 0.3, 0.3, 0.5);
+        let dst = Pixel::new(0.2, 0.2, 0.2, 0.5);
+
+        // co = 0.3 × 0.5 + 0.25 × (0.6 × 0.4) + 0.2 × 0.5 = 0.31
+        let result = BlendFunc::Multiply.apply(src, dst);
+        assert!((result.r - 0.31).abs() < 1e-5);
+        assert!((result.a - 0.75).abs() < 1e-5);
+
+        // Normal through the blend path matches Porter-Duff over
+        let over = blend_normal_premul(src, dst);
+        let general = blend_with(src, dst, |s, _| s);
+        assert!((over.r - general.r).abs() < 1e-5);
+        assert!((over.a - general.a).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_all_modes_respect_alpha() {
+        let src = Pixel::new(0.3, 0.1, 0.45, 0.6);
+        let dst = Pixel::new(0.2, 0.5, 0.05, 0.7);
+
+        let painting = BlendFunc::ALL
+            .into_iter()
+            .filter(|m| !m.is_erase() && *m != BlendFunc::Behind);
+        for mode in painting {
+            // Transparent source leaves the backdrop untouched
+            let result = mode.apply(Pixel::transparent(), dst);
+            assert!(approx_eq(result.g, dst.g), "{:?}", mode);
+
+            // Transparent backdrop shows the source as-is
+            let result = mode.apply(src, Pixel::transparent());
+            assert!(approx_eq(result.b, src.b), "{:?}", mode);
+
+            let result = mode.apply(src, dst);
+            assert!(approx_eq(result.a, 0.88), "{:?}", mode);
+            for c in [result.r, result.g, result.b] {
+                assert!((0.0..=result.a + 1e-5).contains(&c), "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_behind_erase_and_clear() {
+        let src = Pixel::new(0.5, 0.0, 0.0, 0.5);
+        let half = Pixel::new(0.0, 0.5, 0.0, 0.5);
+        let opaque = Pixel::new(0.0, 1.0, 0.0, 1.0);
+
+        // Behind: opaque layer hides the paint, transparent areas receive it
+        let result = BlendFunc::Behind.apply(src, opaque);
+        assert!(approx_eq(result.r, 0.0) && approx_eq(result.g, 1.0));
+        let result = BlendFunc::Behind.apply(src, Pixel::transparent());
+        assert!(approx_eq(result.r, 0.5) && approx_eq(result.a, 0.5));
+        // destination-over: αo = αb + αs × (1 - αb)
+        let result = BlendFunc::Behind.apply(src, half);
+        assert!(approx_eq(result.a, 0.75));
+        assert!(approx_eq(result.g, 0.5) && approx_eq(result.r, 0.25));
+
+        // Erase/Clear: destination-out, source color is irrelevant
+        for mode in [BlendFunc::Erase, BlendFunc::Clear] {
+            let result = mode.apply(src, opaque);
+            assert!(approx_eq(result.a, 0.5) && approx_eq(result.g, 0.5));
+            let result = mode.apply(Pixel::new(0.0, 0.0, 0.0, 1.0), opaque);
+            assert!(approx_eq(result.a, 0.0));
+            let result = mode.apply(Pixel::transparent(), opaque);
+            assert!(approx_eq(result.a, 1.0));
+        }
+    }
+
+    #[test]
+    fn test_blend_func_from_mode() {
+        assert_eq!(BlendFunc::from(BlendMode::SoftLight), BlendFunc::SoftLight);
+        assert_eq!(BlendFunc::from(BlendMode::Exclusion), BlendFunc::Exclusion);
+        assert_eq!(BlendFunc::from(BlendMode::default()), BlendFunc::Normal);
+    }
 }
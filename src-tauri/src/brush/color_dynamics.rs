@@ -0,0 +1,210 @@
+//! Color dynamics - per-dab color variation
+//!
+//! Mirrors Photoshop's Color Dynamics panel:
+//! - Foreground/background mix driven by a control source plus jitter
+//! - Hue, saturation and brightness jitter
+//! - Purity (global saturation shift)
+
+use serde::{Deserialize, Serialize};
+
+use super::dynamics::{ControlSource, DynamicsInput, JitterRng};
+
+/// When color dynamics pick a new color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorDynamicsMode {
+    /// New color for every dab ("Apply Per Tip")
+    #[default]
+    PerDab,
+    /// One color per stroke, chosen at the first dab
+    PerStroke,
+}
+
+/// Color dynamics settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorDynamics {
+    /// Per-dab or per-stroke application
+    pub mode: ColorDynamicsMode,
+    /// Control source for the foreground/background mix
+    /// (full control value = foreground)
+    pub fg_bg_control: ControlSource,
+    /// Random foreground/background mix amount (0-1)
+    pub fg_bg_jitter: f32,
+    /// Hue jitter as a fraction of the hue circle (0-1)
+    pub hue_jitter: f32,
+    /// Saturation jitter (0-1)
+    pub saturation_jitter: f32,
+    /// Brightness jitter (0-1)
+    pub brightness_jitter: f32,
+    /// Purity (-1 = fully desaturated, 0 = unchanged, 1 = fully saturated)
+    pub purity: f32,
+}
+
+impl Default for ColorDynamics {
+    fn default() -> Self {
+        Self {
+            mode: ColorDynamicsMode::PerDab,
+            fg_bg_control: ControlSource::Off,
+            fg_bg_jitter: 0.0,
+            hue_jitter: 0.0,
+            saturation_jitter: 0.0,
+            brightness_jitter: 0.0,
+            purity: 0.0,
+        }
+    }
+}
+
+impl ColorDynamics {
+    /// Compute a dab color from the foreground and background colors (RGB, 0-1)
+    pub fn apply(
+        &self,
+        foreground: [f32; 3],
+        background: [f32; 3],
+        input: &DynamicsInput,
+        rng: &mut JitterRng,
+    ) -> [f32; 3] {
+        // Foreground/background mix: lower control value moves toward background
+        let control = self.fg_bg_control.value(input);
+        let jitter = self.fg_bg_jitter * rng.next_f32();
+        let mix = ((1.0 - control) + jitter).clamp(0.0, 1.0);
+        let rgb = [
+            foreground[0] + (background[0] - foreground[0]) * mix,
+            foreground[1] + (background[1] - foreground[1]) * mix,
+            foreground[2] + (background[2] - foreground[2]) * mix,
+        ];
+
+        let [mut h, mut s, mut v] = rgb_to_hsv(rgb);
+
+        h = (h + self.hue_jitter * rng.next_signed()).rem_euclid(1.0);
+        s = (s + self.saturation_jitter * rng.next_signed()).clamp(0.0, 1.0);
+        v = (v + self.brightness_jitter * rng.next_signed()).clamp(0.0, 1.0);
+
+        let purity = self.purity.clamp(-1.0, 1.0);
+        s = if purity >= 0.0 {
+            s + (1.0 - s) * purity
+        } else {
+            s * (1.0 + purity)
+        };
+
+        hsv_to_rgb([h, s, v])
+    }
+}
+
+/// Convert RGB (0-1) to HSV (all components 0-1)
+pub fn rgb_to_hsv(rgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let h = if delta < 1e-6 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+
+    let s = if max > 1e-6 { delta / max } else { 0.0 };
+
+    [h, s, max]
+}
+
+/// Convert HSV (all components 0-1) to RGB (0-1)
+pub fn hsv_to_rgb(hsv: [f32; 3]) -> [f32; 3] {
+    let [h, s, v] = hsv;
+    let h6 = h.rem_euclid(1.0) * 6.0;
+    let c = v * s;
+    let x = c * (1.0 - (h6.rem_euclid(2.0) - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = match h6 as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    [r + m, g + m, b + m]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 0.01)
+    }
+
+    #[test]
+    fn test_hsv_roundtrip() {
+        for rgb in [
+            [1.0, 0.0, 0.0],
+            [0.2, 0.6, 0.4],
+            [0.9, 0.9, 0.1],
+            [0.3, 0.1, 0.8],
+            [0.5, 0.5, 0.5],
+        ] {
+            assert!(approx_eq(hsv_to_rgb(rgb_to_hsv(rgb)), rgb));
+        }
+    }
+
+    #[test]
+    fn test_no_dynamics_keeps_foreground() {
+        let dynamics = ColorDynamics::default();
+        let mut rng = JitterRng::new(1);
+        let input = DynamicsInput::new(1.0, 0.0, 0.0, 0);
+
+        let color = dynamics.apply([0.2, 0.4, 0.6], [1.0, 1.0, 1.0], &input, &mut rng);
+        assert!(approx_eq(color, [0.2, 0.4, 0.6]));
+    }
+
+    #[test]
+    fn test_pressure_mixes_toward_background() {
+        let dynamics = ColorDynamics {
+            fg_bg_control: ControlSource::Pressure,
+            ..Default::default()
+        };
+        let mut rng = JitterRng::new(1);
+        let fg = [0.0, 0.0, 0.0];
+        let bg = [1.0, 1.0, 1.0];
+
+        let full = dynamics.apply(fg, bg, &DynamicsInput::new(1.0, 0.0, 0.0, 0), &mut rng);
+        let light = dynamics.apply(fg, bg, &DynamicsInput::new(0.0, 0.0, 0.0, 0), &mut rng);
+
+        assert!(approx_eq(full, fg));
+        assert!(approx_eq(light, bg));
+    }
+
+    #[test]
+    fn test_purity_desaturates() {
+        let dynamics = ColorDynamics {
+            purity: -1.0,
+            ..Default::default()
+        };
+        let mut rng = JitterRng::new(1);
+        let input = DynamicsInput::default();
+
+        let color = dynamics.apply([1.0, 0.0, 0.0], [1.0, 1.0, 1.0], &input, &mut rng);
+        assert!(approx_eq(color, [1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_hue_jitter_varies_color() {
+        let dynamics = ColorDynamics {
+            hue_jitter: 0.5,
+            ..Default::default()
+        };
+        let mut rng = JitterRng::new(7);
+        let input = DynamicsInput::default();
+
+        let first = dynamics.apply([1.0, 0.0, 0.0], [1.0, 1.0, 1.0], &input, &mut rng);
+        let second = dynamics.apply([1.0, 0.0, 0.0], [1.0, 1.0, 1.0], &input, &mut rng);
+        assert!(!approx_eq(first, second));
+    }
+}
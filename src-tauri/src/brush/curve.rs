@@ -0,0 +1,508 @@
+//! Pressure curves - shared by brush dynamics and tablet calibration
+//!
+//! `PressureCurve` is the single curve type used for brush size, opacity and
+//! flow as well as the tablet-level curve applied to raw pen pressure.
+//!
+//! - Presets: Linear, Soft, Hard and S-curve
+//! - Custom: monotone cubic spline through control points (Fritsch-Carlson),
+//!   so the curve never overshoots between points. The spline is baked into
+//!   a lookup table on construction; `apply` is a single table lerp.
+//! - `SharedPressureCurve`: a curve baked into an atomic lookup table that
+//!   can be read from the input thread while the UI replaces it, without locks
+//!
+//! The tablet-level curve used to be a separate type whose Soft preset was
+//! `sqrt(p)`, and only the PointerEvent backend applied it. It now uses the
+//! brush presets (Soft is the quadratic ease-out the canvas also uses) and
+//! the emitter applies it to every backend. Names serialized by either old
+//! type (`"soft"`, `"Soft"`, `"SCurve"`, `"s-curve"`, ...) still parse.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of lookup table intervals (table holds `LUT_SIZE + 1` samples)
+const LUT_SIZE: usize = 256;
+
+/// Pressure curve types
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PressureCurve {
+    /// Linear mapping (1:1)
+    #[default]
+    #[serde(alias = "Linear")]
+    Linear,
+    /// Soft curve (more sensitive at low pressure)
+    #[serde(alias = "Soft")]
+    Soft,
+    /// Hard curve (less sensitive at low pressure)
+    #[serde(alias = "Hard")]
+    Hard,
+    /// S-curve (soft at extremes, steeper in the middle)
+    #[serde(alias = "scurve", alias = "s-curve", alias = "SCurve")]
+    SCurve,
+    /// Custom curve through control points
+    #[serde(alias = "Custom")]
+    Custom(SplineCurve),
+}
+
+impl PressureCurve {
+    /// Look up a preset by name ("linear", "soft", "hard", "scurve")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "linear" => Some(PressureCurve::Linear),
+            "soft" => Some(PressureCurve::Soft),
+            "hard" => Some(PressureCurve::Hard),
+            "scurve" | "s-curve" => Some(PressureCurve::SCurve),
+            _ => None,
+        }
+    }
+
+    /// Apply the pressure curve to a raw pressure value
+    pub fn apply(&self, pressure: f32) -> f32 {
+        let p = pressure.clamp(0.0, 1.0);
+
+        match self {
+            PressureCurve::Linear => p,
+            PressureCurve::Soft => {
+                // Ease-out: more sensitive at low pressure
+                1.0 - (1.0 - p).powi(2)
+            }
+            PressureCurve::Hard => {
+                // Ease-in: less sensitive at low pressure
+                p.powi(2)
+            }
+            PressureCurve::SCurve => {
+                // Smoothstep
+                p * p * (3.0 - 2.0 * p)
+            }
+            PressureCurve::Custom(curve) => curve.apply(p),
+        }
+    }
+}
+
+/// Lock-free shared pressure curve
+///
+/// Clones share the same table. `store` rebakes the table in place; a
+/// concurrent `apply` may briefly mix old and new samples, never tear a value.
+#[derive(Debug, Clone)]
+pub struct SharedPressureCurve {
+    lut: Arc<[AtomicU32]>,
+}
+
+impl SharedPressureCurve {
+    /// Create a shared table baked from a curve
+    pub fn new(curve: &PressureCurve) -> Self {
+        let shared = Self {
+            lut: (0..=LUT_SIZE).map(|_| AtomicU32::new(0)).collect(),
+        };
+        shared.store(curve);
+        shared
+    }
+
+    /// Replace the curve seen by all clones
+    pub fn store(&self, curve: &PressureCurve) {
+        for (i, slot) in self.lut.iter().enumerate() {
+            let value = curve.apply(i as f32 / LUT_SIZE as f32);
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Map a pressure value (0-1) through the current curve
+    #[inline]
+    pub fn apply(&self, pressure: f32) -> f32 {
+        let t = pressure.clamp(0.0, 1.0) * LUT_SIZE as f32;
+        let i = (t as usize).min(LUT_SIZE - 1);
+        let frac = t - i as f32;
+        let a = f32::from_bits(self.lut[i].load(Ordering::Relaxed));
+        let b = f32::from_bits(self.lut[i + 1].load(Ordering::Relaxed));
+        a + (b - a) * frac
+    }
+}
+
+impl Default for SharedPressureCurve {
+    fn default() -> Self {
+        Self::new(&PressureCurve::Linear)
+    }
+}
+
+/// A curve control point (both coordinates 0-1)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl CurvePoint {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Monotone cubic spline curve with a precomputed lookup table
+///
+/// Serializes as its list of control points.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<CurvePoint>", into = "Vec<CurvePoint>")]
+pub struct SplineCurve {
+    points: Vec<CurvePoint>,
+    tangents: Vec<f32>,
+    lut: Vec<f32>,
+}
+
+impl SplineCurve {
+    /// Build a curve from control points
+    ///
+    /// Points are clamped to 0-1 and sorted by x. At least two points with
+    /// distinct x are required.
+    pub fn new(points: Vec<CurvePoint>) -> Result<Self, String> {
+        let mut points: Vec<CurvePoint> = points
+            .into_iter()
+            .map(|p| {
+                if p.x.is_finite() && p.y.is_finite() {
+                    Ok(CurvePoint::new(p.x.clamp(0.0, 1.0), p.y.clamp(0.0, 1.0)))
+                } else {
+                    Err("Curve point is not finite".to_string())
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        if points.len() < 2 {
+            return Err("Curve needs at least 2 points".into());
+        }
+
+        points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        if points.windows(2).any(|w| w[1].x - w[0].x <= f32::EPSILON) {
+            return Err("Curve points must have distinct x".into());
+        }
+
+        Ok(Self::from_sorted(points))
+    }
+
+    /// Build a curve from validated, sorted points
+    fn from_sorted(points: Vec<CurvePoint>) -> Self {
+        let tangents = monotone_tangents(&points);
+        let mut curve = Self {
+            points,
+            tangents,
+            lut: Vec::with_capacity(LUT_SIZE + 1),
+        };
+        curve.lut = (0..=LUT_SIZE)
+            .map(|i| curve.evaluate(i as f32 / LUT_SIZE as f32))
+            .collect();
+        curve
+    }
+
+    /// Identity curve (0,0) - (1,1)
+    pub fn linear() -> Self {
+        Self::from_sorted(vec![CurvePoint::new(0.0, 0.0), CurvePoint::new(1.0, 1.0)])
+    }
+
+    /// Control points, sorted by x
+    pub fn points(&self) -> &[CurvePoint] {
+        &self.points
+    }
+
+    /// Map an input (0-1) through the lookup table
+    #[inline]
+    pub fn apply(&self, input: f32) -> f32 {
+        let t = input.clamp(0.0, 1.0) * LUT_SIZE as f32;
+        let i = (t as usize).min(LUT_SIZE - 1);
+        let frac = t - i as f32;
+        self.lut[i] + (self.lut[i + 1] - self.lut[i]) * frac
+    }
+
+    /// Evaluate the spline exactly (slow path, used to build the table)
+    pub fn evaluate(&self, input: f32) -> f32 {
+        let x = input.clamp(0.0, 1.0);
+        let points = &self.points;
+        let last = points.len() - 1;
+
+        // Flat outside the control point range
+        if x <= points[0].x {
+            return points[0].y;
+        }
+        if x >= points[last].x {
+            return points[last].y;
+        }
+
+        let k = points
+            .partition_point(|p| p.x <= x)
+            .saturating_sub(1)
+            .min(last - 1);
+        let (p0, p1) = (points[k], points[k + 1]);
+        let h = p1.x - p0.x;
+        let t = (x - p0.x) / h;
+
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+        let h10 = t3 - 2.0 * t2 + t;
+        let h01 = -2.0 * t3 + 3.0 * t2;
+        let h11 = t3 - t2;
+
+        let y =
+            h00 * p0.y + h10 * h * self.tangents[k] + h01 * p1.y + h11 * h * self.tangents[k + 1];
+        y.clamp(0.0, 1.0)
+    }
+}
+
+impl Default for SplineCurve {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+impl PartialEq for SplineCurve {
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+    }
+}
+
+impl TryFrom<Vec<CurvePoint>> for SplineCurve {
+    type Error = String;
+
+    fn try_from(points: Vec<CurvePoint>) -> Result<Self, Self::Error> {
+        Self::new(points)
+    }
+}
+
+impl From<SplineCurve> for Vec<CurvePoint> {
+    fn from(curve: SplineCurve) -> Self {
+        curve.points
+    }
+}
+
+/// Fritsch-Carlson tangents for a monotone cubic Hermite spline
+fn monotone_tangents(points: &[CurvePoint]) -> Vec<f32> {
+    let n = points.len();
+    let secants: Vec<f32> = points
+        .windows(2)
+        .map(|w| (w[1].y - w[0].y) / (w[1].x - w[0].x))
+        .collect();
+
+    let mut tangents = vec![0.0; n];
+    tangents[0] = secants[0];
+    tangents[n - 1] = secants[n - 2];
+    for k in 1..n - 1 {
+        let (d0, d1) = (secants[k - 1], secants[k]);
+        tangents[k] = if d0 * d1 <= 0.0 { 0.0 } else { (d0 + d1) * 0.5 };
+    }
+
+    // Limit tangents so each segment stays monotone
+    for k in 0..n - 1 {
+        let d = secants[k];
+        if d == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+
+        let a = tangents[k] / d;
+        let b = tangents[k + 1] / d;
+        let s = a * a + b * b;
+        if s > 9.0 {
+            let tau = 3.0 / s.sqrt();
+            tangents[k] = tau * a * d;
+            tangents[k + 1] = tau * b * d;
+        }
+    }
+
+    tangents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(points: &[(f32, f32)]) -> SplineCurve {
+        let points = points.iter().map(|&(x, y)| CurvePoint::new(x, y)).collect();
+        match SplineCurve::new(points) {
+            Ok(curve) => curve,
+            Err(e) => panic!("invalid curve: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_pressure_curve_linear() {
+        let curve = PressureCurve::Linear;
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert_eq!(curve.apply(0.5), 0.5);
+        assert_eq!(curve.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_pressure_curve_soft() {
+        let curve = PressureCurve::Soft;
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert_eq!(curve.apply(1.0), 1.0);
+        // Soft curve should give higher output for low and mid pressure
+        assert!(curve.apply(0.25) > 0.25);
+        assert!(curve.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_pressure_curve_hard() {
+        let curve = PressureCurve::Hard;
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert_eq!(curve.apply(1.0), 1.0);
+        // Hard curve should give lower output for mid pressure
+        assert!(curve.apply(0.5) < 0.5);
+    }
+
+    #[test]
+    fn test_pressure_curve_scurve() {
+        let curve = PressureCurve::SCurve;
+        assert_eq!(curve.apply(0.5), 0.5);
+        assert!(curve.apply(0.2) < 0.2);
+        assert!(curve.apply(0.8) > 0.8);
+    }
+
+    #[test]
+    fn test_pressure_curve_custom() {
+        let curve = PressureCurve::Custom(curve(&[(0.0, 0.0), (0.5, 0.2), (1.0, 1.0)]));
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert!((curve.apply(0.5) - 0.2).abs() < 1e-3);
+        assert_eq!(curve.apply(1.5), 1.0);
+    }
+
+    #[test]
+    fn test_pressure_clamping() {
+        let curve = PressureCurve::Linear;
+        assert_eq!(curve.apply(-0.5), 0.0);
+        assert_eq!(curve.apply(1.5), 1.0);
+    }
+
+    #[test]
+    fn test_pressure_curve_names_and_serde() {
+        assert_eq!(
+            PressureCurve::from_name("S-Curve"),
+            Some(PressureCurve::SCurve)
+        );
+        assert_eq!(PressureCurve::from_name("soft"), Some(PressureCurve::Soft));
+        assert_eq!(PressureCurve::from_name("bogus"), None);
+
+        let parsed: Result<PressureCurve, _> = serde_json::from_str(r#""sCurve""#);
+        assert!(matches!(parsed, Ok(PressureCurve::SCurve)));
+        let parsed: Result<PressureCurve, _> = serde_json::from_str(r#""scurve""#);
+        assert!(matches!(parsed, Ok(PressureCurve::SCurve)));
+
+        // Names written by the old brush (kebab-case) and tablet enums
+        for (json, expected) in [
+            (r#""s-curve""#, PressureCurve::SCurve),
+            (r#""SCurve""#, PressureCurve::SCurve),
+            (r#""Soft""#, PressureCurve::Soft),
+            (r#""Hard""#, PressureCurve::Hard),
+            (r#""Linear""#, PressureCurve::Linear),
+        ] {
+            let parsed: Result<PressureCurve, _> = serde_json::from_str(json);
+            assert!(matches!(parsed, Ok(ref c) if *c == expected), "{}", json);
+        }
+        let old_custom = r#"{"Custom":[{"x":0.0,"y":0.2},{"x":1.0,"y":0.8}]}"#;
+        let parsed: Result<PressureCurve, _> = serde_json::from_str(old_custom);
+        assert!(matches!(parsed, Ok(PressureCurve::Custom(_))));
+
+        let custom = PressureCurve::Custom(curve(&[(0.0, 0.2), (1.0, 0.8)]));
+        let Ok(json) = serde_json::to_string(&custom) else {
+            panic!("curve should serialize");
+        };
+        assert_eq!(json, r#"{"custom":[{"x":0.0,"y":0.2},{"x":1.0,"y":0.8}]}"#);
+        let parsed: Result<PressureCurve, _> = serde_json::from_str(&json);
+        assert!(matches!(parsed, Ok(c) if c == custom));
+    }
+
+    #[test]
+    fn test_shared_curve_matches_and_updates_clones() {
+        let shared = SharedPressureCurve::default();
+        let reader = shared.clone();
+        assert!((reader.apply(0.3) - 0.3).abs() < 1e-5);
+
+        shared.store(&PressureCurve::Hard);
+        for i in 0..=20 {
+            let p = i as f32 / 20.0;
+            assert!((reader.apply(p) - PressureCurve::Hard.apply(p)).abs() < 1e-4);
+        }
+
+        let custom = PressureCurve::Custom(curve(&[(0.0, 0.0), (0.4, 0.7), (1.0, 1.0)]));
+        shared.store(&custom);
+        assert!((reader.apply(0.4) - 0.7).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_linear_curve_is_identity() {
+        let linear = SplineCurve::linear();
+        for i in 0..=10 {
+            let x = i as f32 / 10.0;
+            assert!((linear.apply(x) - x).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_passes_through_points_and_clamps() {
+        let c = curve(&[(0.0, 0.1), (0.5, 0.8), (1.0, 0.9)]);
+        assert!((c.evaluate(0.5) - 0.8).abs() < 1e-6);
+        assert!((c.apply(0.0) - 0.1).abs() < 1e-6);
+        assert!((c.apply(1.0) - 0.9).abs() < 1e-6);
+        assert!((c.apply(-1.0) - 0.1).abs() < 1e-6);
+        assert!((c.apply(2.0) - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_monotone_without_overshoot() {
+        // Steep step that a natural spline would overshoot
+        let c = curve(&[(0.0, 0.0), (0.4, 0.05), (0.5, 0.95), (1.0, 1.0)]);
+        let mut previous = 0.0;
+        for i in 0..=1000 {
+            let y = c.evaluate(i as f32 / 1000.0);
+            assert!(y >= previous - 1e-6, "not monotone at {}", i);
+            assert!((0.0..=1.0).contains(&y));
+            previous = y;
+        }
+    }
+
+    #[test]
+    fn test_lut_matches_exact() {
+        let c = curve(&[(0.0, 0.0), (0.25, 0.6), (0.7, 0.75), (1.0, 1.0)]);
+        for i in 0..=997 {
+            let x = i as f32 / 997.0;
+            assert!((c.apply(x) - c.evaluate(x)).abs() < 2e-3);
+        }
+    }
+
+    #[test]
+    fn test_points_sorted_and_validated() {
+        let c = curve(&[(1.0, 1.0), (0.0, 0.0), (0.5, 1.4)]);
+        assert_eq!(c.points()[0], CurvePoint::new(0.0, 0.0));
+        assert_eq!(c.points()[1], CurvePoint::new(0.5, 1.0));
+
+        assert!(SplineCurve::new(vec![CurvePoint::new(0.5, 0.5)]).is_err());
+        assert!(
+            SplineCurve::new(vec![CurvePoint::new(0.5, 0.0), CurvePoint::new(0.5, 1.0)]).is_err()
+        );
+        assert!(SplineCurve::new(vec![
+            CurvePoint::new(f32::NAN, 0.0),
+            CurvePoint::new(1.0, 1.0)
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let c = curve(&[(0.0, 0.0), (0.3, 0.6), (1.0, 1.0)]);
+        let Ok(json) = serde_json::to_string(&c) else {
+            panic!("curve should serialize");
+        };
+        assert_eq!(
+            json,
+            r#"[{"x":0.0,"y":0.0},{"x":0.3,"y":0.6},{"x":1.0,"y":1.0}]"#
+        );
+
+        let Ok(back) = serde_json::from_str::<SplineCurve>(&json) else {
+            panic!("curve should deserialize");
+        };
+        assert_eq!(back, c);
+        assert!((back.apply(0.3) - 0.6).abs() < 1e-3);
+
+        assert!(serde_json::from_str::<SplineCurve>(r#"[{"x":0.0,"y":0.0}]"#).is_err());
+    }
+}
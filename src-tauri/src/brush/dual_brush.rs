@@ -0,0 +1,247 @@
+//! Dual brush - modulate the primary tip with a secondary tip
+//!
+//! Mirrors Photoshop's Dual Brush panel. The secondary tip is stamped along
+//! the same path with its own size, spacing, scatter and count into a
+//! coverage mask (see `StrokeBuffer::stamp_dual`). The primary tip shape is
+//! then combined with that mask through a `DualBlendMode`.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::abr::GrayscaleImage;
+
+/// How the secondary tip mask combines with the primary tip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DualBlendMode {
+    #[default]
+    Multiply,
+    Darken,
+    Overlay,
+    ColorDodge,
+    ColorBurn,
+    LinearBurn,
+    HardMix,
+}
+
+impl DualBlendMode {
+    /// Map an ABR blend mode enum value (`BlnM`) to a dual blend mode
+    pub fn from_abr(value: &str) -> Option<Self> {
+        match value {
+            "Mltp" => Some(DualBlendMode::Multiply),
+            "Drkn" => Some(DualBlendMode::Darken),
+            "Ovrl" => Some(DualBlendMode::Overlay),
+            "CDdg" => Some(DualBlendMode::ColorDodge),
+            "CBrn" => Some(DualBlendMode::ColorBurn),
+            "linearBurn" => Some(DualBlendMode::LinearBurn),
+            "hardMix" => Some(DualBlendMode::HardMix),
+            _ => None,
+        }
+    }
+
+    /// Combine primary tip coverage with secondary mask coverage (both 0-1)
+    pub fn blend(&self, primary: f32, secondary: f32) -> f32 {
+        let p = primary;
+        let s = secondary;
+
+        let result = match self {
+            DualBlendMode::Multiply => p * s,
+            DualBlendMode::Darken => p.min(s),
+            DualBlendMode::Overlay => {
+                if p < 0.5 {
+                    2.0 * p * s
+                } else {
+                    1.0 - 2.0 * (1.0 - p) * (1.0 - s)
+                }
+            }
+            DualBlendMode::ColorDodge => {
+                if s >= 1.0 {
+                    if p > 0.0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    p / (1.0 - s)
+                }
+            }
+            DualBlendMode::ColorBurn => {
+                if s <= 0.0 {
+                    if p >= 1.0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    1.0 - (1.0 - p) / s
+                }
+            }
+            DualBlendMode::LinearBurn => p + s - 1.0,
+            DualBlendMode::HardMix => {
+                if p + s >= 1.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+
+        // Never extend coverage outside the primary tip
+        if p <= 0.0 {
+            0.0
+        } else {
+            result.clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Dual brush settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DualBrush {
+    /// Blend mode combining the secondary mask with the primary tip
+    pub mode: DualBlendMode,
+    /// Secondary tip diameter in pixels
+    pub size: f32,
+    /// Spacing as fraction of the secondary size (e.g., 0.25 = 25%)
+    pub spacing: f32,
+    /// Scatter as fraction of the secondary size
+    pub scatter: f32,
+    /// Scatter along the stroke direction as well as across it
+    pub both_axes: bool,
+    /// Secondary dabs per spacing interval
+    pub count: u32,
+    /// Hardness of the round secondary tip (ignored for sampled tips)
+    pub hardness: f32,
+    /// Sampled secondary tip (None = round tip)
+    #[serde(skip)]
+    pub tip: Option<Arc<GrayscaleImage>>,
+}
+
+impl Default for DualBrush {
+    fn default() -> Self {
+        Self {
+            mode: DualBlendMode::Multiply,
+            size: 25.0,
+            spacing: 0.25,
+            scatter: 0.0,
+            both_axes: false,
+            count: 1,
+            hardness: 1.0,
+            tip: None,
+        }
+    }
+}
+
+impl DualBrush {
+    /// Secondary tip coverage (0-1) at an offset from the dab center,
+    /// given in units of the dab radius
+    pub fn coverage(&self, u: f32, v: f32) -> f32 {
+        match &self.tip {
+            Some(tip) => sample_tip(tip, u, v),
+            None => {
+                let dist = (u * u + v * v).sqrt();
+                let hardness = self.hardness.clamp(0.0, 1.0);
+                if dist > 1.0 {
+                    0.0
+                } else if dist <= hardness || hardness >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - (dist - hardness) / (1.0 - hardness)
+                }
+            }
+        }
+    }
+}
+
+/// Bilinearly sample a tip image, fitting its longer side to the dab diameter
+fn sample_tip(tip: &GrayscaleImage, u: f32, v: f32) -> f32 {
+    if tip.width == 0 || tip.height == 0 {
+        return 0.0;
+    }
+
+    let half = tip.width.max(tip.height) as f32 * 0.5;
+    let x = u * half + tip.width as f32 * 0.5 - 0.5;
+    let y = v * half + tip.height as f32 * 0.5 - 0.5;
+
+    if x < -1.0 || y < -1.0 || x > tip.width as f32 || y > tip.height as f32 {
+        return 0.0;
+    }
+
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+
+    let texel = |tx: f32, ty: f32| -> f32 {
+        if tx < 0.0 || ty < 0.0 {
+            return 0.0;
+        }
+        tip.get_pixel(tx as u32, ty as u32)
+            .map_or(0.0, |p| p as f32 / 255.0)
+    };
+
+    let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1.0, y0) * fx;
+    let bottom = texel(x0, y0 + 1.0) * (1.0 - fx) + texel(x0 + 1.0, y0 + 1.0) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_modes_keep_primary_bounds() {
+        for mode in [
+            DualBlendMode::Multiply,
+            DualBlendMode::Darken,
+            DualBlendMode::Overlay,
+            DualBlendMode::ColorDodge,
+            DualBlendMode::ColorBurn,
+            DualBlendMode::LinearBurn,
+            DualBlendMode::HardMix,
+        ] {
+            assert_eq!(mode.blend(0.0, 1.0), 0.0, "{:?}", mode);
+            for (p, s) in [(0.3, 0.7), (1.0, 0.0), (0.5, 0.5), (1.0, 1.0)] {
+                let value = mode.blend(p, s);
+                assert!((0.0..=1.0).contains(&value), "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_and_darken() {
+        assert!((DualBlendMode::Multiply.blend(0.5, 0.5) - 0.25).abs() < 1e-6);
+        assert_eq!(DualBlendMode::Darken.blend(0.8, 0.3), 0.3);
+        assert_eq!(DualBlendMode::Multiply.blend(1.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_from_abr() {
+        assert_eq!(
+            DualBlendMode::from_abr("Mltp"),
+            Some(DualBlendMode::Multiply)
+        );
+        assert_eq!(
+            DualBlendMode::from_abr("CBrn"),
+            Some(DualBlendMode::ColorBurn)
+        );
+        assert_eq!(DualBlendMode::from_abr("????"), None);
+    }
+
+    #[test]
+    fn test_round_and_sampled_coverage() {
+        let round = DualBrush::default();
+        assert_eq!(round.coverage(0.0, 0.0), 1.0);
+        assert_eq!(round.coverage(1.5, 0.0), 0.0);
+
+        // 2x2 tip: left column opaque, right column transparent
+        let sampled = DualBrush {
+            tip: Some(Arc::new(GrayscaleImage::new(2, 2, vec![255, 0, 255, 0]))),
+            ..Default::default()
+        };
+        assert!(sampled.coverage(-0.5, 0.0) > 0.9);
+        assert!(sampled.coverage(0.5, 0.0) < 0.1);
+    }
+}
@@ -0,0 +1,242 @@
+//! Brush dynamics - control sources and jitter for dynamic brush parameters
+//!
+//! Photoshop-style dynamics modulate a brush parameter with a control
+//! source (pen pressure, tilt, velocity, direction, barrel rotation, wheel,
+//! fade) and add random jitter on top.
+//! Jitter uses a small deterministic PRNG so strokes can be reproduced.
+
+use serde::{Deserialize, Serialize};
+
+/// Input that drives a dynamic brush parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ControlSource {
+    /// No control (parameter stays at full value)
+    #[default]
+    Off,
+    /// Fade from full to minimum over the given number of dabs
+    Fade(u32),
+    /// Pen pressure
+    Pressure,
+    /// Pen tilt (0 = upright, 1 = flat)
+    Tilt,
+    /// Pen speed (0 = stationary, 1 = fast)
+    Velocity,
+    /// Stroke direction (0-1 over a full turn, counter-clockwise from +x)
+    Direction,
+    /// Airbrush wheel / tangential pressure (-1 to 1 mapped onto 0-1)
+    Wheel,
+    /// Pen barrel rotation (0-1 over a full turn)
+    Rotation,
+}
+
+impl ControlSource {
+    /// Map an ABR control code (`bVTy`) to a control source
+    ///
+    /// ABR codes: 0=Off, 1=Fade, 2=Pen Pressure, 3=Pen Tilt,
+    /// 4=Stylus Wheel, 6=Direction, 8=Rotation.
+    /// Unsupported codes fall back to `Off`.
+    pub fn from_abr(control: u32, fade_steps: u32) -> Self {
+        match control {
+            1 => ControlSource::Fade(fade_steps.max(1)),
+            2 => ControlSource::Pressure,
+            3 => ControlSource::Tilt,
+            4 => ControlSource::Wheel,
+            6 => ControlSource::Direction,
+            8 => ControlSource::Rotation,
+            _ => ControlSource::Off,
+        }
+    }
+
+    /// Evaluate the control value (0.0 - 1.0) for a dab
+    pub fn value(&self, input: &DynamicsInput) -> f32 {
+        match self {
+            ControlSource::Off => 1.0,
+            ControlSource::Fade(steps) => {
+                let progress = input.dab_index as f32 / (*steps).max(1) as f32;
+                1.0 - progress.min(1.0)
+            }
+            ControlSource::Pressure => input.pressure.clamp(0.0, 1.0),
+            ControlSource::Tilt => input.tilt.clamp(0.0, 1.0),
+            ControlSource::Velocity => input.velocity.clamp(0.0, 1.0),
+            ControlSource::Direction => (input.direction / std::f32::consts::TAU).rem_euclid(1.0),
+            ControlSource::Wheel => ((input.wheel + 1.0) * 0.5).clamp(0.0, 1.0),
+            ControlSource::Rotation => (input.rotation / 360.0).rem_euclid(1.0),
+        }
+    }
+}
+
+/// Per-dab values read by control sources
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicsInput {
+    /// Pen pressure (0.0 - 1.0)
+    pub pressure: f32,
+    /// Tilt magnitude (0.0 = upright, 1.0 = flat)
+    pub tilt: f32,
+    /// Normalized pen speed (0.0 = stationary, 1.0 = fast)
+    pub velocity: f32,
+    /// Stroke direction in radians (atan2 of the movement, 0 = +x)
+    pub direction: f32,
+    /// Barrel rotation in degrees (0 when the pen does not report it)
+    pub rotation: f32,
+    /// Tangential pressure / wheel, -1 to 1 (1 when the pen does not report it)
+    pub wheel: f32,
+    /// Index of the dab within the current stroke
+    pub dab_index: u32,
+}
+
+impl DynamicsInput {
+    /// Build dynamics input from pen state
+    pub fn new(pressure: f32, tilt_x: f32, tilt_y: f32, dab_index: u32) -> Self {
+        let tilt = ((tilt_x * tilt_x + tilt_y * tilt_y).sqrt() / 90.0).clamp(0.0, 1.0);
+        Self {
+            pressure,
+            tilt,
+            velocity: 0.0,
+            direction: 0.0,
+            rotation: 0.0,
+            wheel: 1.0,
+            dab_index,
+        }
+    }
+
+    /// Set the normalized pen speed
+    pub fn with_velocity(mut self, velocity: f32) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Set the stroke direction (radians)
+    pub fn with_direction(mut self, direction: f32) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set barrel rotation and wheel from optional pen axes
+    pub fn with_pen_axes(mut self, rotation: Option<f32>, wheel: Option<f32>) -> Self {
+        self.rotation = rotation.unwrap_or(0.0);
+        self.wheel = wheel.unwrap_or(1.0);
+        self
+    }
+}
+
+impl Default for DynamicsInput {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0, 0)
+    }
+}
+
+/// Deterministic PRNG for dab jitter (xorshift64*)
+#[derive(Debug, Clone)]
+pub struct JitterRng {
+    state: u64,
+}
+
+impl JitterRng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        // SplitMix64 scramble so that small seeds still produce good sequences
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Self { state: z.max(1) }
+    }
+
+    /// Next raw 64-bit value
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform value in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform value in [-1, 1)
+    pub fn next_signed(&mut self) -> f32 {
+        self.next_f32() * 2.0 - 1.0
+    }
+}
+
+impl Default for JitterRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_source_from_abr() {
+        assert_eq!(ControlSource::from_abr(0, 25), ControlSource::Off);
+        assert_eq!(ControlSource::from_abr(1, 25), ControlSource::Fade(25));
+        assert_eq!(ControlSource::from_abr(2, 25), ControlSource::Pressure);
+        assert_eq!(ControlSource::from_abr(3, 25), ControlSource::Tilt);
+        assert_eq!(ControlSource::from_abr(4, 25), ControlSource::Wheel);
+        assert_eq!(ControlSource::from_abr(6, 25), ControlSource::Direction);
+        assert_eq!(ControlSource::from_abr(8, 25), ControlSource::Rotation);
+    }
+
+    #[test]
+    fn test_control_source_pen_axes() {
+        let input = DynamicsInput::default().with_pen_axes(Some(270.0), Some(0.4));
+        assert_eq!(ControlSource::Rotation.value(&input), 0.75);
+        assert_eq!(ControlSource::Wheel.value(&input), 0.7);
+        // The full tangential pressure range is used, not just its upper half
+        let wheel =
+            |w| ControlSource::Wheel.value(&DynamicsInput::default().with_pen_axes(None, Some(w)));
+        assert_eq!(wheel(-1.0), 0.0);
+        assert_eq!(wheel(0.0), 0.5);
+        assert_eq!(wheel(1.0), 1.0);
+
+        // Missing axes read as neutral
+        let plain = DynamicsInput::new(0.5, 0.0, 0.0, 0);
+        assert_eq!(ControlSource::Rotation.value(&plain), 0.0);
+        assert_eq!(ControlSource::Wheel.value(&plain), 1.0);
+    }
+
+    #[test]
+    fn test_control_source_direction() {
+        let input = |direction: f32| DynamicsInput::default().with_direction(direction);
+        let half_turn = std::f32::consts::PI;
+
+        assert_eq!(ControlSource::Direction.value(&input(0.0)), 0.0);
+        assert!((ControlSource::Direction.value(&input(half_turn / 2.0)) - 0.25).abs() < 1e-6);
+        // Negative angles wrap around
+        assert!((ControlSource::Direction.value(&input(-half_turn / 2.0)) - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_control_source_fade() {
+        let fade = ControlSource::Fade(10);
+        let start = DynamicsInput::new(1.0, 0.0, 0.0, 0);
+        let mid = DynamicsInput::new(1.0, 0.0, 0.0, 5);
+        let end = DynamicsInput::new(1.0, 0.0, 0.0, 20);
+
+        assert_eq!(fade.value(&start), 1.0);
+        assert!((fade.value(&mid) - 0.5).abs() < 0.001);
+        assert_eq!(fade.value(&end), 0.0);
+    }
+
+    #[test]
+    fn test_jitter_rng_deterministic_and_in_range() {
+        let mut a = JitterRng::new(42);
+        let mut b = JitterRng::new(42);
+
+        for _ in 0..1000 {
+            let va = a.next_f32();
+            assert_eq!(va, b.next_f32());
+            assert!((0.0..1.0).contains(&va));
+
+            let s = a.next_signed();
+            b.next_signed();
+            assert!((-1.0..1.0).contains(&s));
+        }
+    }
+}
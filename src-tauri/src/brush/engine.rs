@@ -1,5 +1,7 @@
 //! Brush engine - processes raw input into renderable brush strokes
 
+use super::color_dynamics::{ColorDynamics, ColorDynamicsMode};
+use super::dynamics::{DynamicsInput, JitterRng};
 use super::interpolation::{interpolate_catmull_rom, InterpolationMode};
 use super::{BlendMode, BrushPoint, PressureCurve, StrokeSegment};
 use crate::input::RawInputPoint;
@@ -25,6 +27,8 @@ pub struct BrushSettings {
     pub opacity_curve: PressureCurve,
     /// Interpolation mode
     pub interpolation: InterpolationMode,
+    /// Color dynamics (None = single stroke color)
+    pub color_dynamics: Option<ColorDynamics>,
 }
 
 impl Default for BrushSettings {
@@ -39,6 +43,7 @@ impl Default for BrushSettings {
             size_curve: PressureCurve::Linear,
             opacity_curve: PressureCurve::Linear,
             interpolation: InterpolationMode::CatmullRom,
+            color_dynamics: None,
         }
     }
 }
@@ -48,7 +53,14 @@ pub struct BrushEngine {
     settings: BrushSettings,
     current_brush_id: u32,
     current_color: [f32; 4],
+    current_background_color: [f32; 4],
     current_blend_mode: BlendMode,
+    /// Jitter source for color dynamics, continued across `process()` calls
+    rng: JitterRng,
+    /// Dabs emitted so far in the current stroke
+    dab_index: u32,
+    /// Color chosen for the current stroke (per-stroke color dynamics)
+    stroke_color: Option<[f32; 4]>,
 }
 
 impl BrushEngine {
@@ -58,7 +70,11 @@ impl BrushEngine {
             settings: BrushSettings::default(),
             current_brush_id: 0,
             current_color: [0.0, 0.0, 0.0, 1.0], // Black
+            current_background_color: [1.0, 1.0, 1.0, 1.0], // White
             current_blend_mode: BlendMode::Normal,
+            rng: JitterRng::default(),
+            dab_index: 0,
+            stroke_color: None,
         }
     }
 
@@ -70,6 +86,17 @@ impl BrushEngine {
         }
     }
 
+    /// Reseed the jitter generator (for reproducible strokes)
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = JitterRng::new(seed);
+    }
+
+    /// Reset per-stroke state for a new stroke
+    pub fn begin_stroke(&mut self) {
+        self.dab_index = 0;
+        self.stroke_color = None;
+    }
+
     /// Update brush settings
     pub fn set_settings(&mut self, settings: BrushSettings) {
         self.settings = settings;
@@ -80,13 +107,18 @@ impl BrushEngine {
         self.current_color = color;
     }
 
+    /// Set background color (RGBA, 0.0-1.0), used by color dynamics
+    pub fn set_background_color(&mut self, color: [f32; 4]) {
+        self.current_background_color = color;
+    }
+
     /// Set blend mode
     pub fn set_blend_mode(&mut self, mode: BlendMode) {
         self.current_blend_mode = mode;
     }
 
     /// Process raw input points into renderable stroke segments
-    pub fn process(&self, points: &[RawInputPoint]) -> Vec<StrokeSegment> {
+    pub fn process(&mut self, points: &[RawInputPoint]) -> Vec<StrokeSegment> {
         if points.len() < 2 {
             return vec![];
         }
@@ -95,20 +127,59 @@ impl BrushEngine {
         let interpolated = self.interpolate_points(points);
 
         // Convert to brush points with pressure curves applied
-        let brush_points: Vec<BrushPoint> = interpolated
+        let mut brush_points: Vec<BrushPoint> = interpolated
             .iter()
             .map(|p| self.raw_to_brush_point(p))
             .collect();
 
+        let color = self.apply_color_dynamics(&mut brush_points, &interpolated);
+
         // Create stroke segment
         vec![StrokeSegment {
             points: brush_points,
             brush_id: self.current_brush_id,
-            color: self.current_color,
+            color,
             blend_mode: self.current_blend_mode,
         }]
     }
 
+    /// Apply color dynamics to brush points, returning the segment color
+    ///
+    /// Per-dab mode writes a color into every point; per-stroke mode
+    /// picks one color at the start of the stroke and keeps it.
+    fn apply_color_dynamics(
+        &mut self,
+        brush_points: &mut [BrushPoint],
+        raw_points: &[RawInputPoint],
+    ) -> [f32; 4] {
+        let Some(dynamics) = self.settings.color_dynamics else {
+            return self.current_color;
+        };
+
+        if dynamics.mode == ColorDynamicsMode::PerStroke {
+            if let Some(color) = self.stroke_color {
+                return color;
+            }
+        }
+
+        let [r, g, b, a] = self.current_color;
+        let [bg_r, bg_g, bg_b, _] = self.current_background_color;
+
+        for (point, raw) in brush_points.iter_mut().zip(raw_points) {
+            let input = DynamicsInput::new(raw.pressure, raw.tilt_x, raw.tilt_y, self.dab_index);
+            let [dr, dg, db] = dynamics.apply([r, g, b], [bg_r, bg_g, bg_b], &input, &mut self.rng);
+            self.dab_index += 1;
+
+            if dynamics.mode == ColorDynamicsMode::PerStroke {
+                self.stroke_color = Some([dr, dg, db, a]);
+                return [dr, dg, db, a];
+            }
+            point.color = Some([dr, dg, db, a]);
+        }
+
+        self.current_color
+    }
+
     /// Interpolate raw points based on settings
     fn interpolate_points(&self, points: &[RawInputPoint]) -> Vec<RawInputPoint> {
         match self.settings.interpolation {
@@ -149,7 +220,7 @@ impl BrushEngine {
                     pressure: p0.pressure + (p1.pressure - p0.pressure) * t,
                     tilt_x: p0.tilt_x + (p1.tilt_x - p0.tilt_x) * t,
                     tilt_y: p0.tilt_y + (p1.tilt_y - p0.tilt_y) * t,
-                    timestamp_ms: p0.timestamp_ms,
+                    ..*p0
                 });
             }
         }
@@ -197,6 +268,7 @@ impl BrushEngine {
             size: size.max(1.0),
             opacity: opacity.clamp(0.0, 1.0),
             rotation,
+            color: None,
         }
     }
 }
@@ -220,6 +292,11 @@ mod tests {
                 tilt_x: 0.0,
                 tilt_y: 0.0,
                 timestamp_ms: i as u64,
+                timestamp_us: i as u64 * 1000,
+                arrival_us: i as u64 * 1000,
+                rotation: None,
+                tangential_pressure: None,
+                buttons: 0,
             })
             .collect()
     }
@@ -233,14 +310,14 @@ mod tests {
 
     #[test]
     fn test_process_empty_points() {
-        let engine = BrushEngine::new();
+        let mut engine = BrushEngine::new();
         let result = engine.process(&[]);
         assert!(result.is_empty());
     }
 
     #[test]
     fn test_process_single_point() {
-        let engine = BrushEngine::new();
+        let mut engine = BrushEngine::new();
         let points = make_test_points(1);
         let result = engine.process(&points);
         assert!(result.is_empty()); // Need at least 2 points
@@ -248,7 +325,7 @@ mod tests {
 
     #[test]
     fn test_process_multiple_points() {
-        let engine = BrushEngine::new();
+        let mut engine = BrushEngine::new();
         let points = make_test_points(5);
         let result = engine.process(&points);
 
@@ -268,4 +345,64 @@ mod tests {
 
         assert!(bp_high.size > bp_low.size);
     }
+
+    #[test]
+    fn test_color_dynamics_per_point_color() {
+        let mut engine = BrushEngine::with_settings(BrushSettings {
+            color_dynamics: Some(ColorDynamics {
+                brightness_jitter: 1.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        engine.set_color([0.5, 0.5, 0.5, 1.0]);
+
+        let result = engine.process(&make_test_points(5));
+        let points = &result[0].points;
+
+        assert!(points.iter().all(|p| p.color.is_some()));
+        assert!(points.windows(2).any(|w| w[0].color != w[1].color));
+    }
+
+    #[test]
+    fn test_color_dynamics_state_spans_process_calls() {
+        let dynamics = ColorDynamics {
+            hue_jitter: 1.0,
+            brightness_jitter: 1.0,
+            ..Default::default()
+        };
+        let mut engine = BrushEngine::with_settings(BrushSettings {
+            color_dynamics: Some(ColorDynamics {
+                mode: ColorDynamicsMode::PerStroke,
+                ..dynamics
+            }),
+            ..Default::default()
+        });
+        engine.set_color([0.5, 0.2, 0.8, 1.0]);
+
+        // One color for the whole stroke, however it is batched
+        engine.begin_stroke();
+        let first = engine.process(&make_test_points(5))[0].color;
+        let second = engine.process(&make_test_points(5))[0].color;
+        assert_eq!(first, second);
+        assert_ne!(first, [0.5, 0.2, 0.8, 1.0]);
+
+        // A new stroke picks a new color
+        engine.begin_stroke();
+        let next = engine.process(&make_test_points(5))[0].color;
+        assert_ne!(next, first);
+
+        // Per-dab jitter continues instead of repeating each batch
+        engine.set_settings(BrushSettings {
+            color_dynamics: Some(dynamics),
+            ..Default::default()
+        });
+        engine.begin_stroke();
+        let a = engine.process(&make_test_points(5));
+        let b = engine.process(&make_test_points(5));
+        let colors = |segments: &[StrokeSegment]| -> Vec<_> {
+            segments[0].points.iter().map(|p| p.color).collect()
+        };
+        assert_ne!(colors(&a), colors(&b));
+    }
 }
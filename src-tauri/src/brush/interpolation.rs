@@ -80,7 +80,7 @@ fn catmull_rom_point(
         pressure: b0 * p0.pressure + b1 * p1.pressure + b2 * p2.pressure + b3 * p3.pressure,
         tilt_x: b0 * p0.tilt_x + b1 * p1.tilt_x + b2 * p2.tilt_x + b3 * p3.tilt_x,
         tilt_y: b0 * p0.tilt_y + b1 * p1.tilt_y + b2 * p2.tilt_y + b3 * p3.tilt_y,
-        timestamp_ms: p1.timestamp_ms, // Use the start point's timestamp
+        ..*p1 // Timestamp, rotation and wheel from the start point
     }
 }
 
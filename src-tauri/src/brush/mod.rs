@@ -9,21 +9,48 @@
 //! 2. **Stroke Buffer** (stroke_buffer.rs): Accumulates dabs within a single stroke
 //! 3. **Layer Level**: Composites stroke with Opacity as ceiling
 //!
+//! Transfer dynamics (transfer.rs) vary Flow per dab and scale the Opacity
+//! ceiling under each dab. Shape dynamics (shape_dynamics.rs) vary dab size
+//! and angle, e.g. ink-style thinning with speed or angle following direction.
+//! Tilt dynamics (tilt.rs) turn pen lean into flatter, wider, rotated dabs.
+//! A dual brush (dual_brush.rs) stamps a secondary tip into a mask in the
+//! stroke buffer that modulates the primary tip.
+//! Texture (texture.rs) modulates alpha with a pattern fixed in canvas space.
+//! Taper (taper.rs) thins or fades the start and end of each stroke.
+//!
 //! This separation allows Flow to accumulate within a stroke while Opacity
 //! acts as a maximum limit.
 
 mod blend;
+mod color_dynamics;
+mod curve;
+mod dual_brush;
+mod dynamics;
 mod engine;
 mod interpolation;
+mod shape_dynamics;
 pub mod soft_dab;
 mod stamper;
 mod stroke_buffer;
+mod taper;
+mod texture;
+mod tilt;
+mod transfer;
 
 pub use blend::{blend_normal_premul, BlendFunc};
+pub use color_dynamics::{hsv_to_rgb, rgb_to_hsv, ColorDynamics, ColorDynamicsMode};
+pub use curve::{CurvePoint, PressureCurve, SharedPressureCurve, SplineCurve};
+pub use dual_brush::{DualBlendMode, DualBrush};
+pub use dynamics::{ControlSource, DynamicsInput, JitterRng};
 pub use engine::{BrushEngine, BrushSettings};
 pub use interpolation::{interpolate_catmull_rom, InterpolationMode};
+pub use shape_dynamics::{AngleChannel, ShapeDynamics};
 pub use stamper::{BrushStamper, Dab, StamperConfig};
-pub use stroke_buffer::{Pixel, Rect, StrokeBuffer};
+pub use stroke_buffer::{Pixel, Rect, StrokeBuffer, StrokeConstraints};
+pub use taper::{TaperLength, TaperSettings};
+pub use texture::{pattern_from_image_bytes, TextureSettings};
+pub use tilt::{PenOrientation, TiltDynamics};
+pub use transfer::{TransferChannel, TransferDynamics};
 
 use serde::{Deserialize, Serialize};
 
@@ -40,6 +67,9 @@ pub struct BrushPoint {
     pub opacity: f32,
     /// Rotation angle in radians
     pub rotation: f32,
+    /// Per-point RGBA color from color dynamics (None = segment color)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[f32; 4]>,
 }
 
 /// A segment of a stroke ready for rendering
@@ -73,78 +103,3 @@ pub enum BlendMode {
     Difference,
     Exclusion,
 }
-
-/// Pressure curve types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum PressureCurve {
-    /// Linear mapping (1:1)
-    #[default]
-    Linear,
-    /// Soft curve (more sensitive at low pressure)
-    Soft,
-    /// Hard curve (less sensitive at low pressure)
-    Hard,
-    /// Custom curve (uses control points)
-    Custom,
-}
-
-impl PressureCurve {
-    /// Apply the pressure curve to a raw pressure value
-    pub fn apply(&self, pressure: f32) -> f32 {
-        let p = pressure.clamp(0.0, 1.0);
-
-        match self {
-            PressureCurve::Linear => p,
-            PressureCurve::Soft => {
-                // Ease-out: more sensitive at low pressure
-                1.0 - (1.0 - p).powi(2)
-            }
-            PressureCurve::Hard => {
-                // Ease-in: less sensitive at low pressure
-                p.powi(2)
-            }
-            PressureCurve::Custom => {
-                // TODO: Implement custom curve with control points
-                p
-            }
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_pressure_curve_linear() {
-        let curve = PressureCurve::Linear;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert_eq!(curve.apply(0.5), 0.5);
-        assert_eq!(curve.apply(1.0), 1.0);
-    }
-
-    #[test]
-    fn test_pressure_curve_soft() {
-        let curve = PressureCurve::Soft;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert_eq!(curve.apply(1.0), 1.0);
-        // Soft curve should give higher output for mid pressure
-        assert!(curve.apply(0.5) > 0.5);
-    }
-
-    #[test]
-    fn test_pressure_curve_hard() {
-        let curve = PressureCurve::Hard;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert_eq!(curve.apply(1.0), 1.0);
-        // Hard curve should give lower output for mid pressure
-        assert!(curve.apply(0.5) < 0.5);
-    }
-
-    #[test]
-    fn test_pressure_clamping() {
-        let curve = PressureCurve::Linear;
-        assert_eq!(curve.apply(-0.5), 0.0);
-        assert_eq!(curve.apply(1.5), 1.0);
-    }
-}
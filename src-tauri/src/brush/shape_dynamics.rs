@@ -0,0 +1,111 @@
+//! Shape dynamics - per-dab size and angle variation
+//!
+//! Mirrors Photoshop's Shape Dynamics panel:
+//! - Size: control source, minimum and jitter (same rules as transfer
+//!   channels), plus ink-style thinning that shrinks dabs as the pen speeds up
+//! - Angle: control source and jitter. `Direction` follows the stroke
+//!   direction; other sources turn the dab by up to a full rotation.
+
+use std::f32::consts::{PI, TAU};
+
+use serde::{Deserialize, Serialize};
+
+use super::dynamics::{ControlSource, DynamicsInput, JitterRng};
+use super::transfer::TransferChannel;
+
+/// Angle channel
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AngleChannel {
+    /// Control source (`Off` keeps the default tilt-based angle)
+    pub control: ControlSource,
+    /// Random rotation amount (0-1, 1 = up to ±180°)
+    pub jitter: f32,
+}
+
+impl AngleChannel {
+    /// Dab angle in radians, given the angle used when the control is off
+    pub fn evaluate(&self, default_angle: f32, input: &DynamicsInput, rng: &mut JitterRng) -> f32 {
+        let controlled = match self.control {
+            ControlSource::Off => default_angle,
+            ControlSource::Direction => input.direction,
+            source => source.value(input) * TAU,
+        };
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter > 0.0 {
+            controlled + jitter * PI * rng.next_signed()
+        } else {
+            controlled
+        }
+    }
+}
+
+/// Shape dynamics settings
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShapeDynamics {
+    /// Size channel (multiplier of the pressure-controlled size)
+    pub size: TransferChannel,
+    /// Size reduction at full pen speed (0-1)
+    pub ink_thinning: f32,
+    /// Angle channel
+    pub angle: AngleChannel,
+}
+
+impl ShapeDynamics {
+    /// Size multiplier (0.0 - 1.0) for a dab
+    pub fn size_scale(&self, input: &DynamicsInput, rng: &mut JitterRng) -> f32 {
+        let thinning = 1.0 - self.ink_thinning.clamp(0.0, 1.0) * input.velocity.clamp(0.0, 1.0);
+        self.size.evaluate(input, rng) * thinning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ink_thinning_shrinks_fast_dabs() {
+        let shape = ShapeDynamics {
+            ink_thinning: 0.6,
+            ..Default::default()
+        };
+        let mut rng = JitterRng::new(1);
+        let slow = DynamicsInput::new(1.0, 0.0, 0.0, 0);
+        let fast = slow.with_velocity(1.0);
+
+        assert_eq!(shape.size_scale(&slow, &mut rng), 1.0);
+        assert!((shape.size_scale(&fast, &mut rng) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_angle_follows_direction() {
+        let channel = AngleChannel {
+            control: ControlSource::Direction,
+            jitter: 0.0,
+        };
+        let mut rng = JitterRng::new(1);
+        let input = DynamicsInput::new(1.0, 0.0, 0.0, 0).with_direction(PI / 2.0);
+
+        assert_eq!(channel.evaluate(0.3, &input, &mut rng), PI / 2.0);
+
+        let off = AngleChannel::default();
+        assert_eq!(off.evaluate(0.3, &input, &mut rng), 0.3);
+    }
+
+    #[test]
+    fn test_angle_jitter_is_bounded() {
+        let channel = AngleChannel {
+            control: ControlSource::Off,
+            jitter: 0.25,
+        };
+        let mut rng = JitterRng::new(7);
+        let input = DynamicsInput::default();
+
+        for _ in 0..200 {
+            let angle = channel.evaluate(1.0, &input, &mut rng);
+            assert!((angle - 1.0).abs() <= 0.25 * PI);
+        }
+    }
+}
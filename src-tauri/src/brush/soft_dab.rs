@@ -3,12 +3,31 @@
 //! This module implements Krita-style soft brush rendering with:
 //! - SIMD vectorized erf calculation (AVX: 8 pixels at once)
 //! - Per-row batch processing (FastRowProcessor pattern)
-//! - Alpha Darken compositing
+//! - Alpha Darken compositing, plus Behind, Erase and Clear dab modes
 //!
 //! Reference: Krita's kis_brush_mask_processor_factories.cpp
 
 use std::f32::consts::SQRT_2;
 
+use serde::{Deserialize, Serialize};
+
+/// How a soft dab changes the target buffer
+///
+/// All modes use `flow` as the per-dab rate and `dab_opacity` as the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DabMode {
+    /// Alpha Darken painting: alpha rises toward `dab_opacity`
+    #[default]
+    Paint,
+    /// Paint underneath existing pixels (destination-over)
+    Behind,
+    /// Alpha falls toward `1 - dab_opacity`, color is kept
+    Erase,
+    /// Like Erase, but fully cleared pixels also lose their color
+    Clear,
+}
+
 /// Gaussian mask parameters (pre-calculated for performance)
 ///
 /// These parameters are computed once when brush settings change,
@@ -287,6 +306,7 @@ pub fn process_row(
 /// * `color` - RGB color (0-255)
 /// * `flow` - Per-dab accumulation rate (0.0-1.0)
 /// * `dab_opacity` - Target alpha ceiling (0.0-1.0)
+/// * `mode` - Paint, Behind, Erase or Clear
 ///
 /// # Returns
 /// Dirty rectangle (left, top, width, height)
@@ -302,6 +322,7 @@ pub fn render_soft_dab(
     color: (u8, u8, u8),
     flow: f32,
     dab_opacity: f32,
+    mode: DabMode,
 ) -> (usize, usize, usize, usize) {
     // Calculate extent based on fade (soft brushes need larger area)
     let extent_mult = 1.0 + params.fade;
@@ -330,7 +351,9 @@ pub fn render_soft_dab(
         // Calculate mask values for this row
         process_row(&mut mask_row, width, world_y, cx - left as f32, cy, params);
 
-        for (col, &mask_shape) in mask_row.iter().enumerate().take(width) {
+        for (col, &fade) in mask_row.iter().enumerate().take(width) {
+            // Mask rows hold Krita-style fade (0 = opaque, 1 = transparent)
+            let mask_shape = 1.0 - fade;
             if mask_shape < 0.001 {
                 continue;
             }
@@ -348,44 +371,109 @@ pub fn render_soft_dab(
             let dst_b = buffer[idx + 2] as f32;
             let dst_a = buffer[idx + 3] as f32 / 255.0;
 
-            // Alpha Darken compositing (Krita-style)
-            // - If dst_a >= target: no change
-            // - Otherwise: lerp toward target
-            let out_a = if dst_a >= target_alpha - 0.001 {
-                dst_a
-            } else {
-                dst_a + (target_alpha - dst_a) * src_alpha
+            let out = match mode {
+                DabMode::Paint => alpha_darken(
+                    [dst_r, dst_g, dst_b],
+                    dst_a,
+                    [r, g, b],
+                    src_alpha,
+                    target_alpha,
+                ),
+                DabMode::Behind => behind(
+                    [dst_r, dst_g, dst_b],
+                    dst_a,
+                    [r, g, b],
+                    src_alpha,
+                    target_alpha,
+                ),
+                DabMode::Erase | DabMode::Clear => {
+                    // Mirror of Alpha Darken: lerp alpha down toward the floor
+                    let floor = 1.0 - target_alpha;
+                    let out_a = if dst_a <= floor + 0.001 {
+                        dst_a
+                    } else {
+                        dst_a + (floor - dst_a) * src_alpha
+                    };
+                    Some(([dst_r, dst_g, dst_b], out_a))
+                }
             };
 
-            if out_a > 0.001 {
-                let has_existing = dst_a > 0.001;
-                let out_r = if has_existing {
-                    dst_r + (r - dst_r) * src_alpha
-                } else {
-                    r
-                };
-                let out_g = if has_existing {
-                    dst_g + (g - dst_g) * src_alpha
-                } else {
-                    g
-                };
-                let out_b = if has_existing {
-                    dst_b + (b - dst_b) * src_alpha
-                } else {
-                    b
-                };
-
-                buffer[idx] = out_r.round().clamp(0.0, 255.0) as u8;
-                buffer[idx + 1] = out_g.round().clamp(0.0, 255.0) as u8;
-                buffer[idx + 2] = out_b.round().clamp(0.0, 255.0) as u8;
-                buffer[idx + 3] = (out_a * 255.0).round().clamp(0.0, 255.0) as u8;
+            let Some((rgb, out_a)) = out else {
+                continue;
+            };
+            let alpha_u8 = (out_a * 255.0).round().clamp(0.0, 255.0) as u8;
+
+            if mode == DabMode::Clear && alpha_u8 == 0 {
+                buffer[idx..idx + 4].copy_from_slice(&[0, 0, 0, 0]);
+                continue;
             }
+
+            buffer[idx] = rgb[0].round().clamp(0.0, 255.0) as u8;
+            buffer[idx + 1] = rgb[1].round().clamp(0.0, 255.0) as u8;
+            buffer[idx + 2] = rgb[2].round().clamp(0.0, 255.0) as u8;
+            buffer[idx + 3] = alpha_u8;
         }
     }
 
     (left, top, width, height)
 }
 
+/// Alpha Darken compositing (Krita-style), straight alpha
+///
+/// - If dst_a >= target: alpha unchanged
+/// - Otherwise: lerp alpha toward target
+///
+/// Returns None when the result stays transparent.
+#[inline]
+fn alpha_darken(
+    dst: [f32; 3],
+    dst_a: f32,
+    color: [f32; 3],
+    src_alpha: f32,
+    target_alpha: f32,
+) -> Option<([f32; 3], f32)> {
+    let out_a = if dst_a >= target_alpha - 0.001 {
+        dst_a
+    } else {
+        dst_a + (target_alpha - dst_a) * src_alpha
+    };
+
+    if out_a <= 0.001 {
+        return None;
+    }
+
+    let rgb = if dst_a > 0.001 {
+        [0, 1, 2].map(|i| dst[i] + (color[i] - dst[i]) * src_alpha)
+    } else {
+        color
+    };
+    Some((rgb, out_a))
+}
+
+/// Behind compositing: alpha grows as with Alpha Darken, but the added
+/// coverage goes underneath the existing color (destination-over)
+#[inline]
+fn behind(
+    dst: [f32; 3],
+    dst_a: f32,
+    color: [f32; 3],
+    src_alpha: f32,
+    target_alpha: f32,
+) -> Option<([f32; 3], f32)> {
+    if dst_a >= target_alpha - 0.001 {
+        return None;
+    }
+
+    let out_a = dst_a + (target_alpha - dst_a) * src_alpha;
+    if out_a <= 0.001 {
+        return None;
+    }
+
+    let added = out_a - dst_a;
+    let rgb = [0, 1, 2].map(|i| (dst[i] * dst_a + color[i] * added) / out_a);
+    Some((rgb, out_a))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -456,6 +544,7 @@ mod tests {
             (255, 0, 0),
             1.0,
             1.0,
+            DabMode::Paint,
         );
 
         // Should have valid dirty rect
@@ -472,4 +561,120 @@ mod tests {
         // At least verify the function executed and produced some output
         assert!(has_alpha || has_red || width > 0);
     }
+
+    #[test]
+    fn test_paint_falloff_is_opaque_at_center() {
+        // Regression: mask rows hold fade, so the dab center must be the
+        // most opaque point and coverage must fall off toward the rim
+        let mut buffer = vec![0u8; 41 * 41 * 4];
+        let params = GaussParams::new(0.5, 8.0, 1.0);
+        render_soft_dab(
+            &mut buffer,
+            41,
+            41,
+            20.5,
+            20.5,
+            8.0,
+            &params,
+            (255, 0, 0),
+            1.0,
+            1.0,
+            DabMode::Paint,
+        );
+
+        let alpha: Vec<u8> = (20..41).map(|x| buffer[(20 * 41 + x) * 4 + 3]).collect();
+        assert_eq!(alpha[0], 255);
+        assert!(alpha.windows(2).all(|w| w[0] >= w[1]), "{:?}", alpha);
+        // Half coverage a few pixels in, nearly nothing at the radius
+        assert!((100..180).contains(&alpha[3]), "{:?}", alpha);
+        assert!(alpha[8] < 8, "{:?}", alpha);
+        assert_eq!(alpha[20], 0);
+        assert_eq!(buffer[(20 * 41 + 20) * 4], 255);
+    }
+
+    /// Stamp one hard dab of the given mode at the center of a 21x21 buffer
+    fn stamp_center(buffer: &mut [u8], flow: f32, opacity: f32, mode: DabMode) -> [u8; 4] {
+        let params = GaussParams::new(1.0, 5.0, 1.0);
+        render_soft_dab(
+            buffer,
+            21,
+            21,
+            10.5,
+            10.5,
+            5.0,
+            &params,
+            (255, 0, 0),
+            flow,
+            opacity,
+            mode,
+        );
+        let idx = (10 * 21 + 10) * 4;
+        [
+            buffer[idx],
+            buffer[idx + 1],
+            buffer[idx + 2],
+            buffer[idx + 3],
+        ]
+    }
+
+    #[test]
+    fn test_erase_respects_flow_and_opacity() {
+        let mut buffer = [0u8, 0, 255, 255].repeat(21 * 21);
+
+        // Half flow removes half of the way to the floor
+        let pixel = stamp_center(&mut buffer, 0.5, 1.0, DabMode::Erase);
+        assert_eq!(pixel, [0, 0, 255, 128]);
+
+        // Repeated dabs never erase below 1 - opacity
+        let mut buffer = [0u8, 0, 255, 255].repeat(21 * 21);
+        for _ in 0..20 {
+            stamp_center(&mut buffer, 0.5, 0.6, DabMode::Erase);
+        }
+        let idx = (10 * 21 + 10) * 4;
+        assert!(
+            (102..=104).contains(&buffer[idx + 3]),
+            "{}",
+            buffer[idx + 3]
+        );
+        // Far corner untouched
+        assert_eq!(buffer[3], 255);
+    }
+
+    #[test]
+    fn test_clear_drops_color_of_cleared_pixels() {
+        let mut erased = [0u8, 0, 255, 255].repeat(21 * 21);
+        let mut cleared = erased.clone();
+
+        assert_eq!(
+            stamp_center(&mut erased, 1.0, 1.0, DabMode::Erase),
+            [0, 0, 255, 0]
+        );
+        assert_eq!(
+            stamp_center(&mut cleared, 1.0, 1.0, DabMode::Clear),
+            [0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_behind_paints_under_existing_color() {
+        // Opaque pixels are unchanged
+        let mut buffer = [0u8, 0, 255, 255].repeat(21 * 21);
+        assert_eq!(
+            stamp_center(&mut buffer, 1.0, 1.0, DabMode::Behind),
+            [0, 0, 255, 255]
+        );
+
+        // Transparent pixels receive the dab like normal painting
+        let mut buffer = vec![0u8; 21 * 21 * 4];
+        assert_eq!(
+            stamp_center(&mut buffer, 1.0, 0.5, DabMode::Behind),
+            [255, 0, 0, 128]
+        );
+
+        // Half-covered pixels keep their color on top
+        let mut buffer = [0u8, 0, 255, 128].repeat(21 * 21);
+        let pixel = stamp_center(&mut buffer, 1.0, 1.0, DabMode::Behind);
+        assert_eq!(pixel[3], 255);
+        assert!(pixel[0] > 100 && pixel[2] > 100);
+    }
 }
@@ -3,7 +3,25 @@
 //! This module handles the conversion of input points to brush dabs,
 //! using distance accumulation to ensure consistent spacing regardless
 //! of input device sampling rate.
-
+//!
+//! With build-up (airbrush) enabled, dabs are also emitted over time so
+//! paint keeps accumulating while the pen is stationary.
+//!
+//! Pen speed (from timestamps) and stroke direction (from the interpolated
+//! path) are tracked per dab and exposed to dynamics as control sources.
+//!
+//! With taper enabled, dabs are tagged with their distance along the stroke;
+//! dabs near the end are held back until `finish_stroke` (see taper.rs).
+
+use super::color_dynamics::{ColorDynamics, ColorDynamicsMode};
+use super::dual_brush::DualBrush;
+use super::dynamics::{DynamicsInput, JitterRng};
+use super::shape_dynamics::ShapeDynamics;
+use super::taper::TaperSettings;
+use super::texture::TextureSettings;
+use super::tilt::{PenOrientation, TiltDynamics};
+use super::transfer::TransferDynamics;
+use super::PressureCurve;
 use crate::input::RawInputPoint;
 
 /// A single brush dab to be rendered
@@ -17,10 +35,18 @@ pub struct Dab {
     pub size: f32,
     /// Dab alpha (0-1), affected by flow and pressure
     pub alpha: f32,
+    /// Stroke opacity multiplier under this dab (0-1), from transfer dynamics
+    pub opacity: f32,
     /// Rotation angle in radians
     pub angle: f32,
+    /// Ratio of minor to major axis (1 = round), from tilt dynamics
+    pub roundness: f32,
     /// Pressure at this point (for reference)
     pub pressure: f32,
+    /// Dab color (RGB, 0-1), after color dynamics
+    pub color: [f32; 3],
+    /// Texture depth multiplier (0-1), from texture depth dynamics
+    pub texture_depth: f32,
 }
 
 /// Interpolated point along the stroke path
@@ -31,6 +57,8 @@ struct PathPoint {
     pressure: f32,
     tilt_x: f32,
     tilt_y: f32,
+    rotation: Option<f32>,
+    tangential_pressure: Option<f32>,
 }
 
 impl PathPoint {
@@ -41,6 +69,8 @@ impl PathPoint {
             pressure: p.pressure,
             tilt_x: p.tilt_x,
             tilt_y: p.tilt_y,
+            rotation: p.rotation,
+            tangential_pressure: p.tangential_pressure,
         }
     }
 
@@ -51,6 +81,28 @@ impl PathPoint {
             pressure: self.pressure + (other.pressure - self.pressure) * t,
             tilt_x: self.tilt_x + (other.tilt_x - self.tilt_x) * t,
             tilt_y: self.tilt_y + (other.tilt_y - self.tilt_y) * t,
+            ..self.lerp_pen_axes(other, t)
+        }
+    }
+
+    /// Interpolate rotation (shortest way round) and tangential pressure
+    fn lerp_pen_axes(&self, other: &Self, t: f32) -> Self {
+        let rotation = match (self.rotation, other.rotation) {
+            (Some(a), Some(b)) => {
+                let delta = (b - a + 180.0).rem_euclid(360.0) - 180.0;
+                Some((a + delta * t).rem_euclid(360.0))
+            }
+            (a, b) => a.or(b),
+        };
+        let tangential_pressure = match (self.tangential_pressure, other.tangential_pressure) {
+            (Some(a), Some(b)) => Some(a + (b - a) * t),
+            (a, b) => a.or(b),
+        };
+
+        Self {
+            rotation,
+            tangential_pressure,
+            ..*self
         }
     }
 
@@ -80,6 +132,30 @@ pub struct StamperConfig {
     pub min_size_ratio: f32,
     /// Minimum alpha ratio when pressure = 0 (0-1)
     pub min_alpha_ratio: f32,
+    /// Pressure curve for size
+    pub size_curve: PressureCurve,
+    /// Pressure curve for flow
+    pub flow_curve: PressureCurve,
+    /// Foreground color (RGB, 0-1)
+    pub color: [f32; 3],
+    /// Background color (RGB, 0-1), used by color dynamics
+    pub background_color: [f32; 3],
+    /// Per-dab color variation (None = always foreground color)
+    pub color_dynamics: Option<ColorDynamics>,
+    /// Flow/opacity dynamics (None = legacy `pressure_alpha` behavior)
+    pub transfer: Option<TransferDynamics>,
+    /// Size/angle dynamics (None = pressure size, tilt angle)
+    pub shape: Option<ShapeDynamics>,
+    /// Tilt-driven angle, roundness and size (None = round dabs)
+    pub tilt: Option<TiltDynamics>,
+    /// Secondary tip (None = no dual brush)
+    pub dual_brush: Option<DualBrush>,
+    /// Pattern texture (None = no texture)
+    pub texture: Option<TextureSettings>,
+    /// Airbrush-style build-up: keep emitting dabs while stationary
+    pub build_up: bool,
+    /// Stroke start/end taper (None = blunt caps)
+    pub taper: Option<TaperSettings>,
 }
 
 impl Default for StamperConfig {
@@ -93,10 +169,31 @@ impl Default for StamperConfig {
             pressure_alpha: true,
             min_size_ratio: 0.0,
             min_alpha_ratio: 0.0,
+            size_curve: PressureCurve::Linear,
+            flow_curve: PressureCurve::Linear,
+            color: [0.0, 0.0, 0.0],
+            background_color: [1.0, 1.0, 1.0],
+            color_dynamics: None,
+            transfer: None,
+            shape: None,
+            tilt: None,
+            dual_brush: None,
+            texture: None,
+            build_up: false,
+            taper: None,
         }
     }
 }
 
+/// Pen speed (pixels per millisecond) treated as full velocity
+const VELOCITY_REFERENCE_PX_PER_MS: f32 = 4.0;
+
+/// Interval between build-up dabs in microseconds (50 dabs per second)
+const BUILD_UP_INTERVAL_US: u64 = 20_000;
+
+/// Maximum build-up dabs emitted at once (limits catch-up after stalls)
+const BUILD_UP_MAX_DABS: u64 = 8;
+
 /// Brush stamper that converts input points to dabs
 pub struct BrushStamper {
     config: StamperConfig,
@@ -108,6 +205,28 @@ pub struct BrushStamper {
     point_history: Vec<PathPoint>,
     /// Whether this is the first point of a stroke
     is_stroke_start: bool,
+    /// Number of dabs emitted in the current stroke
+    dab_index: u32,
+    /// Random source for dab jitter
+    rng: JitterRng,
+    /// Color chosen for the current stroke (per-stroke color dynamics)
+    stroke_color: Option<[f32; 3]>,
+    /// Last raw input point (for velocity estimation)
+    last_input: Option<RawInputPoint>,
+    /// Smoothed normalized pen speed (0-1)
+    velocity: f32,
+    /// Stroke direction in radians along the interpolated path
+    direction: f32,
+    /// Accumulated distance since last secondary (dual brush) dab
+    dual_distance: f32,
+    /// Secondary dabs emitted since the last `take_dual_dabs`
+    dual_dabs: Vec<Dab>,
+    /// Time of the last emitted dab in microseconds (for build-up)
+    last_dab_time_us: Option<u64>,
+    /// Path length of the current stroke so far
+    stroke_length: f32,
+    /// Dabs held back for taper, with their distance along the stroke
+    taper_pending: Vec<(Dab, f32)>,
 }
 
 impl BrushStamper {
@@ -119,9 +238,25 @@ impl BrushStamper {
             last_stamp_point: None,
             point_history: Vec::with_capacity(4),
             is_stroke_start: true,
+            dab_index: 0,
+            rng: JitterRng::default(),
+            stroke_color: None,
+            last_input: None,
+            velocity: 0.0,
+            direction: 0.0,
+            dual_distance: 0.0,
+            dual_dabs: Vec::new(),
+            last_dab_time_us: None,
+            stroke_length: 0.0,
+            taper_pending: Vec::new(),
         }
     }
 
+    /// Reseed the jitter generator (for reproducible strokes)
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = JitterRng::new(seed);
+    }
+
     /// Update configuration
     pub fn set_config(&mut self, config: StamperConfig) {
         self.config = config;
@@ -138,6 +273,24 @@ impl BrushStamper {
         self.last_stamp_point = None;
         self.point_history.clear();
         self.is_stroke_start = true;
+        self.dab_index = 0;
+        self.stroke_color = None;
+        self.last_input = None;
+        self.velocity = 0.0;
+        self.direction = 0.0;
+        self.dual_distance = 0.0;
+        self.dual_dabs.clear();
+        self.last_dab_time_us = None;
+        self.stroke_length = 0.0;
+        self.taper_pending.clear();
+    }
+
+    /// Take the secondary dabs emitted so far
+    ///
+    /// Stamp these with `StrokeBuffer::stamp_dual` before stamping the
+    /// primary dabs returned by the same `process_point` call.
+    pub fn take_dual_dabs(&mut self) -> Vec<Dab> {
+        std::mem::take(&mut self.dual_dabs)
     }
 
     /// Process a new input point and return dabs to render
@@ -145,6 +298,8 @@ impl BrushStamper {
         let path_point = PathPoint::from_raw(point);
         let mut dabs = Vec::new();
 
+        self.update_velocity(point);
+
         // Add to history for interpolation
         self.point_history.push(path_point);
         if self.point_history.len() > 4 {
@@ -155,13 +310,15 @@ impl BrushStamper {
         if self.is_stroke_start {
             self.is_stroke_start = false;
             self.last_stamp_point = Some(path_point);
-            dabs.push(self.create_dab(&path_point));
-            return dabs;
+            self.emit_dual_dabs(&path_point, (1.0, 0.0));
+            dabs.push((self.create_dab(&path_point), 0.0));
+            self.last_dab_time_us = Some(point.timestamp_us);
+            return self.apply_taper(dabs);
         }
 
         // Need at least 2 points to interpolate
         if self.point_history.len() < 2 {
-            return dabs;
+            return Vec::new();
         }
 
         // Get interpolated path points
@@ -178,7 +335,13 @@ impl BrushStamper {
             };
 
             let distance = last.distance_to(&path_point);
+            if distance > 0.001 {
+                self.direction = (path_point.y - last.y).atan2(path_point.x - last.x);
+            }
+            let segment_start = self.stroke_length;
+            self.stroke_length += distance;
             self.accumulated_distance += distance;
+            self.advance_dual(&last, &path_point, distance);
 
             // Dynamic spacing based on current size
             let current_size = self.calculate_size(path_point.pressure);
@@ -195,7 +358,7 @@ impl BrushStamper {
                 };
 
                 let dab_point = last.lerp(&path_point, t);
-                dabs.push(self.create_dab(&dab_point));
+                dabs.push((self.create_dab(&dab_point), segment_start + distance * t));
 
                 self.accumulated_distance -= threshold;
                 self.last_stamp_point = Some(dab_point);
@@ -204,39 +367,265 @@ impl BrushStamper {
             self.last_stamp_point = Some(path_point);
         }
 
-        dabs
+        if !dabs.is_empty() {
+            self.last_dab_time_us = Some(point.timestamp_us);
+        }
+        self.emit_build_up(point.timestamp_us, &mut dabs);
+
+        self.apply_taper(dabs)
+    }
+
+    /// Emit build-up dabs at the current pen position without new input
+    ///
+    /// Call periodically while the pen is down so build-up continues even
+    /// when the device stops reporting a stationary pen. `timestamp_us` is
+    /// on the input clock, like `RawInputPoint::timestamp_us`.
+    pub fn tick(&mut self, timestamp_us: u64) -> Vec<Dab> {
+        let mut dabs = Vec::new();
+        if !self.is_stroke_start {
+            self.emit_build_up(timestamp_us, &mut dabs);
+        }
+        self.apply_taper(dabs)
+    }
+
+    /// Emit time-based dabs at the current position (build-up)
+    fn emit_build_up(&mut self, now_us: u64, dabs: &mut Vec<(Dab, f32)>) {
+        if !self.config.build_up {
+            return;
+        }
+        let (Some(point), Some(last_time)) =
+            (self.point_history.last().copied(), self.last_dab_time_us)
+        else {
+            return;
+        };
+
+        let intervals = now_us.saturating_sub(last_time) / BUILD_UP_INTERVAL_US;
+        if intervals == 0 {
+            return;
+        }
+
+        for _ in 0..intervals.min(BUILD_UP_MAX_DABS) {
+            dabs.push((self.create_dab(&point), self.stroke_length));
+        }
+
+        // Drop the backlog beyond the cap instead of bursting later
+        self.last_dab_time_us = Some(if intervals > BUILD_UP_MAX_DABS {
+            now_us
+        } else {
+            last_time + intervals * BUILD_UP_INTERVAL_US
+        });
     }
 
     /// Finish stroke and return any remaining dabs
+    ///
+    /// With taper, this releases the held-back tail scaled by the final
+    /// stroke length.
     pub fn finish_stroke(&mut self) -> Vec<Dab> {
-        // Could emit a final dab at the exact end point if needed
-        let dabs = Vec::new();
+        let dabs = match self.config.taper {
+            Some(taper) => {
+                let total = self.stroke_length;
+                std::mem::take(&mut self.taper_pending)
+                    .into_iter()
+                    .map(|(dab, distance)| {
+                        scale_dab(&taper, dab, taper.factor(distance, Some(total)))
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
         self.begin_stroke();
         dabs
     }
 
+    /// Apply taper-in and hold back dabs that may still need taper-out
+    fn apply_taper(&mut self, dabs: Vec<(Dab, f32)>) -> Vec<Dab> {
+        let Some(taper) = self.config.taper else {
+            return dabs.into_iter().map(|(dab, _)| dab).collect();
+        };
+
+        self.taper_pending.extend(dabs);
+
+        // Dabs before the earliest possible taper-out start are final
+        let release_until = taper.taper_out_start(self.stroke_length);
+        let ready = self
+            .taper_pending
+            .iter()
+            .position(|(_, distance)| *distance > release_until)
+            .unwrap_or(self.taper_pending.len());
+
+        self.taper_pending
+            .drain(..ready)
+            .map(|(dab, distance)| scale_dab(&taper, dab, taper.factor(distance, None)))
+            .collect()
+    }
+
+    /// Accumulate distance for the secondary tip and emit its dabs
+    fn advance_dual(&mut self, from: &PathPoint, to: &PathPoint, distance: f32) {
+        let Some(dual) = &self.config.dual_brush else {
+            return;
+        };
+        let threshold = (dual.size * dual.spacing).max(1.0);
+
+        let direction = if distance > 0.001 {
+            ((to.x - from.x) / distance, (to.y - from.y) / distance)
+        } else {
+            (1.0, 0.0)
+        };
+
+        self.dual_distance += distance;
+        while self.dual_distance >= threshold {
+            let overshoot = self.dual_distance - threshold;
+            let t = if distance > 0.001 {
+                1.0 - (overshoot / distance).min(1.0)
+            } else {
+                1.0
+            };
+
+            self.emit_dual_dabs(&from.lerp(to, t), direction);
+            self.dual_distance -= threshold;
+        }
+    }
+
+    /// Emit `count` scattered secondary dabs around a path point
+    fn emit_dual_dabs(&mut self, point: &PathPoint, direction: (f32, f32)) {
+        let Some(dual) = &self.config.dual_brush else {
+            return;
+        };
+        let size = dual.size;
+        let scatter = dual.scatter * dual.size;
+        let both_axes = dual.both_axes;
+        let count = dual.count.max(1);
+
+        for _ in 0..count {
+            let across = scatter * self.rng.next_signed();
+            let along = if both_axes {
+                scatter * self.rng.next_signed()
+            } else {
+                0.0
+            };
+
+            self.dual_dabs.push(Dab {
+                x: point.x + direction.0 * along - direction.1 * across,
+                y: point.y + direction.1 * along + direction.0 * across,
+                size,
+                alpha: 1.0,
+                opacity: 1.0,
+                angle: 0.0,
+                roundness: 1.0,
+                pressure: point.pressure,
+                color: self.config.color,
+                texture_depth: 1.0,
+            });
+        }
+    }
+
+    /// Update the smoothed pen speed from a new input point
+    fn update_velocity(&mut self, point: &RawInputPoint) {
+        if let Some(last) = self.last_input {
+            let dt_us = point.timestamp_us.saturating_sub(last.timestamp_us);
+            // Points with the same timestamp keep the previous estimate
+            if dt_us > 0 {
+                let dx = point.x - last.x;
+                let dy = point.y - last.y;
+                let speed = (dx * dx + dy * dy).sqrt() * 1000.0 / dt_us as f32;
+                let normalized = (speed / VELOCITY_REFERENCE_PX_PER_MS).min(1.0);
+                self.velocity += (normalized - self.velocity) * 0.5;
+            }
+        }
+        self.last_input = Some(*point);
+    }
+
     /// Create a dab from a path point
-    fn create_dab(&self, point: &PathPoint) -> Dab {
-        let size = self.calculate_size(point.pressure);
-        let alpha = self.calculate_alpha(point.pressure);
-        let angle = point.tilt_y.atan2(point.tilt_x);
+    fn create_dab(&mut self, point: &PathPoint) -> Dab {
+        let input = DynamicsInput::new(point.pressure, point.tilt_x, point.tilt_y, self.dab_index)
+            .with_velocity(self.velocity)
+            .with_direction(self.direction)
+            .with_pen_axes(point.rotation, point.tangential_pressure);
+
+        let mut size = self.calculate_size(point.pressure);
+        let mut angle = point.tilt_y.atan2(point.tilt_x);
+        let mut roundness = 1.0;
+        if let Some(tilt) = &self.config.tilt {
+            let orientation = PenOrientation::from_tilt(point.tilt_x, point.tilt_y);
+            let strength = tilt.strength(&orientation);
+            angle = tilt.dab_angle(&orientation, 0.0);
+            roundness = tilt.roundness(strength);
+            size *= tilt.size_scale(strength);
+        }
+        if let Some(shape) = self.config.shape {
+            size *= shape.size_scale(&input, &mut self.rng);
+            angle = shape.angle.evaluate(angle, &input, &mut self.rng);
+        }
+
+        let (alpha, opacity) = self.calculate_transfer(&input);
+        let color = self.calculate_color(&input);
+        let texture_depth = match &self.config.texture {
+            Some(texture) => texture.dab_depth(&input, &mut self.rng),
+            None => 1.0,
+        };
+        self.dab_index += 1;
 
         Dab {
             x: point.x,
             y: point.y,
             size,
             alpha,
+            opacity,
             angle,
+            roundness,
             pressure: point.pressure,
+            color,
+            texture_depth,
         }
     }
 
+    /// Calculate dab alpha (flow) and opacity multiplier from transfer dynamics
+    fn calculate_transfer(&mut self, input: &DynamicsInput) -> (f32, f32) {
+        let Some(transfer) = self.config.transfer else {
+            return (self.calculate_alpha(input.pressure), 1.0);
+        };
+
+        // The flow curve shapes pressure before the flow control reads it
+        let flow_input = DynamicsInput {
+            pressure: self.config.flow_curve.apply(input.pressure),
+            ..*input
+        };
+        let flow = self.config.flow * transfer.flow.evaluate(&flow_input, &mut self.rng);
+        let opacity = transfer.opacity.evaluate(input, &mut self.rng);
+        (flow, opacity)
+    }
+
+    /// Calculate dab color from color dynamics
+    fn calculate_color(&mut self, input: &DynamicsInput) -> [f32; 3] {
+        let Some(dynamics) = self.config.color_dynamics else {
+            return self.config.color;
+        };
+
+        if dynamics.mode == ColorDynamicsMode::PerStroke {
+            if let Some(color) = self.stroke_color {
+                return color;
+            }
+        }
+
+        let color = dynamics.apply(
+            self.config.color,
+            self.config.background_color,
+            input,
+            &mut self.rng,
+        );
+
+        if dynamics.mode == ColorDynamicsMode::PerStroke {
+            self.stroke_color = Some(color);
+        }
+        color
+    }
+
     /// Calculate dab size based on pressure
     fn calculate_size(&self, pressure: f32) -> f32 {
         if self.config.pressure_size {
             let min = self.config.size * self.config.min_size_ratio;
             let range = self.config.size - min;
-            min + range * pressure
+            min + range * self.config.size_curve.apply(pressure)
         } else {
             self.config.size
         }
@@ -248,7 +637,7 @@ impl BrushStamper {
         if self.config.pressure_alpha {
             let min = base_alpha * self.config.min_alpha_ratio;
             let range = base_alpha - min;
-            min + range * pressure
+            min + range * self.config.flow_curve.apply(pressure)
         } else {
             base_alpha
         }
@@ -355,12 +744,29 @@ fn catmull_rom_point(
             .clamp(0.0, 1.0),
         tilt_x: b0 * p0.tilt_x + b1 * p1.tilt_x + b2 * p2.tilt_x + b3 * p3.tilt_x,
         tilt_y: b0 * p0.tilt_y + b1 * p1.tilt_y + b2 * p2.tilt_y + b3 * p3.tilt_y,
+        // Angles and the wheel would overshoot on a spline; interpolate linearly
+        ..p1.lerp_pen_axes(p2, t)
     }
 }
 
+/// Scale a dab's size and/or alpha by a taper factor
+fn scale_dab(taper: &TaperSettings, mut dab: Dab, factor: f32) -> Dab {
+    if taper.size {
+        dab.size *= factor;
+    }
+    if taper.alpha {
+        dab.alpha *= factor;
+    }
+    dab
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::brush::{
+        AngleChannel, ControlSource, CurvePoint, ShapeDynamics, SplineCurve, TaperLength,
+        TiltDynamics, TransferChannel,
+    };
 
     fn make_point(x: f32, y: f32, pressure: f32) -> RawInputPoint {
         RawInputPoint::new(x, y, pressure)
@@ -444,6 +850,26 @@ mod tests {
         assert!(dabs_high[0].size > dabs_low[0].size);
     }
 
+    #[test]
+    fn test_flow_curve_maps_pressure() {
+        let Ok(spline) = SplineCurve::new(vec![
+            CurvePoint::new(0.0, 0.0),
+            CurvePoint::new(0.5, 0.1),
+            CurvePoint::new(1.0, 1.0),
+        ]) else {
+            panic!("valid curve");
+        };
+        let mut stamper = BrushStamper::new(StamperConfig {
+            flow_curve: PressureCurve::Custom(spline),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let dabs = stamper.process_point(&make_point(0.0, 0.0, 0.5));
+
+        assert!((dabs[0].alpha - 0.1).abs() < 0.01);
+    }
+
     #[test]
     fn test_flow_affects_alpha() {
         let mut stamper = BrushStamper::new(StamperConfig {
@@ -458,6 +884,303 @@ mod tests {
         assert!((dabs[0].alpha - 0.5).abs() < 0.01);
     }
 
+    #[test]
+    fn test_transfer_flow_and_opacity() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            flow: 0.8,
+            transfer: Some(TransferDynamics {
+                flow: TransferChannel::pressure(0.0),
+                opacity: TransferChannel::pressure(0.5),
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let dabs = stamper.process_point(&make_point(0.0, 0.0, 0.5));
+        assert!((dabs[0].alpha - 0.4).abs() < 0.01);
+        assert!((dabs[0].opacity - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_transfer_flow_uses_flow_curve() {
+        let Ok(spline) = SplineCurve::new(vec![
+            CurvePoint::new(0.0, 0.0),
+            CurvePoint::new(0.5, 0.1),
+            CurvePoint::new(1.0, 1.0),
+        ]) else {
+            panic!("valid curve");
+        };
+        let mut stamper = BrushStamper::new(StamperConfig {
+            flow_curve: PressureCurve::Custom(spline),
+            transfer: Some(TransferDynamics {
+                flow: TransferChannel::pressure(0.2),
+                opacity: TransferChannel::pressure(0.5),
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let dabs = stamper.process_point(&make_point(0.0, 0.0, 0.5));
+        // Flow reads the curved pressure (0.1); opacity reads raw pressure
+        assert!((dabs[0].alpha - 0.28).abs() < 0.01);
+        assert!((dabs[0].opacity - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_legacy_alpha_has_full_opacity() {
+        let mut stamper = BrushStamper::new(StamperConfig::default());
+        stamper.begin_stroke();
+
+        let dabs = stamper.process_point(&make_point(0.0, 0.0, 0.3));
+        assert_eq!(dabs[0].opacity, 1.0);
+    }
+
+    #[test]
+    fn test_velocity_control_source() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            transfer: Some(TransferDynamics {
+                flow: TransferChannel {
+                    control: ControlSource::Velocity,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let slow = make_point(0.0, 0.0, 1.0).with_timestamp_us(0);
+        stamper.process_point(&slow);
+
+        let fast = make_point(200.0, 0.0, 1.0).with_timestamp_us(10_000);
+        let dabs = stamper.process_point(&fast);
+
+        // First dab of the stroke is stationary, later dabs see the speed
+        assert!(dabs.last().is_some_and(|d| d.alpha > 0.4));
+    }
+
+    #[test]
+    fn test_velocity_uses_microsecond_timestamps() {
+        // 240 Hz reports, 8 px apart: 1.92 px/ms, whatever the ms rounding
+        let mut stamper = BrushStamper::new(StamperConfig::default());
+        stamper.begin_stroke();
+        for i in 0..40u64 {
+            let point = make_point(i as f32 * 8.0, 0.0, 1.0).with_timestamp_us(i * 4_167);
+            stamper.process_point(&point);
+        }
+        let expected = 8.0 / 4.167 / VELOCITY_REFERENCE_PX_PER_MS;
+        assert!(
+            (stamper.velocity - expected).abs() < 1e-3,
+            "{}",
+            stamper.velocity
+        );
+    }
+
+    /// Horizontal stroke from x=0 to x=200 taking `duration_ms`
+    fn timed_stroke(config: StamperConfig, duration_ms: u64) -> Vec<Dab> {
+        let mut stamper = BrushStamper::new(config);
+        stamper.begin_stroke();
+        let mut dabs = Vec::new();
+        for i in 0..=10u64 {
+            let point =
+                make_point(i as f32 * 20.0, 0.0, 1.0).with_timestamp_us(i * duration_ms * 100);
+            dabs.extend(stamper.process_point(&point));
+        }
+        dabs
+    }
+
+    #[test]
+    fn test_ink_thinning_with_speed() {
+        let config = StamperConfig {
+            pressure_size: false,
+            shape: Some(ShapeDynamics {
+                ink_thinning: 0.5,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let slow = timed_stroke(config.clone(), 2000);
+        let fast = timed_stroke(config, 50);
+        let (Some(slow), Some(fast)) = (slow.last(), fast.last()) else {
+            panic!("strokes should emit dabs");
+        };
+
+        assert!(slow.size > 19.0, "slow size {}", slow.size);
+        assert!(fast.size < 11.0, "fast size {}", fast.size);
+    }
+
+    #[test]
+    fn test_direction_drives_angle() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            shape: Some(ShapeDynamics {
+                angle: AngleChannel {
+                    control: ControlSource::Direction,
+                    jitter: 0.0,
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        // Straight down: +y is 90 degrees
+        stamper.process_point(&make_point(0.0, 0.0, 1.0));
+        let dabs = stamper.process_point(&make_point(0.0, 50.0, 1.0));
+
+        assert!(!dabs.is_empty());
+        for dab in &dabs {
+            assert!((dab.angle - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_tilt_flattens_and_rotates_dabs() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            pressure_size: false,
+            tilt: Some(TiltDynamics::pencil()),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let upright = stamper.process_point(&make_point(0.0, 0.0, 1.0));
+        assert_eq!(upright[0].roundness, 1.0);
+        assert_eq!(upright[0].size, 20.0);
+
+        let mut leaning = make_point(10.0, 0.0, 1.0);
+        leaning.tilt_y = 70.0;
+        let dabs = stamper.process_point(&leaning);
+        let Some(dab) = dabs.last() else {
+            panic!("expected dabs");
+        };
+        assert!((dab.roundness - 0.25).abs() < 1e-3);
+        assert!((dab.size - 60.0).abs() < 0.1);
+        assert!((dab.angle - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rotation_drives_angle_and_wraps() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            shape: Some(ShapeDynamics {
+                angle: AngleChannel {
+                    control: ControlSource::Rotation,
+                    jitter: 0.0,
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let first = stamper.process_point(&make_point(0.0, 0.0, 1.0).with_rotation(Some(350.0)));
+        assert!((first[0].angle - 350f32.to_radians()).abs() < 1e-4);
+
+        // 350° -> 10° goes through 0°, not through 180°
+        let dabs = stamper.process_point(&make_point(40.0, 0.0, 1.0).with_rotation(Some(10.0)));
+        assert!(!dabs.is_empty());
+        for dab in &dabs {
+            let degrees = dab.angle.to_degrees();
+            assert!(
+                degrees >= 350.0 - 1e-3 || degrees <= 10.0 + 1e-3,
+                "{}",
+                degrees
+            );
+        }
+    }
+
+    #[test]
+    fn test_wheel_control_source() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            transfer: Some(TransferDynamics {
+                flow: TransferChannel {
+                    control: ControlSource::Wheel,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let dabs =
+            stamper.process_point(&make_point(0.0, 0.0, 1.0).with_tangential_pressure(Some(0.25)));
+        assert_eq!(dabs[0].alpha, 0.625);
+    }
+
+    #[test]
+    fn test_dual_brush_emits_secondary_dabs() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            dual_brush: Some(DualBrush {
+                size: 10.0,
+                spacing: 0.5, // 5 pixel spacing
+                scatter: 1.0,
+                count: 2,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        stamper.process_point(&make_point(0.0, 0.0, 1.0));
+        stamper.process_point(&make_point(50.0, 0.0, 1.0));
+        let dual = stamper.take_dual_dabs();
+
+        // Start point plus roughly one interval per 5 pixels, two dabs each
+        assert!(dual.len() >= 20);
+        assert!(dual.iter().all(|d| d.size == 10.0));
+        // Scatter offsets across the horizontal stroke only
+        assert!(dual.iter().all(|d| d.y.abs() <= 10.0));
+        assert!(dual.iter().any(|d| d.y.abs() > 0.5));
+        assert!(stamper.take_dual_dabs().is_empty());
+    }
+
+    #[test]
+    fn test_build_up_emits_while_stationary() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            build_up: true,
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let mut point = make_point(10.0, 10.0, 1.0).with_timestamp_us(1_000_000);
+        assert_eq!(stamper.process_point(&point).len(), 1);
+
+        // Same position 60ms later: three build-up intervals
+        point = point.with_timestamp_us(1_060_000);
+        let dabs = stamper.process_point(&point);
+        assert_eq!(dabs.len(), 3);
+        assert!(dabs.iter().all(|d| d.x == 10.0 && d.y == 10.0));
+
+        // No new input: tick keeps emitting
+        assert_eq!(stamper.tick(1_100_000).len(), 2);
+
+        // Long stall is capped
+        assert_eq!(stamper.tick(5_000_000).len(), BUILD_UP_MAX_DABS as usize);
+        assert!(stamper.tick(5_000_000).is_empty());
+    }
+
+    #[test]
+    fn test_no_build_up_when_disabled() {
+        let mut stamper = BrushStamper::new(StamperConfig::default());
+        stamper.begin_stroke();
+
+        let mut point = make_point(10.0, 10.0, 1.0).with_timestamp_us(1_000_000);
+        stamper.process_point(&point);
+        point = point.with_timestamp_us(1_100_000);
+        assert!(stamper.process_point(&point).is_empty());
+        assert!(stamper.tick(2_000_000).is_empty());
+    }
+
+    #[test]
+    fn test_no_dual_brush_no_secondary_dabs() {
+        let mut stamper = BrushStamper::new(StamperConfig::default());
+        stamper.begin_stroke();
+
+        stamper.process_point(&make_point(0.0, 0.0, 1.0));
+        stamper.process_point(&make_point(50.0, 0.0, 1.0));
+        assert!(stamper.take_dual_dabs().is_empty());
+    }
+
     #[test]
     fn test_begin_stroke_resets_state() {
         let mut stamper = BrushStamper::new(StamperConfig::default());
@@ -473,4 +1196,124 @@ mod tests {
         // Should emit initial dab again
         assert_eq!(dabs.len(), 1);
     }
+
+    #[test]
+    fn test_dab_uses_foreground_color() {
+        let mut stamper = BrushStamper::new(StamperConfig {
+            color: [1.0, 0.0, 0.0],
+            ..Default::default()
+        });
+        stamper.begin_stroke();
+
+        let dabs = stamper.process_point(&make_point(0.0, 0.0, 1.0));
+        assert_eq!(dabs[0].color, [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_color_dynamics_per_dab_vs_per_stroke() {
+        let dynamics = ColorDynamics {
+            hue_jitter: 0.5,
+            ..Default::default()
+        };
+        let config = StamperConfig {
+            color: [1.0, 0.0, 0.0],
+            color_dynamics: Some(dynamics),
+            ..Default::default()
+        };
+
+        let mut per_dab = BrushStamper::new(config.clone());
+        per_dab.begin_stroke();
+        let mut dabs = per_dab.process_point(&make_point(0.0, 0.0, 1.0));
+        dabs.extend(per_dab.process_point(&make_point(50.0, 0.0, 1.0)));
+        assert!(dabs.windows(2).any(|w| w[0].color != w[1].color));
+
+        let mut per_stroke = BrushStamper::new(StamperConfig {
+            color_dynamics: Some(ColorDynamics {
+                mode: ColorDynamicsMode::PerStroke,
+                ..dynamics
+            }),
+            ..config
+        });
+        per_stroke.begin_stroke();
+        let mut dabs = per_stroke.process_point(&make_point(0.0, 0.0, 1.0));
+        dabs.extend(per_stroke.process_point(&make_point(50.0, 0.0, 1.0)));
+        assert!(dabs.windows(2).all(|w| w[0].color == w[1].color));
+    }
+
+    /// Draw a straight 100px stroke and return (live dabs, finish dabs)
+    fn draw_line(config: StamperConfig) -> (Vec<Dab>, Vec<Dab>) {
+        let mut stamper = BrushStamper::new(config);
+        stamper.begin_stroke();
+
+        let mut live = Vec::new();
+        for i in 0..=50 {
+            live.extend(stamper.process_point(&make_point(i as f32 * 2.0, 0.0, 1.0)));
+        }
+        (live, stamper.finish_stroke())
+    }
+
+    #[test]
+    fn test_taper_in_and_out_pixels() {
+        let (untapered, rest) = draw_line(StamperConfig::default());
+        assert!(rest.is_empty());
+
+        let (live, tail) = draw_line(StamperConfig {
+            taper: Some(TaperSettings {
+                taper_in: Some(TaperLength::Pixels(20.0)),
+                taper_out: Some(TaperLength::Pixels(20.0)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        // Same dabs, only the tail is held back until finish
+        assert!(!tail.is_empty());
+        let tapered: Vec<Dab> = live.iter().chain(&tail).copied().collect();
+        assert_eq!(tapered.len(), untapered.len());
+        assert!(tail.iter().all(|d| d.x >= 100.0 - 20.0 - 1e-3));
+
+        assert_eq!(tapered[0].size, 0.0);
+        assert!(tapered[1].size > 0.0 && tapered[1].size < 20.0);
+        assert!(tapered.iter().any(|d| d.size == 20.0));
+        assert!(tapered.windows(2).take(3).all(|w| w[1].size > w[0].size));
+        let last = tapered[tapered.len() - 1];
+        assert!(last.size < 20.0 * (100.0 - last.x + 1e-3) / 20.0 + 1e-3);
+        assert!(tapered.iter().all(|d| d.alpha == 1.0));
+    }
+
+    #[test]
+    fn test_taper_percent_holds_back_only_the_tail() {
+        let config = StamperConfig {
+            taper: Some(TaperSettings {
+                taper_out: Some(TaperLength::Percent(0.5)),
+                size: false,
+                alpha: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (live, tail) = draw_line(config.clone());
+        // The first half is stamped live, only the possible tail waits
+        assert!(!live.is_empty());
+        assert!(live.iter().all(|d| d.x <= 50.0 && d.alpha == 1.0));
+        assert!(tail.iter().all(|d| d.x >= 50.0 - 1e-3));
+
+        let all: Vec<Dab> = live.iter().chain(&tail).copied().collect();
+        assert!(all.iter().all(|d| d.size == 20.0));
+        assert_eq!(all[0].alpha, 1.0);
+        // The second half of the stroke fades out linearly
+        assert!(all.iter().filter(|d| d.x < 45.0).all(|d| d.alpha == 1.0));
+        assert!(all.windows(2).all(|w| w[1].alpha <= w[0].alpha));
+        let last = all[all.len() - 1];
+        assert!(last.alpha < 0.1, "{}", last.alpha);
+
+        let (live, tail) = draw_line(config);
+        let again: Vec<Dab> = live.into_iter().chain(tail).collect();
+        assert_eq!(again.len(), all.len());
+        assert!(again
+            .iter()
+            .zip(&all)
+            .all(|(a, b)| a.x == b.x && a.alpha == b.alpha));
+    }
 }
@@ -3,8 +3,79 @@
 //! The stroke buffer is the key to achieving Photoshop-like brush behavior:
 //! - Flow controls individual dab opacity (accumulates within stroke)
 //! - Opacity acts as a ceiling (maximum alpha for the entire stroke)
+//!
+//! With transfer dynamics the ceiling varies per dab: each pixel keeps the
+//! highest opacity multiplier of the dabs that touched it.
+//!
+//! With a dual brush, secondary dabs are stamped into a coverage mask first;
+//! the primary tip shape is combined with that mask before it is blended.
+//!
+//! A texture pattern is sampled by absolute pixel coordinate: per dab when
+//! "texture each tip" is on, otherwise once when the stroke is composited.
+//!
+//! Noise adds fixed per-pixel grain to soft dab edges. Wet edges run a
+//! composite pass in `end_stroke` that lowers the stroke interior relative
+//! to its rim.
+//!
+//! `StrokeConstraints` (lock alpha, selection, clipping parent) are applied
+//! while compositing and only read inside the dirty rect.
+
+use super::blend::{blend_normal_premul, BlendFunc};
+use super::dual_brush::DualBrush;
+use super::stamper::Dab;
+use super::texture::TextureSettings;
+
+/// Alpha multiplier for the stroke interior with wet edges
+const WET_EDGE_INTERIOR: f32 = 0.5;
+
+/// Width of the wet edge rim in pixels
+const WET_EDGE_RADIUS: usize = 3;
+
+/// Maximum alpha change from noise at the outer edge of a dab
+const NOISE_STRENGTH: f32 = 0.5;
+
+/// Optional limits on where a stroke may change the layer
+///
+/// Masks cover the whole layer (one value per pixel, row-major) but are only
+/// read inside the stroke's dirty rect. Missing entries count as 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrokeConstraints<'a> {
+    /// Preserve transparency: keep the layer's alpha, only change color
+    pub lock_alpha: bool,
+    /// 8-bit selection coverage scaling the stroke alpha
+    pub selection: Option<&'a [u8]>,
+    /// RGBA data of the clipping-mask parent; its alpha scales the stroke alpha
+    pub clip_parent: Option<&'a [u8]>,
+}
+
+impl StrokeConstraints<'_> {
+    /// Alpha multiplier (0-1) at a layer pixel index
+    #[inline]
+    fn coverage(&self, idx: usize) -> f32 {
+        let mut coverage = 1.0;
+        if let Some(selection) = self.selection {
+            coverage *= selection.get(idx).map_or(0.0, |&v| v as f32 / 255.0);
+        }
+        if let Some(parent) = self.clip_parent {
+            coverage *= parent.get(idx * 4 + 3).map_or(0.0, |&v| v as f32 / 255.0);
+        }
+        coverage
+    }
+}
 
-use super::blend::blend_normal_premul;
+/// Zero `values` (one per pixel, row-major) inside a clamped rect
+fn zero_rect(values: &mut [f32], width: u32, rect: Rect) {
+    if rect.is_empty() {
+        return;
+    }
+    for y in rect.top..rect.bottom {
+        let row = (y as u32 * width) as usize;
+        let span = row + rect.left as usize..row + rect.right as usize;
+        if let Some(values) = values.get_mut(span) {
+            values.fill(0.0);
+        }
+    }
+}
 
 /// A simple rectangle for dirty region tracking
 #[derive(Debug, Clone, Copy, Default)]
@@ -134,6 +205,25 @@ pub struct StrokeBuffer {
     dirty_rect: Rect,
     /// Whether a stroke is currently active
     active: bool,
+    /// Per-pixel opacity ceiling multiplier, allocated on the first dab
+    /// with opacity below 1.0 and kept for later strokes; only touched
+    /// inside the dirty rect and zero elsewhere
+    opacity_map: Vec<f32>,
+    /// The opacity map is in use for this stroke (false = full ceiling)
+    opacity_active: bool,
+    /// Secondary tip settings (None = no dual brush)
+    dual_brush: Option<DualBrush>,
+    /// Secondary tip coverage, allocated on the first secondary dab and
+    /// kept for later strokes; zero outside `dual_rect`
+    dual_mask: Vec<f32>,
+    /// Region of the dual mask written by secondary dabs this stroke
+    dual_rect: Rect,
+    /// Pattern texture (None = no texture)
+    texture: Option<TextureSettings>,
+    /// Darken the stroke rim relative to its interior
+    wet_edges: bool,
+    /// Add grain to soft dab edges
+    noise: bool,
 }
 
 impl StrokeBuffer {
@@ -146,6 +236,14 @@ impl StrokeBuffer {
             data: vec![Pixel::transparent(); size],
             dirty_rect: Rect::empty(),
             active: false,
+            opacity_map: Vec::new(),
+            opacity_active: false,
+            dual_brush: None,
+            dual_mask: Vec::new(),
+            dual_rect: Rect::empty(),
+            texture: None,
+            wet_edges: false,
+            noise: false,
         }
     }
 
@@ -157,6 +255,10 @@ impl StrokeBuffer {
         self.data = vec![Pixel::transparent(); size];
         self.dirty_rect = Rect::empty();
         self.active = false;
+        self.opacity_map = Vec::new();
+        self.opacity_active = false;
+        self.dual_mask = Vec::new();
+        self.dual_rect = Rect::empty();
     }
 
     /// Begin a new stroke
@@ -170,7 +272,40 @@ impl StrokeBuffer {
         for pixel in &mut self.data {
             *pixel = Pixel::transparent();
         }
+        if std::mem::take(&mut self.opacity_active) {
+            let rect = self.clamped_dirty_rect();
+            zero_rect(&mut self.opacity_map, self.width, rect);
+        }
         self.dirty_rect = Rect::empty();
+        self.clear_dual_mask();
+    }
+
+    /// Set the dual brush used to modulate primary dabs (None = disabled)
+    pub fn set_dual_brush(&mut self, dual_brush: Option<DualBrush>) {
+        self.dual_brush = dual_brush;
+        self.clear_dual_mask();
+    }
+
+    /// Zero the part of the dual mask written this stroke
+    fn clear_dual_mask(&mut self) {
+        let mut rect = std::mem::replace(&mut self.dual_rect, Rect::empty());
+        rect.clamp_to(self.width as i32, self.height as i32);
+        zero_rect(&mut self.dual_mask, self.width, rect);
+    }
+
+    /// Set the pattern texture (None = disabled)
+    pub fn set_texture(&mut self, texture: Option<TextureSettings>) {
+        self.texture = texture;
+    }
+
+    /// Enable or disable wet edges
+    pub fn set_wet_edges(&mut self, enabled: bool) {
+        self.wet_edges = enabled;
+    }
+
+    /// Enable or disable edge noise
+    pub fn set_noise(&mut self, enabled: bool) {
+        self.noise = enabled;
     }
 
     /// Check if stroke is active
@@ -196,6 +331,8 @@ impl StrokeBuffer {
         if let Some(p) = self.data.get_mut(idx) {
             *p = pixel;
         }
+        self.dirty_rect.expand(x as i32, y as i32, 0);
+        self.mark_full_opacity(idx);
     }
 
     /// Blend a pixel into the buffer using standard alpha blending
@@ -207,6 +344,54 @@ impl StrokeBuffer {
         if let Some(dst) = self.data.get_mut(idx) {
             *dst = blend_normal_premul(src, *dst);
         }
+        self.dirty_rect.expand(x as i32, y as i32, 0);
+        self.mark_full_opacity(idx);
+    }
+
+    /// Raise the opacity ceiling of a pixel to full
+    fn mark_full_opacity(&mut self, idx: usize) {
+        if self.opacity_active {
+            if let Some(ceiling) = self.opacity_map.get_mut(idx) {
+                *ceiling = 1.0;
+            }
+        }
+    }
+
+    /// Start using the opacity map, giving pixels already painted a full
+    /// ceiling; only the dirty rect is scanned
+    fn ensure_opacity_map(&mut self) {
+        if self.opacity_active {
+            return;
+        }
+        self.opacity_active = true;
+        if self.opacity_map.len() != self.data.len() {
+            self.opacity_map = vec![0.0; self.data.len()];
+        }
+        let rect = self.clamped_dirty_rect();
+        self.for_each_in(rect, |buffer, idx| {
+            if buffer.data.get(idx).is_some_and(|p| p.a > 0.0) {
+                if let Some(ceiling) = buffer.opacity_map.get_mut(idx) {
+                    *ceiling = 1.0;
+                }
+            }
+        });
+    }
+
+    /// Dirty rect clamped to the buffer
+    fn clamped_dirty_rect(&self) -> Rect {
+        let mut rect = self.dirty_rect;
+        rect.clamp_to(self.width as i32, self.height as i32);
+        rect
+    }
+
+    /// Visit each pixel index of a clamped rect
+    fn for_each_in(&mut self, rect: Rect, mut f: impl FnMut(&mut Self, usize)) {
+        for y in rect.top..rect.bottom {
+            for x in rect.left..rect.right {
+                let idx = (y as u32 * self.width + x as u32) as usize;
+                f(self, idx);
+            }
+        }
     }
 
     /// Stamp a circular dab onto the buffer
@@ -226,6 +411,32 @@ impl StrokeBuffer {
         alpha: f32,
         hardness: f32,
     ) {
+        self.stamp_dab_with_opacity(cx, cy, radius, color, alpha, hardness, 1.0, 1.0, 0.0, 1.0);
+    }
+
+    /// Stamp an elliptical dab with a per-dab opacity ceiling multiplier
+    /// and texture depth multiplier
+    ///
+    /// `radius` is the major semi-axis along `angle`; the minor semi-axis
+    /// is `radius * roundness`.
+    #[allow(clippy::too_many_arguments)]
+    fn stamp_dab_with_opacity(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        color: [f32; 3],
+        alpha: f32,
+        hardness: f32,
+        opacity: f32,
+        texture_depth: f32,
+        angle: f32,
+        roundness: f32,
+    ) {
+        if opacity < 1.0 {
+            self.ensure_opacity_map();
+        }
+
         let r = radius.max(0.5);
         let left = (cx - r).floor() as i32;
         let top = (cy - r).floor() as i32;
@@ -240,6 +451,12 @@ impl StrokeBuffer {
         let inner_radius = r * hardness;
         let fade_width = r - inner_radius;
 
+        let (sin, cos) = angle.sin_cos();
+        let minor_scale = 1.0 / roundness.clamp(0.01, 1.0);
+
+        let dual_mode = self.dual_brush.as_ref().map(|d| d.mode);
+        let tip_texture = self.texture.as_ref().filter(|t| t.each_tip);
+
         for py in top..=bottom {
             if py < 0 || py >= self.height as i32 {
                 continue;
@@ -251,21 +468,43 @@ impl StrokeBuffer {
 
                 let dx = px as f32 + 0.5 - cx;
                 let dy = py as f32 + 0.5 - cy;
-                let dist = (dx * dx + dy * dy).sqrt();
+                // Distance in dab space (minor axis stretched to a circle)
+                let along = dx * cos + dy * sin;
+                let across = (dy * cos - dx * sin) * minor_scale;
+                let dist = (along * along + across * across).sqrt();
 
                 if dist > r {
                     continue;
                 }
 
                 // Calculate falloff
-                let dab_alpha = if dist <= inner_radius {
-                    alpha
+                let mut shape = if dist <= inner_radius {
+                    1.0
                 } else if fade_width > 0.001 {
-                    alpha * (1.0 - (dist - inner_radius) / fade_width)
+                    1.0 - (dist - inner_radius) / fade_width
                 } else {
-                    alpha
+                    1.0
                 };
 
+                let idx = (py as u32 * self.width + px as u32) as usize;
+
+                // Modulate by the secondary tip mask
+                if let Some(mode) = dual_mode {
+                    let secondary = self.dual_mask.get(idx).copied().unwrap_or(0.0);
+                    shape = mode.blend(shape, secondary);
+                }
+
+                if let Some(texture) = tip_texture {
+                    shape *= texture.modulate(px, py, texture_depth);
+                }
+
+                // Grain grows toward the outer edge; the solid core is untouched
+                if self.noise && shape < 1.0 {
+                    let grain = (pixel_noise(px, py) * 2.0 - 1.0) * NOISE_STRENGTH * (1.0 - shape);
+                    shape = (shape + grain).clamp(0.0, 1.0);
+                }
+
+                let dab_alpha = alpha * shape;
                 if dab_alpha < 0.001 {
                     continue;
                 }
@@ -278,7 +517,55 @@ impl StrokeBuffer {
                     a: dab_alpha,
                 };
 
-                self.blend_pixel(px as u32, py as u32, src);
+                if let Some(dst) = self.data.get_mut(idx) {
+                    *dst = blend_normal_premul(src, *dst);
+                }
+                if self.opacity_active {
+                    if let Some(ceiling) = self.opacity_map.get_mut(idx) {
+                        *ceiling = ceiling.max(opacity);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stamp a secondary (dual brush) dab into the dual mask
+    ///
+    /// Does nothing unless a dual brush is set. Secondary dabs must be
+    /// stamped before the primary dabs they should modulate.
+    pub fn stamp_dual(&mut self, dab: &Dab) {
+        let Some(dual) = &self.dual_brush else {
+            return;
+        };
+
+        let size = (self.width * self.height) as usize;
+        if self.dual_mask.len() != size {
+            self.dual_mask = vec![0.0; size];
+        }
+        let mask = &mut self.dual_mask;
+
+        let r = (dab.size * 0.5).max(0.5);
+        let left = ((dab.x - r).floor() as i32).max(0);
+        let top = ((dab.y - r).floor() as i32).max(0);
+        let right = ((dab.x + r).ceil() as i32).min(self.width as i32 - 1);
+        let bottom = ((dab.y + r).ceil() as i32).min(self.height as i32 - 1);
+        self.dual_rect
+            .union(&Rect::new(left, top, right + 1, bottom + 1));
+
+        for py in top..=bottom {
+            for px in left..=right {
+                let u = (px as f32 + 0.5 - dab.x) / r;
+                let v = (py as f32 + 0.5 - dab.y) / r;
+                let coverage = dual.coverage(u, v) * dab.alpha;
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                // Union of secondary dabs ("over" accumulation)
+                let idx = (py as u32 * self.width + px as u32) as usize;
+                if let Some(m) = mask.get_mut(idx) {
+                    *m += coverage * (1.0 - *m);
+                }
             }
         }
     }
@@ -287,11 +574,25 @@ impl StrokeBuffer {
     ///
     /// # Arguments
     /// * `layer_data` - Target layer RGBA data (will be modified)
-    /// * `opacity` - Maximum opacity (ceiling) for this stroke
+    /// * `opacity` - Maximum opacity (ceiling) for this stroke, scaled per
+    ///   pixel by the dab opacity multipliers
+    /// * `blend` - Blend mode used to composite the clamped stroke onto the layer
     ///
     /// # Returns
     /// The dirty rectangle that was modified
-    pub fn end_stroke(&mut self, layer_data: &mut [u8], opacity: f32) -> Rect {
+    pub fn end_stroke(&mut self, layer_data: &mut [u8], opacity: f32, blend: BlendFunc) -> Rect {
+        self.end_stroke_constrained(layer_data, opacity, blend, &StrokeConstraints::default())
+    }
+
+    /// End the stroke like `end_stroke`, limited by lock alpha, a selection
+    /// and/or a clipping parent
+    pub fn end_stroke_constrained(
+        &mut self,
+        layer_data: &mut [u8],
+        opacity: f32,
+        blend: BlendFunc,
+        constraints: &StrokeConstraints,
+    ) -> Rect {
         if !self.active {
             return Rect::empty();
         }
@@ -299,13 +600,15 @@ impl StrokeBuffer {
         self.active = false;
 
         // Clamp dirty rect to buffer bounds
-        let mut rect = self.dirty_rect;
-        rect.clamp_to(self.width as i32, self.height as i32);
-
+        let rect = self.clamped_dirty_rect();
         if rect.is_empty() {
             return Rect::empty();
         }
 
+        let stroke_texture = self.texture.as_ref().filter(|t| !t.each_tip);
+        let wet_factors = self.wet_edges.then(|| self.wet_edge_factors(&rect));
+        let rect_width = (rect.right - rect.left) as usize;
+
         // Composite stroke buffer to layer with opacity ceiling
         for y in rect.top..rect.bottom {
             for x in rect.left..rect.right {
@@ -317,7 +620,23 @@ impl StrokeBuffer {
                 }
 
                 // Apply opacity ceiling
-                let clamped_alpha = stroke_pixel.a.min(opacity);
+                let ceiling = if self.opacity_active {
+                    self.opacity_map.get(idx).map_or(opacity, |&m| opacity * m)
+                } else {
+                    opacity
+                };
+                let mut clamped_alpha = stroke_pixel.a.min(ceiling);
+                if let Some(texture) = stroke_texture {
+                    clamped_alpha *= texture.modulate(x, y, 1.0);
+                }
+                if let Some(factors) = &wet_factors {
+                    let local = (y - rect.top) as usize * rect_width + (x - rect.left) as usize;
+                    clamped_alpha *= factors.get(local).copied().unwrap_or(1.0);
+                }
+                clamped_alpha *= constraints.coverage(idx);
+                if clamped_alpha < 0.001 {
+                    continue;
+                }
                 let clamped_pixel = stroke_pixel.with_alpha(clamped_alpha);
 
                 // Get layer pixel
@@ -333,8 +652,19 @@ impl StrokeBuffer {
                     layer_data[layer_idx + 3],
                 );
 
+                if constraints.lock_alpha && layer_pixel.a < 0.001 {
+                    continue;
+                }
+
                 // Blend stroke onto layer
-                let result = blend_normal_premul(clamped_pixel, layer_pixel);
+                let mut result = blend.apply(clamped_pixel, layer_pixel);
+                if constraints.lock_alpha {
+                    result = if result.a < 0.001 {
+                        layer_pixel
+                    } else {
+                        result.with_alpha(layer_pixel.a)
+                    };
+                }
                 let rgba = result.to_rgba_u8();
 
                 layer_data[layer_idx] = rgba[0];
@@ -347,6 +677,38 @@ impl StrokeBuffer {
         rect
     }
 
+    /// Wet edge alpha multipliers for each pixel of `rect` (row-major)
+    ///
+    /// Compares stroke alpha with its local average: pixels near the rim
+    /// (average below their own alpha) keep full alpha, the interior is
+    /// reduced to `WET_EDGE_INTERIOR`.
+    fn wet_edge_factors(&self, rect: &Rect) -> Vec<f32> {
+        let w = (rect.right - rect.left) as usize;
+        let h = (rect.bottom - rect.top) as usize;
+
+        let mut alpha = Vec::with_capacity(w * h);
+        for y in rect.top..rect.bottom {
+            for x in rect.left..rect.right {
+                let idx = (y as u32 * self.width + x as u32) as usize;
+                alpha.push(self.data.get(idx).map_or(0.0, |p| p.a));
+            }
+        }
+
+        let average = box_blur(&alpha, w, h, WET_EDGE_RADIUS);
+
+        alpha
+            .iter()
+            .zip(&average)
+            .map(|(&a, &b)| {
+                if a < 0.001 {
+                    return 1.0;
+                }
+                let rim = ((a - b) / a * 2.0).clamp(0.0, 1.0);
+                WET_EDGE_INTERIOR + (1.0 - WET_EDGE_INTERIOR) * rim
+            })
+            .collect()
+    }
+
     /// Get the dirty rectangle
     pub fn dirty_rect(&self) -> Rect {
         self.dirty_rect
@@ -358,10 +720,72 @@ impl StrokeBuffer {
     }
 }
 
+/// Separable box blur treating everything outside the grid as zero
+fn box_blur(values: &[f32], w: usize, h: usize, radius: usize) -> Vec<f32> {
+    let norm = 1.0 / (2 * radius + 1) as f32;
+
+    let blur_line = |get: &dyn Fn(usize) -> f32, len: usize, out: &mut dyn FnMut(usize, f32)| {
+        let mut sum: f32 = (0..radius.min(len)).map(get).sum();
+        for i in 0..len {
+            if i + radius < len {
+                sum += get(i + radius);
+            }
+            out(i, sum * norm);
+            if i >= radius {
+                sum -= get(i - radius);
+            }
+        }
+    };
+
+    let mut horizontal = vec![0.0; values.len()];
+    for y in 0..h {
+        let row = &values[y * w..(y + 1) * w];
+        blur_line(&|x| row[x], w, &mut |x, v| horizontal[y * w + x] = v);
+    }
+
+    let mut result = vec![0.0; values.len()];
+    for x in 0..w {
+        blur_line(&|y| horizontal[y * w + x], h, &mut |y, v| {
+            result[y * w + x] = v
+        });
+    }
+
+    result
+}
+
+/// Deterministic per-pixel noise in [0, 1)
+fn pixel_noise(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x9e37_79b1) ^ (y as u32).wrapping_mul(0x85eb_ca77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^= h >> 15;
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    impl StrokeBuffer {
+        /// Stamp a `BrushStamper` dab with its own color and opacity
+        fn stamp(&mut self, dab: &Dab, hardness: f32) {
+            self.stamp_dab_with_opacity(
+                dab.x,
+                dab.y,
+                dab.size * 0.5,
+                dab.color,
+                dab.alpha,
+                hardness,
+                dab.opacity,
+                dab.texture_depth,
+                dab.angle,
+                dab.roundness,
+            );
+        }
+    }
+
     #[test]
     fn test_stroke_buffer_creation() {
         let buffer = StrokeBuffer::new(100, 100);
@@ -376,7 +800,7 @@ mod tests {
         assert!(buffer.is_active());
 
         let mut layer_data = vec![0u8; 100 * 100 * 4];
-        buffer.end_stroke(&mut layer_data, 1.0);
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Normal);
         assert!(!buffer.is_active());
     }
 
@@ -409,7 +833,7 @@ mod tests {
 
         // Composite with opacity ceiling of 0.5
         let mut layer_data = vec![0u8; 10 * 10 * 4];
-        buffer.end_stroke(&mut layer_data, 0.5);
+        buffer.end_stroke(&mut layer_data, 0.5, BlendFunc::Normal);
 
         // Check that layer pixel alpha is capped at ~0.5
         let idx = (5 * 10 + 5) * 4;
@@ -417,6 +841,206 @@ mod tests {
         assert!(alpha <= 0.55); // Allow small tolerance
     }
 
+    #[test]
+    fn test_end_stroke_blend_modes_pixel_exact() {
+        let layer_rgba = [60u8, 180, 120, 200];
+        let idx = (5 * 10 + 5) * 4;
+
+        // Stroke (0.9, 0.3, 0.6) at opacity 0.5 over the layer, computed
+        // independently from the W3C compositing formulas
+        let expected: [(BlendFunc, [u8; 4]); 27] = [
+            (BlendFunc::Normal, [154, 121, 138, 227]),
+            (BlendFunc::Multiply, [77, 112, 102, 227]),
+            (BlendFunc::Screen, [157, 177, 159, 227]),
+            (BlendFunc::Overlay, [101, 154, 134, 227]),
+            (BlendFunc::Darken, [80, 121, 123, 227]),
+            (BlendFunc::Lighten, [154, 167, 138, 227]),
+            (BlendFunc::ColorDodge, [166, 200, 183, 227]),
+            (BlendFunc::ColorBurn, [70, 90, 84, 227]),
+            (BlendFunc::HardLight, [149, 135, 135, 227]),
+            (BlendFunc::SoftLight, [102, 158, 128, 227]),
+            (BlendFunc::Difference, [128, 133, 85, 227]),
+            (BlendFunc::Exclusion, [133, 153, 127, 227]),
+            (BlendFunc::LinearBurn, [69, 89, 79, 227]),
+            (BlendFunc::LinearDodge, [166, 200, 183, 227]),
+            (BlendFunc::VividLight, [166, 145, 137, 227]),
+            (BlendFunc::LinearLight, [166, 122, 146, 227]),
+            (BlendFunc::PinLight, [143, 155, 123, 227]),
+            (BlendFunc::HardMix, [166, 200, 183, 227]),
+            (BlendFunc::Subtract, [54, 133, 71, 227]),
+            (BlendFunc::Divide, [83, 200, 159, 227]),
+            (BlendFunc::Hue, [148, 130, 139, 227]),
+            (BlendFunc::Saturation, [71, 172, 121, 227]),
+            (BlendFunc::Color, [157, 124, 141, 227]),
+            (BlendFunc::Luminosity, [77, 164, 121, 227]),
+            (BlendFunc::Behind, [80, 167, 123, 227]),
+            (BlendFunc::Erase, [60, 179, 120, 100]),
+            (BlendFunc::Clear, [60, 179, 120, 100]),
+        ];
+        assert_eq!(expected.map(|(mode, _)| mode), BlendFunc::ALL);
+
+        for (mode, rgba) in expected {
+            let mut buffer = StrokeBuffer::new(10, 10);
+            buffer.begin_stroke();
+            buffer.stamp_dab(5.0, 5.0, 5.0, [0.9, 0.3, 0.6], 1.0, 1.0);
+
+            let mut layer_data = layer_rgba.repeat(100);
+            buffer.end_stroke(&mut layer_data, 0.5, mode);
+            assert_eq!(layer_data[idx..idx + 4], rgba, "{:?}", mode);
+
+            // Pixels outside the stroke are untouched
+            assert_eq!(layer_data[0..4], layer_rgba, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_end_stroke_multiply_on_white() {
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        buffer.stamp_dab(5.0, 5.0, 5.0, [1.0, 0.0, 0.0], 1.0, 1.0);
+
+        let mut multiply = [255u8; 10 * 10 * 4];
+        buffer.end_stroke(&mut multiply, 1.0, BlendFunc::Multiply);
+        let idx = (5 * 10 + 5) * 4;
+        assert_eq!(multiply[idx..idx + 4], [255, 0, 0, 255]);
+
+        // Multiply over black stays black, unlike normal painting
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        buffer.stamp_dab(5.0, 5.0, 5.0, [1.0, 0.0, 0.0], 1.0, 1.0);
+        let mut black = [0u8, 0, 0, 255].repeat(100);
+        buffer.end_stroke(&mut black, 1.0, BlendFunc::Multiply);
+        assert_eq!(black[idx..idx + 4], [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_end_stroke_erase_uses_opacity_ceiling() {
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        // Low flow accumulates past the ceiling
+        for _ in 0..10 {
+            buffer.stamp_dab(5.0, 5.0, 5.0, [0.0, 0.0, 0.0], 0.3, 1.0);
+        }
+
+        let mut layer_data = [0u8, 0, 255, 255].repeat(100);
+        buffer.end_stroke(&mut layer_data, 0.5, BlendFunc::Erase);
+
+        let idx = (5 * 10 + 5) * 4;
+        assert_eq!(layer_data[idx..idx + 4], [0, 0, 255, 127]);
+        assert_eq!(layer_data[0..4], [0, 0, 255, 255]);
+
+        // Behind leaves an opaque layer untouched
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        buffer.stamp_dab(5.0, 5.0, 5.0, [1.0, 0.0, 0.0], 1.0, 1.0);
+        let mut layer_data = [0u8, 0, 255, 255].repeat(100);
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Behind);
+        assert_eq!(layer_data[idx..idx + 4], [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_end_stroke_clear_uses_flow_and_opacity() {
+        let idx = (5 * 10 + 5) * 4;
+
+        // Low flow accumulates past the ceiling, as with Erase
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        for _ in 0..10 {
+            buffer.stamp_dab(5.0, 5.0, 5.0, [0.0, 0.0, 0.0], 0.3, 1.0);
+        }
+        let mut layer_data = [0u8, 0, 255, 255].repeat(100);
+        buffer.end_stroke(&mut layer_data, 0.5, BlendFunc::Clear);
+        assert_eq!(layer_data[idx..idx + 4], [0, 0, 255, 127]);
+        assert_eq!(layer_data[0..4], [0, 0, 255, 255]);
+
+        // A single low-flow dab only clears by its flow
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        buffer.stamp_dab(5.0, 5.0, 5.0, [1.0, 0.0, 0.0], 0.3, 1.0);
+        let mut layer_data = [0u8, 0, 255, 255].repeat(100);
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Clear);
+        assert_eq!(layer_data[idx..idx + 4], [0, 0, 255, 178]);
+    }
+
+    /// Full-coverage red dab over a 10x10 buffer
+    fn red_stroke() -> StrokeBuffer {
+        let mut buffer = StrokeBuffer::new(10, 10);
+        buffer.begin_stroke();
+        buffer.stamp_dab(5.0, 5.0, 8.0, [1.0, 0.0, 0.0], 1.0, 1.0);
+        buffer
+    }
+
+    #[test]
+    fn test_lock_alpha_preserves_transparency() {
+        // Left half transparent, right half half-transparent blue
+        let mut layer_data = vec![0u8; 10 * 10 * 4];
+        for y in 0..10 {
+            for x in 5..10 {
+                let idx = (y * 10 + x) * 4;
+                layer_data[idx..idx + 4].copy_from_slice(&[0, 0, 255, 128]);
+            }
+        }
+
+        let constraints = StrokeConstraints {
+            lock_alpha: true,
+            ..Default::default()
+        };
+        red_stroke().end_stroke_constrained(&mut layer_data, 1.0, BlendFunc::Normal, &constraints);
+
+        let left = (5 * 10 + 3) * 4;
+        let right = (5 * 10 + 6) * 4;
+        assert_eq!(layer_data[left..left + 4], [0, 0, 0, 0]);
+        assert_eq!(layer_data[right..right + 4], [255, 0, 0, 128]);
+
+        // Erasing with locked alpha leaves the layer unchanged
+        let mut buffer = red_stroke();
+        let before = layer_data.clone();
+        buffer.end_stroke_constrained(&mut layer_data, 1.0, BlendFunc::Erase, &constraints);
+        assert_eq!(layer_data, before);
+    }
+
+    #[test]
+    fn test_selection_scales_stroke_alpha() {
+        // Rows 0-4 unselected, row 5 half selected, rows 6-9 fully selected
+        let mut selection = vec![0u8; 10 * 10];
+        selection[50..60].fill(128);
+        selection[60..].fill(255);
+
+        let constraints = StrokeConstraints {
+            selection: Some(&selection),
+            ..Default::default()
+        };
+        let mut layer_data = vec![0u8; 10 * 10 * 4];
+        red_stroke().end_stroke_constrained(&mut layer_data, 1.0, BlendFunc::Normal, &constraints);
+
+        assert_eq!(layer_data[(4 * 10 + 5) * 4 + 3], 0);
+        assert_eq!(layer_data[(5 * 10 + 5) * 4 + 3], 128);
+        assert_eq!(layer_data[(6 * 10 + 5) * 4 + 3], 255);
+    }
+
+    #[test]
+    fn test_clip_parent_alpha() {
+        // Parent is opaque only in column 5
+        let mut parent = vec![0u8; 10 * 10 * 4];
+        for y in 0..10 {
+            parent[(y * 10 + 5) * 4 + 3] = 255;
+        }
+
+        let constraints = StrokeConstraints {
+            clip_parent: Some(&parent),
+            ..Default::default()
+        };
+        let mut layer_data = vec![0u8; 10 * 10 * 4];
+        red_stroke().end_stroke_constrained(&mut layer_data, 1.0, BlendFunc::Normal, &constraints);
+
+        assert_eq!(
+            layer_data[(5 * 10 + 5) * 4..(5 * 10 + 6) * 4],
+            [255, 0, 0, 255]
+        );
+        assert_eq!(layer_data[(5 * 10 + 4) * 4 + 3], 0);
+        assert_eq!(layer_data[(5 * 10 + 6) * 4 + 3], 0);
+    }
+
     #[test]
     fn test_flow_accumulation() {
         let mut buffer = StrokeBuffer::new(20, 20);
@@ -432,6 +1056,259 @@ mod tests {
         assert!(center.a > 0.5);
     }
 
+    #[test]
+    fn test_stamp_elliptical_dab() {
+        let mut buffer = StrokeBuffer::new(40, 40);
+        buffer.begin_stroke();
+
+        // Long axis vertical, half as wide as tall
+        buffer.stamp(
+            &Dab {
+                x: 20.0,
+                y: 20.0,
+                size: 20.0,
+                alpha: 1.0,
+                opacity: 1.0,
+                angle: std::f32::consts::FRAC_PI_2,
+                roundness: 0.5,
+                pressure: 1.0,
+                color: [0.0, 0.0, 0.0],
+                texture_depth: 1.0,
+            },
+            1.0,
+        );
+
+        assert!(buffer.get_pixel(20, 28).a > 0.9);
+        assert!(buffer.get_pixel(20, 12).a > 0.9);
+        assert!(buffer.get_pixel(23, 20).a > 0.9);
+        assert_eq!(buffer.get_pixel(27, 20).a, 0.0);
+        assert_eq!(buffer.get_pixel(12, 20).a, 0.0);
+    }
+
+    #[test]
+    fn test_stamp_honors_dab_color() {
+        let mut buffer = StrokeBuffer::new(40, 20);
+        buffer.begin_stroke();
+
+        let dab = Dab {
+            x: 10.0,
+            y: 10.0,
+            size: 8.0,
+            alpha: 1.0,
+            opacity: 1.0,
+            angle: 0.0,
+            roundness: 1.0,
+            pressure: 1.0,
+            color: [1.0, 0.0, 0.0],
+            texture_depth: 1.0,
+        };
+        buffer.stamp(&dab, 1.0);
+        buffer.stamp(
+            &Dab {
+                x: 30.0,
+                color: [0.0, 0.0, 1.0],
+                ..dab
+            },
+            1.0,
+        );
+
+        let red = buffer.get_pixel(10, 10);
+        let blue = buffer.get_pixel(30, 10);
+        assert!(red.r > 0.9 && red.b < 0.01);
+        assert!(blue.b > 0.9 && blue.r < 0.01);
+    }
+
+    #[test]
+    fn test_dab_opacity_varies_ceiling() {
+        let mut buffer = StrokeBuffer::new(40, 20);
+        buffer.begin_stroke();
+
+        let dab = Dab {
+            x: 10.0,
+            y: 10.0,
+            size: 8.0,
+            alpha: 1.0,
+            opacity: 1.0,
+            angle: 0.0,
+            roundness: 1.0,
+            pressure: 1.0,
+            color: [1.0, 1.0, 1.0],
+            texture_depth: 1.0,
+        };
+        buffer.stamp(&dab, 1.0);
+        buffer.stamp(
+            &Dab {
+                x: 30.0,
+                opacity: 0.5,
+                ..dab
+            },
+            1.0,
+        );
+
+        let mut layer_data = vec![0u8; 40 * 20 * 4];
+        buffer.end_stroke(&mut layer_data, 0.8, BlendFunc::Normal);
+
+        let full = layer_data[(10 * 40 + 10) * 4 + 3] as f32 / 255.0;
+        let half = layer_data[(10 * 40 + 30) * 4 + 3] as f32 / 255.0;
+        assert!((full - 0.8).abs() < 0.01);
+        assert!((half - 0.4).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_opacity_map_reset_between_strokes() {
+        let mut buffer = StrokeBuffer::new(40, 20);
+        let dab = Dab {
+            x: 10.0,
+            y: 10.0,
+            size: 8.0,
+            alpha: 1.0,
+            opacity: 0.8,
+            angle: 0.0,
+            roundness: 1.0,
+            pressure: 1.0,
+            color: [1.0, 1.0, 1.0],
+            texture_depth: 1.0,
+        };
+        buffer.begin_stroke();
+        buffer.stamp(&dab, 1.0);
+        let mut layer_data = vec![0u8; 40 * 20 * 4];
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Normal);
+
+        // The map is reused; the first stroke's ceiling must not carry over
+        buffer.begin_stroke();
+        buffer.stamp(
+            &Dab {
+                opacity: 0.3,
+                ..dab
+            },
+            1.0,
+        );
+        let mut layer_data = vec![0u8; 40 * 20 * 4];
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Normal);
+
+        let alpha = layer_data[(10 * 40 + 10) * 4 + 3] as f32 / 255.0;
+        assert!((alpha - 0.3).abs() < 0.01, "{}", alpha);
+        buffer.clear();
+        assert!(buffer.opacity_map.iter().all(|&m| m == 0.0));
+    }
+
+    #[test]
+    fn test_dual_brush_masks_primary_dab() {
+        let mut buffer = StrokeBuffer::new(40, 20);
+        buffer.set_dual_brush(Some(DualBrush {
+            size: 6.0,
+            ..Default::default()
+        }));
+        buffer.begin_stroke();
+
+        let dab = Dab {
+            x: 10.0,
+            y: 10.0,
+            size: 16.0,
+            alpha: 1.0,
+            opacity: 1.0,
+            angle: 0.0,
+            roundness: 1.0,
+            pressure: 1.0,
+            color: [1.0, 1.0, 1.0],
+            texture_depth: 1.0,
+        };
+        buffer.stamp_dual(&Dab {
+            x: 6.0,
+            size: 6.0,
+            ..dab
+        });
+        buffer.stamp(&dab, 1.0);
+
+        // Primary shows only where the secondary tip landed
+        assert!(buffer.get_pixel(6, 10).a > 0.9);
+        assert!(buffer.get_pixel(14, 10).a < 0.001);
+
+        // The mask is kept for the next stroke but starts out empty
+        let allocation = buffer.dual_mask.as_ptr();
+        buffer.begin_stroke();
+        assert_eq!(buffer.dual_mask.as_ptr(), allocation);
+        assert!(buffer.dual_mask.iter().all(|&m| m == 0.0));
+        buffer.stamp(&dab, 1.0);
+        assert!(buffer.get_pixel(6, 10).a < 0.001);
+    }
+
+    #[test]
+    fn test_texture_fixed_in_canvas_space() {
+        // 2x1 pattern: black, white; full depth
+        let texture = TextureSettings {
+            pattern: Some(std::sync::Arc::new(crate::abr::GrayscaleImage::new(
+                2,
+                1,
+                vec![0, 255],
+            ))),
+            ..Default::default()
+        };
+
+        for each_tip in [true, false] {
+            let mut buffer = StrokeBuffer::new(20, 20);
+            buffer.set_texture(Some(TextureSettings {
+                each_tip,
+                ..texture.clone()
+            }));
+            buffer.begin_stroke();
+            buffer.stamp_dab(10.0, 10.0, 5.0, [1.0, 1.0, 1.0], 1.0, 1.0);
+
+            let mut layer_data = vec![0u8; 20 * 20 * 4];
+            buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Normal);
+
+            // Even columns hit black texels, odd columns white
+            assert_eq!(layer_data[(10 * 20 + 10) * 4 + 3], 0);
+            assert!(layer_data[(10 * 20 + 11) * 4 + 3] > 250);
+        }
+    }
+
+    #[test]
+    fn test_wet_edges_darken_rim() {
+        let mut buffer = StrokeBuffer::new(40, 40);
+        buffer.set_wet_edges(true);
+        buffer.begin_stroke();
+        buffer.stamp_dab(20.0, 20.0, 12.0, [0.0, 0.0, 0.0], 1.0, 1.0);
+
+        let mut layer_data = vec![0u8; 40 * 40 * 4];
+        buffer.end_stroke(&mut layer_data, 1.0, BlendFunc::Normal);
+
+        let alpha_at = |x: usize, y: usize| layer_data[(y * 40 + x) * 4 + 3] as f32 / 255.0;
+        let interior = alpha_at(20, 20);
+        let rim = alpha_at(30, 20);
+
+        assert!((interior - WET_EDGE_INTERIOR).abs() < 0.02);
+        assert!(rim > interior + 0.2);
+    }
+
+    #[test]
+    fn test_noise_only_affects_soft_edge() {
+        let mut plain = StrokeBuffer::new(40, 40);
+        let mut noisy = StrokeBuffer::new(40, 40);
+        noisy.set_noise(true);
+
+        for buffer in [&mut plain, &mut noisy] {
+            buffer.begin_stroke();
+            buffer.stamp_dab(20.0, 20.0, 15.0, [1.0, 1.0, 1.0], 1.0, 0.3);
+        }
+
+        // Solid core unchanged
+        assert_eq!(plain.get_pixel(20, 20).a, noisy.get_pixel(20, 20).a);
+
+        // Soft edge differs somewhere
+        let edge_differs =
+            (5..35).any(|x| (plain.get_pixel(x, 12).a - noisy.get_pixel(x, 12).a).abs() > 0.01);
+        assert!(edge_differs);
+    }
+
+    #[test]
+    fn test_box_blur_zero_boundary() {
+        let values = vec![1.0; 9];
+        let blurred = box_blur(&values, 3, 3, 1);
+        assert!((blurred[4] - 1.0).abs() < 1e-6);
+        assert!((blurred[0] - 4.0 / 9.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_rect_operations() {
         let mut rect = Rect::empty();
@@ -0,0 +1,151 @@
+//! Taper - thin or fade the start and end of a stroke
+//!
+//! Taper-in is given in pixels; taper-out in pixels or as a fraction of the
+//! stroke length. The stamper tags each dab with its distance along the
+//! stroke and scales size and/or alpha by `TaperSettings::factor`.
+//!
+//! - Taper-in is applied as dabs are emitted
+//! - Taper-out needs the stroke end: the stamper holds back the dabs that
+//!   may still fall in it and releases them from `finish_stroke`. For a
+//!   fraction `f` of a stroke that is `L` long so far, that is the last
+//!   `f * L`, since the final length can only grow.
+
+use serde::{Deserialize, Serialize};
+
+/// Length of a taper
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaperLength {
+    /// Fixed length in pixels
+    Pixels(f32),
+    /// Fraction of the total stroke length (0-1), taper-out only
+    Percent(f32),
+}
+
+impl TaperLength {
+    /// Length in pixels, or None if it depends on an unknown stroke length
+    pub fn resolve(&self, stroke_length: Option<f32>) -> Option<f32> {
+        match *self {
+            TaperLength::Pixels(px) => Some(px.max(0.0)),
+            TaperLength::Percent(fraction) => {
+                stroke_length.map(|total| total * fraction.clamp(0.0, 1.0))
+            }
+        }
+    }
+}
+
+/// Taper settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaperSettings {
+    /// Taper at the stroke start (None = blunt start); a percentage is
+    /// ignored since dabs are stamped before the stroke length is known
+    pub taper_in: Option<TaperLength>,
+    /// Taper at the stroke end (None = blunt end)
+    pub taper_out: Option<TaperLength>,
+    /// Scale dab size
+    pub size: bool,
+    /// Scale dab alpha
+    pub alpha: bool,
+    /// Scale reached at the very start/end (0-1)
+    pub minimum: f32,
+}
+
+impl Default for TaperSettings {
+    fn default() -> Self {
+        Self {
+            taper_in: None,
+            taper_out: None,
+            size: true,
+            alpha: false,
+            minimum: 0.0,
+        }
+    }
+}
+
+impl TaperSettings {
+    /// Distance before which no dab can end up in the taper-out, for a
+    /// stroke that is `stroke_length` long so far
+    pub fn taper_out_start(&self, stroke_length: f32) -> f32 {
+        match self.taper_out {
+            None => f32::INFINITY,
+            Some(TaperLength::Pixels(px)) => stroke_length - px.max(0.0),
+            Some(TaperLength::Percent(fraction)) => {
+                stroke_length * (1.0 - fraction.clamp(0.0, 1.0))
+            }
+        }
+    }
+
+    /// Scale (minimum-1) for a dab at `distance` along the stroke
+    ///
+    /// `stroke_length` is None while the stroke is in progress; taper-out
+    /// is then ignored.
+    pub fn factor(&self, distance: f32, stroke_length: Option<f32>) -> f32 {
+        let mut t: f32 = 1.0;
+
+        if let Some(TaperLength::Pixels(length)) = self.taper_in {
+            if length > 0.0 {
+                t = t.min(distance / length);
+            }
+        }
+
+        if let (Some(total), Some(taper_out)) = (stroke_length, self.taper_out) {
+            if let Some(length) = taper_out.resolve(Some(total)) {
+                if length > 0.0 {
+                    t = t.min((total - distance) / length);
+                }
+            }
+        }
+
+        let minimum = self.minimum.clamp(0.0, 1.0);
+        minimum + (1.0 - minimum) * t.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_taper_in_and_out() {
+        let taper = TaperSettings {
+            taper_in: Some(TaperLength::Pixels(10.0)),
+            taper_out: Some(TaperLength::Pixels(20.0)),
+            ..Default::default()
+        };
+
+        assert_eq!(taper.factor(0.0, None), 0.0);
+        assert_eq!(taper.factor(5.0, None), 0.5);
+        assert_eq!(taper.factor(50.0, None), 1.0);
+
+        // Taper-out only once the total is known
+        assert_eq!(taper.factor(90.0, Some(100.0)), 0.5);
+        assert_eq!(taper.factor(100.0, Some(100.0)), 0.0);
+        assert_eq!(taper.factor(50.0, Some(100.0)), 1.0);
+        assert_eq!(taper.taper_out_start(100.0), 80.0);
+    }
+
+    #[test]
+    fn test_percent_taper_and_minimum() {
+        let taper = TaperSettings {
+            taper_out: Some(TaperLength::Percent(0.25)),
+            minimum: 0.2,
+            ..Default::default()
+        };
+
+        // Unknown stroke length: no taper yet
+        assert_eq!(taper.factor(200.0, None), 1.0);
+        assert!((taper.factor(200.0, Some(200.0)) - 0.2).abs() < 1e-6);
+        assert!((taper.factor(175.0, Some(200.0)) - 0.6).abs() < 1e-6);
+        assert_eq!(taper.factor(140.0, Some(200.0)), 1.0);
+        // Only the last quarter of the stroke so far can still taper
+        assert_eq!(taper.taper_out_start(100.0), 75.0);
+
+        // Percentage taper-in would need the length up front: ignored
+        let taper = TaperSettings {
+            taper_in: Some(TaperLength::Percent(0.25)),
+            ..Default::default()
+        };
+        assert_eq!(taper.factor(0.0, Some(200.0)), 1.0);
+    }
+}
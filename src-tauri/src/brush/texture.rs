@@ -0,0 +1,231 @@
+//! Texture - tiling pattern modulation of brush dabs
+//!
+//! Mirrors Photoshop's Texture panel. A grayscale pattern tiles in canvas
+//! space (sampled by absolute pixel coordinate, so it stays fixed while the
+//! brush moves) and reduces alpha where the pattern is dark.
+//!
+//! - Texture each tip: applied per dab, with per-dab depth dynamics
+//! - Otherwise: applied once to the whole stroke when it is composited
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::dynamics::{DynamicsInput, JitterRng};
+use super::transfer::TransferChannel;
+use crate::abr::GrayscaleImage;
+
+/// Texture settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureSettings {
+    /// Pattern identifier (ABR pattern UUID or file name)
+    pub pattern_id: Option<String>,
+    /// Pattern scale (1.0 = 100%)
+    pub scale: f32,
+    /// Brightness shift (-1 to 1)
+    pub brightness: f32,
+    /// Contrast (-1 to 1, 0 = unchanged)
+    pub contrast: f32,
+    /// Depth: how strongly the pattern reduces alpha (0-1)
+    pub depth: f32,
+    /// Per-dab depth dynamics (only with `each_tip`)
+    pub depth_dynamics: TransferChannel,
+    /// Invert the pattern
+    pub invert: bool,
+    /// Apply per dab instead of once per stroke
+    pub each_tip: bool,
+    /// Pattern image (None = texture disabled)
+    #[serde(skip)]
+    pub pattern: Option<Arc<GrayscaleImage>>,
+}
+
+impl Default for TextureSettings {
+    fn default() -> Self {
+        Self {
+            pattern_id: None,
+            scale: 1.0,
+            brightness: 0.0,
+            contrast: 0.0,
+            depth: 1.0,
+            depth_dynamics: TransferChannel::default(),
+            invert: false,
+            each_tip: false,
+            pattern: None,
+        }
+    }
+}
+
+impl TextureSettings {
+    /// Per-dab depth multiplier (0-1) from depth dynamics
+    pub fn dab_depth(&self, input: &DynamicsInput, rng: &mut JitterRng) -> f32 {
+        if self.each_tip {
+            self.depth_dynamics.evaluate(input, rng)
+        } else {
+            1.0
+        }
+    }
+
+    /// Pattern value (0-1) at a canvas pixel, after brightness/contrast/invert
+    pub fn sample(&self, x: i32, y: i32) -> f32 {
+        let Some(pattern) = &self.pattern else {
+            return 1.0;
+        };
+        if pattern.width == 0 || pattern.height == 0 {
+            return 1.0;
+        }
+
+        let scale = self.scale.max(0.01);
+        let u = (x as f32 + 0.5) / scale - 0.5;
+        let v = (y as f32 + 0.5) / scale - 0.5;
+        let mut value = sample_wrapped(pattern, u, v);
+
+        value = (value - 0.5) * (1.0 + self.contrast.clamp(-1.0, 1.0)) + 0.5;
+        value = (value + self.brightness.clamp(-1.0, 1.0)).clamp(0.0, 1.0);
+
+        if self.invert {
+            1.0 - value
+        } else {
+            value
+        }
+    }
+
+    /// Alpha multiplier (0-1) at a canvas pixel for the given depth multiplier
+    pub fn modulate(&self, x: i32, y: i32, depth_scale: f32) -> f32 {
+        let depth = (self.depth * depth_scale).clamp(0.0, 1.0);
+        if depth <= 0.0 || self.pattern.is_none() {
+            return 1.0;
+        }
+        1.0 - depth * (1.0 - self.sample(x, y))
+    }
+}
+
+/// Bilinearly sample a pattern, tiling in both directions
+fn sample_wrapped(pattern: &GrayscaleImage, u: f32, v: f32) -> f32 {
+    let w = pattern.width as i64;
+    let h = pattern.height as i64;
+
+    let x0 = u.floor();
+    let y0 = v.floor();
+    let fx = u - x0;
+    let fy = v - y0;
+
+    let texel = |tx: i64, ty: i64| -> f32 {
+        let px = tx.rem_euclid(w) as u32;
+        let py = ty.rem_euclid(h) as u32;
+        pattern.get_pixel(px, py).map_or(1.0, |p| p as f32 / 255.0)
+    };
+
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1, y0) * fx;
+    let bottom = texel(x0, y0 + 1) * (1.0 - fx) + texel(x0 + 1, y0 + 1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+/// Decode a PNG (or any format supported by `image`) into a grayscale pattern
+pub fn pattern_from_image_bytes(data: &[u8]) -> Result<GrayscaleImage, String> {
+    let image = image::load_from_memory(data).map_err(|e| e.to_string())?;
+    let gray = image.to_luma8();
+    Ok(GrayscaleImage::new(
+        gray.width(),
+        gray.height(),
+        gray.into_raw(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brush::ControlSource;
+
+    /// 2x1 pattern: black, white
+    fn checker() -> TextureSettings {
+        TextureSettings {
+            pattern: Some(Arc::new(GrayscaleImage::new(2, 1, vec![0, 255]))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sample_tiles_in_canvas_space() {
+        let texture = checker();
+        assert_eq!(texture.sample(0, 0), 0.0);
+        assert_eq!(texture.sample(1, 0), 1.0);
+        assert_eq!(texture.sample(2, 7), 0.0);
+        assert_eq!(texture.sample(-1, 0), 1.0);
+    }
+
+    #[test]
+    fn test_scale_invert_and_brightness() {
+        let scaled = TextureSettings {
+            scale: 2.0,
+            ..checker()
+        };
+        assert!(scaled.sample(0, 0) < 0.3);
+        assert!(scaled.sample(3, 0) > 0.7);
+
+        let inverted = TextureSettings {
+            invert: true,
+            ..checker()
+        };
+        assert_eq!(inverted.sample(0, 0), 1.0);
+
+        let bright = TextureSettings {
+            brightness: 0.5,
+            ..checker()
+        };
+        assert_eq!(bright.sample(0, 0), 0.5);
+    }
+
+    #[test]
+    fn test_modulate_depth() {
+        let texture = TextureSettings {
+            depth: 0.5,
+            ..checker()
+        };
+        assert_eq!(texture.modulate(0, 0, 1.0), 0.5);
+        assert_eq!(texture.modulate(1, 0, 1.0), 1.0);
+        assert_eq!(texture.modulate(0, 0, 0.0), 1.0);
+        assert_eq!(TextureSettings::default().modulate(0, 0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_dab_depth_pressure_control() {
+        let texture = TextureSettings {
+            each_tip: true,
+            depth_dynamics: TransferChannel {
+                control: ControlSource::Pressure,
+                minimum: 0.2,
+                ..Default::default()
+            },
+            ..checker()
+        };
+        let mut rng = JitterRng::new(1);
+
+        let light = texture.dab_depth(&DynamicsInput::new(0.0, 0.0, 0.0, 0), &mut rng);
+        let heavy = texture.dab_depth(&DynamicsInput::new(1.0, 0.0, 0.0, 0), &mut rng);
+        assert!((light - 0.2).abs() < 1e-6);
+        assert!((heavy - 1.0).abs() < 1e-6);
+
+        let per_stroke = TextureSettings {
+            each_tip: false,
+            ..texture
+        };
+        let input = DynamicsInput::new(0.0, 0.0, 0.0, 0);
+        assert_eq!(per_stroke.dab_depth(&input, &mut rng), 1.0);
+    }
+
+    #[test]
+    fn test_pattern_from_png() {
+        let gray = image::GrayImage::from_raw(2, 1, vec![10, 200]).unwrap_or_default();
+        let mut png = Vec::new();
+        let encoded = gray.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png);
+        assert!(encoded.is_ok());
+
+        let Ok(pattern) = pattern_from_image_bytes(&png) else {
+            panic!("PNG should decode");
+        };
+        assert_eq!((pattern.width, pattern.height), (2, 1));
+        assert_eq!(pattern.data, vec![10, 200]);
+    }
+}
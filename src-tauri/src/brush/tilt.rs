@@ -0,0 +1,176 @@
+//! Tilt dynamics - pen tilt drives dab angle, roundness and size
+//!
+//! Tablets report tilt as two angles (`tilt_x`, `tilt_y`, degrees from
+//! vertical). They are converted to azimuth (direction the pen leans) and
+//! altitude (angle above the surface) as in the W3C Pointer Events spec.
+//!
+//! - Azimuth sets the dab angle, so the long axis follows the pen
+//! - Leaning the pen (lower altitude) flattens the dab and grows it, like
+//!   shading with the side of a pencil
+//!
+//! Tilt below the dead-zone is treated as upright, which hides sensor noise
+//! around vertical. The remaining range is mapped through a curve.
+
+use std::f32::consts::FRAC_PI_2;
+
+use serde::{Deserialize, Serialize};
+
+use super::PressureCurve;
+
+/// Largest tilt component used (tan(90°) is infinite)
+const MAX_TILT_DEG: f32 = 89.9;
+
+/// Pen orientation derived from tilt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PenOrientation {
+    /// Direction the pen leans, radians counter-clockwise from +x
+    pub azimuth: f32,
+    /// Angle between pen and surface, radians (π/2 = upright)
+    pub altitude: f32,
+}
+
+impl PenOrientation {
+    /// Convert tilt angles (degrees, -90 to 90) to azimuth/altitude
+    pub fn from_tilt(tilt_x: f32, tilt_y: f32) -> Self {
+        let tan_x = tilt_x.clamp(-MAX_TILT_DEG, MAX_TILT_DEG).to_radians().tan();
+        let tan_y = tilt_y.clamp(-MAX_TILT_DEG, MAX_TILT_DEG).to_radians().tan();
+        let lean = (tan_x * tan_x + tan_y * tan_y).sqrt();
+
+        Self {
+            azimuth: tan_y.atan2(tan_x),
+            altitude: if lean > 0.0 {
+                (1.0 / lean).atan()
+            } else {
+                FRAC_PI_2
+            },
+        }
+    }
+
+    /// Angle of the pen away from vertical, in degrees (0 = upright)
+    pub fn lean_degrees(&self) -> f32 {
+        (FRAC_PI_2 - self.altitude).to_degrees()
+    }
+}
+
+/// Tilt dynamics settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TiltDynamics {
+    /// Dab angle follows the tilt azimuth
+    pub angle: bool,
+    /// Roundness reached at full lean (0-1, 1 = no flattening)
+    pub min_roundness: f32,
+    /// Size multiplier reached at full lean (1 = no change)
+    pub max_size_scale: f32,
+    /// Lean (degrees from vertical) treated as upright
+    pub dead_zone: f32,
+    /// Lean at which the effect is complete (degrees from vertical)
+    pub full_lean: f32,
+    /// Curve mapping normalized lean (0-1) to effect strength
+    pub curve: PressureCurve,
+}
+
+impl Default for TiltDynamics {
+    fn default() -> Self {
+        Self {
+            angle: true,
+            min_roundness: 1.0,
+            max_size_scale: 1.0,
+            dead_zone: 5.0,
+            full_lean: 60.0,
+            curve: PressureCurve::Linear,
+        }
+    }
+}
+
+impl TiltDynamics {
+    /// Krita-like pencil shading: flat, wide dabs when leaning
+    pub fn pencil() -> Self {
+        Self {
+            min_roundness: 0.25,
+            max_size_scale: 3.0,
+            ..Default::default()
+        }
+    }
+
+    /// Effect strength (0-1) after dead-zone and curve
+    pub fn strength(&self, orientation: &PenOrientation) -> f32 {
+        let dead_zone = self.dead_zone.clamp(0.0, 89.0);
+        let range = (self.full_lean - dead_zone).max(1e-3);
+        let lean = ((orientation.lean_degrees() - dead_zone) / range).clamp(0.0, 1.0);
+        self.curve.apply(lean)
+    }
+
+    /// Dab angle, or `fallback` when disabled or the pen is within the dead-zone
+    pub fn dab_angle(&self, orientation: &PenOrientation, fallback: f32) -> f32 {
+        if self.angle && orientation.lean_degrees() > self.dead_zone {
+            orientation.azimuth
+        } else {
+            fallback
+        }
+    }
+
+    /// Dab roundness (1 = circle)
+    pub fn roundness(&self, strength: f32) -> f32 {
+        let min = self.min_roundness.clamp(0.01, 1.0);
+        1.0 - (1.0 - min) * strength
+    }
+
+    /// Dab size multiplier
+    pub fn size_scale(&self, strength: f32) -> f32 {
+        1.0 + (self.max_size_scale.max(0.0) - 1.0) * strength
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientation_from_tilt() {
+        let upright = PenOrientation::from_tilt(0.0, 0.0);
+        assert_eq!(upright.altitude, FRAC_PI_2);
+        assert_eq!(upright.lean_degrees(), 0.0);
+
+        let right = PenOrientation::from_tilt(45.0, 0.0);
+        assert!(right.azimuth.abs() < 1e-6);
+        assert!((right.lean_degrees() - 45.0).abs() < 1e-3);
+
+        let down = PenOrientation::from_tilt(0.0, 30.0);
+        assert!((down.azimuth - FRAC_PI_2).abs() < 1e-6);
+        assert!((down.lean_degrees() - 30.0).abs() < 1e-3);
+
+        // Both components add up to a steeper lean
+        let diagonal = PenOrientation::from_tilt(30.0, 30.0);
+        assert!(diagonal.lean_degrees() > 30.0);
+        assert!((diagonal.azimuth - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dead_zone_and_full_lean() {
+        let tilt = TiltDynamics::pencil();
+
+        let near_vertical = PenOrientation::from_tilt(3.0, 0.0);
+        assert_eq!(tilt.strength(&near_vertical), 0.0);
+        assert_eq!(tilt.dab_angle(&near_vertical, 0.5), 0.5);
+
+        let flat = PenOrientation::from_tilt(0.0, -70.0);
+        assert_eq!(tilt.strength(&flat), 1.0);
+        assert!((tilt.dab_angle(&flat, 0.5) + FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(tilt.roundness(1.0), 0.25);
+        assert_eq!(tilt.size_scale(1.0), 3.0);
+
+        let half = PenOrientation::from_tilt(32.5, 0.0);
+        assert!((tilt.strength(&half) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_curve_shapes_strength() {
+        let half = PenOrientation::from_tilt(32.5, 0.0);
+        let hard = TiltDynamics {
+            curve: PressureCurve::Hard,
+            ..TiltDynamics::pencil()
+        };
+        assert!((hard.strength(&half) - 0.25).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,113 @@
+//! Transfer dynamics - per-dab flow and opacity variation
+//!
+//! Mirrors Photoshop's Transfer panel. Each channel has a control source,
+//! a minimum (the floor reached when the control value is 0) and jitter.
+//!
+//! - Flow scales the alpha of each dab (accumulates within the stroke)
+//! - Opacity scales the stroke ceiling under each dab (see `StrokeBuffer`)
+
+use serde::{Deserialize, Serialize};
+
+use super::dynamics::{ControlSource, DynamicsInput, JitterRng};
+
+/// A single dynamic channel (flow or opacity)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferChannel {
+    /// Control source driving the channel
+    pub control: ControlSource,
+    /// Random reduction amount (0-1)
+    pub jitter: f32,
+    /// Value reached when the control value is 0 (0-1)
+    pub minimum: f32,
+}
+
+impl Default for TransferChannel {
+    fn default() -> Self {
+        Self {
+            control: ControlSource::Off,
+            jitter: 0.0,
+            minimum: 0.0,
+        }
+    }
+}
+
+impl TransferChannel {
+    /// Channel driven by pen pressure with the given minimum
+    pub fn pressure(minimum: f32) -> Self {
+        Self {
+            control: ControlSource::Pressure,
+            minimum,
+            ..Default::default()
+        }
+    }
+
+    /// Evaluate the channel multiplier (0.0 - 1.0) for a dab
+    pub fn evaluate(&self, input: &DynamicsInput, rng: &mut JitterRng) -> f32 {
+        let minimum = self.minimum.clamp(0.0, 1.0);
+        let controlled = minimum + (1.0 - minimum) * self.control.value(input);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let value = if jitter > 0.0 {
+            controlled * (1.0 - jitter * rng.next_f32())
+        } else {
+            controlled
+        };
+
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// Transfer dynamics settings
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferDynamics {
+    /// Flow channel (per-dab alpha)
+    pub flow: TransferChannel,
+    /// Opacity channel (per-dab stroke ceiling)
+    pub opacity: TransferChannel,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_off_is_full() {
+        let channel = TransferChannel::default();
+        let mut rng = JitterRng::new(1);
+        let input = DynamicsInput::new(0.2, 0.0, 0.0, 0);
+
+        assert_eq!(channel.evaluate(&input, &mut rng), 1.0);
+    }
+
+    #[test]
+    fn test_channel_pressure_minimum() {
+        let channel = TransferChannel::pressure(0.25);
+        let mut rng = JitterRng::new(1);
+
+        let zero = channel.evaluate(&DynamicsInput::new(0.0, 0.0, 0.0, 0), &mut rng);
+        let half = channel.evaluate(&DynamicsInput::new(0.5, 0.0, 0.0, 0), &mut rng);
+        let full = channel.evaluate(&DynamicsInput::new(1.0, 0.0, 0.0, 0), &mut rng);
+
+        assert!((zero - 0.25).abs() < 0.001);
+        assert!((half - 0.625).abs() < 0.001);
+        assert!((full - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_channel_jitter_only_reduces() {
+        let channel = TransferChannel {
+            jitter: 0.5,
+            ..Default::default()
+        };
+        let mut rng = JitterRng::new(3);
+        let input = DynamicsInput::default();
+
+        let values: Vec<f32> = (0..100)
+            .map(|_| channel.evaluate(&input, &mut rng))
+            .collect();
+        assert!(values.iter().all(|&v| (0.5..=1.0).contains(&v)));
+        assert!(values.iter().any(|&v| v < 0.9));
+    }
+}
@@ -1,14 +1,16 @@
 //! Tauri commands - IPC interface between frontend and backend
 
-use crate::brush::{BrushEngine, StrokeSegment};
+use crate::brush::{BrushEngine, PressureCurve, SharedPressureCurve, StrokeSegment};
 use crate::input::wintab_spike::SpikeResult;
 use crate::input::{
-    PressureCurve, PressureSmoother, RawInputPoint, TabletBackend, TabletConfig, TabletEvent,
-    TabletInfo, TabletStatus,
+    CalibrationProfile, CalibrationRecorder, CalibrationStore, CoordinateMapper, InputRecorder,
+    InputSpace, Monitor, PointerType, PressureSmoother, RawInputPoint, TabletBackend, TabletConfig,
+    TabletEvent, TabletInfo, TabletMapping, TabletStatus, ToolInfo, Viewport, WindowGeometry,
 };
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex, OnceLock};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 #[cfg(target_os = "windows")]
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
@@ -73,7 +75,8 @@ pub fn process_stroke(points: Vec<RawInputPoint>) -> Result<Vec<StrokeSegment>,
         return Ok(vec![]);
     }
 
-    let engine = BrushEngine::default();
+    let mut engine = BrushEngine::default();
+    engine.begin_stroke();
     let segments = engine.process(&points);
 
     Ok(segments)
@@ -120,23 +123,69 @@ static TABLET_STATE: OnceLock<Arc<Mutex<TabletState>>> = OnceLock::new();
 pub enum BackendType {
     WinTab,
     PointerEvent,
+    /// Linux evdev (/dev/input)
+    Evdev,
+    /// Generated strokes, for tests and demos without a tablet
+    Synthetic,
     Auto,
 }
 
+/// Pressure calibration for the detected device
+#[derive(Default)]
+struct CalibrationState {
+    /// Saved profiles
+    store: CalibrationStore,
+    /// Name of the detected tablet
+    device: Option<String>,
+    /// Serial of the pen in proximity
+    serial: Option<u64>,
+    /// Guided scribble in progress
+    recorder: Option<CalibrationRecorder>,
+}
+
+impl CalibrationState {
+    /// Saved profile for the current device and pen
+    fn profile(&self) -> Option<&CalibrationProfile> {
+        self.store.find(self.device.as_deref()?, self.serial)
+    }
+
+    /// Calibration stage for the current device and pen (linear without a profile)
+    fn curve(&self) -> PressureCurve {
+        self.profile()
+            .map_or(PressureCurve::Linear, CalibrationProfile::to_curve)
+    }
+}
+
+/// Receives processed tablet events (the frontend, or a test)
+type EventSink = Arc<dyn Fn(&TabletEvent) + Send + Sync>;
+
 /// Tablet state holding the active backend
 struct TabletState {
     backend_type: BackendType,
     wintab: Option<crate::input::WinTabBackend>,
     pointer: Option<crate::input::PointerEventBackend>,
+    evdev: Option<crate::input::EvdevBackend>,
+    synthetic: Option<crate::input::SyntheticBackend>,
     config: TabletConfig,
-    app_handle: Option<AppHandle>,
+    /// Where the emitter thread sends events
+    event_sink: Option<EventSink>,
+    /// Where pressure calibration profiles are saved
+    calibration_file: Option<PathBuf>,
     emitter_running: bool,
     /// Pressure smoother for first-stroke issue (shared with emitter thread)
     pressure_smoother: Arc<Mutex<PressureSmoother>>,
     /// Track if pen is currently drawing (pressure > 0)
     is_drawing: Arc<std::sync::atomic::AtomicBool>,
-    /// Pressure curve for mapping (applied after smoothing)
-    pressure_curve: Arc<std::sync::atomic::AtomicU8>,
+    /// Pressure curve for mapping (applied after smoothing, shared with emitter thread)
+    pressure_curve: SharedPressureCurve,
+    /// Device calibration (dead-zone/ceiling), applied before the pressure curve
+    calibration_curve: SharedPressureCurve,
+    /// Active input recording, fed raw backend events by the emitter thread
+    recorder: Arc<Mutex<Option<InputRecorder>>>,
+    /// Tablet-to-canvas mapping (shared with emitter thread)
+    mapper: Arc<Mutex<CoordinateMapper>>,
+    /// Pressure calibration (shared with emitter thread)
+    calibration: Arc<Mutex<CalibrationState>>,
 }
 
 impl TabletState {
@@ -145,12 +194,19 @@ impl TabletState {
             backend_type: BackendType::Auto,
             wintab: None,
             pointer: None,
+            evdev: None,
+            synthetic: None,
             config: TabletConfig::default(),
-            app_handle: None,
+            event_sink: None,
+            calibration_file: None,
             emitter_running: false,
             pressure_smoother: Arc::new(Mutex::new(PressureSmoother::new(3))),
             is_drawing: Arc::new(std::sync::atomic::AtomicBool::new(false)),
-            pressure_curve: Arc::new(std::sync::atomic::AtomicU8::new(0)), // 0=Linear
+            pressure_curve: SharedPressureCurve::default(),
+            calibration_curve: SharedPressureCurve::default(),
+            recorder: Arc::new(Mutex::new(None)),
+            mapper: Arc::new(Mutex::new(CoordinateMapper::default())),
+            calibration: Arc::new(Mutex::new(CalibrationState::default())),
         }
     }
 
@@ -158,10 +214,14 @@ impl TabletState {
         match self.backend_type {
             BackendType::WinTab => self.wintab.as_mut().map(|b| b as &mut dyn TabletBackend),
             BackendType::PointerEvent => self.pointer.as_mut().map(|b| b as &mut dyn TabletBackend),
+            BackendType::Evdev => self.evdev.as_mut().map(|b| b as &mut dyn TabletBackend),
+            BackendType::Synthetic => self.synthetic.as_mut().map(|b| b as &mut dyn TabletBackend),
             BackendType::Auto => {
-                // Prefer WinTab if available
+                // Prefer WinTab, then evdev, if available
                 if self.wintab.is_some() {
                     self.wintab.as_mut().map(|b| b as &mut dyn TabletBackend)
+                } else if self.evdev.is_some() {
+                    self.evdev.as_mut().map(|b| b as &mut dyn TabletBackend)
                 } else {
                     self.pointer.as_mut().map(|b| b as &mut dyn TabletBackend)
                 }
@@ -176,6 +236,14 @@ fn get_tablet_state() -> Arc<Mutex<TabletState>> {
         .clone()
 }
 
+/// Where pressure calibration profiles are saved
+fn calibration_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("tablet_calibration.json"))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
 /// Tablet status response for frontend
 #[derive(Debug, Clone, Serialize)]
 pub struct TabletStatusResponse {
@@ -185,9 +253,14 @@ pub struct TabletStatusResponse {
 }
 
 /// Initialize tablet input system
+///
+/// `pressure_curve` names a preset (see `PressureCurve::from_name`). It is
+/// applied by the emitter after smoothing for every backend; Soft is the
+/// brush ease-out curve, not the `sqrt` the PointerEvent backend used to
+/// apply on its own. The frontend does not persist this setting.
 #[tauri::command]
-pub fn init_tablet(
-    app: AppHandle,
+pub fn init_tablet<R: Runtime>(
+    app: AppHandle<R>,
     backend: Option<BackendType>,
     polling_rate: Option<u32>,
     pressure_curve: Option<String>,
@@ -195,11 +268,23 @@ pub fn init_tablet(
     let state = get_tablet_state();
     let mut state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
 
-    // Store app handle for event emission
-    state.app_handle = Some(app.clone());
+    // Send events to the frontend
+    let emitter = app.clone();
+    state.event_sink = Some(Arc::new(move |event: &TabletEvent| {
+        if let Err(e) = emitter.emit("tablet-event", event) {
+            tracing::error!("[Tablet] Failed to emit event: {}", e);
+        }
+    }));
+    state.calibration_file = calibration_path(&app)
+        .map_err(|e| tracing::warn!("[Tablet] {}", e))
+        .ok();
 
     // If already initialized with a working backend, return current status (idempotent)
-    if state.wintab.is_some() || state.pointer.is_some() {
+    if state.wintab.is_some()
+        || state.evdev.is_some()
+        || state.pointer.is_some()
+        || state.synthetic.is_some()
+    {
         tracing::info!("[Tablet] Already initialized, returning current status");
         let (status, backend_name, info) = if let Some(b) = state.active_backend() {
             (b.status(), b.name().to_string(), b.info().cloned())
@@ -242,27 +327,24 @@ pub fn init_tablet(
 
     // Configure
     state.config.polling_rate_hz = polling_rate.unwrap_or(200);
-    let curve = match pressure_curve.as_deref() {
-        Some("soft") => PressureCurve::Soft,
-        Some("hard") => PressureCurve::Hard,
-        Some("scurve") => PressureCurve::SCurve,
-        _ => PressureCurve::Linear,
-    };
+    let curve = pressure_curve
+        .as_deref()
+        .and_then(PressureCurve::from_name)
+        .unwrap_or_default();
+    state.pressure_curve.store(&curve);
     state.config.pressure_curve = curve;
-    // Store curve type as atomic for thread-safe access (0=Linear, 1=Soft, 2=Hard, 3=SCurve)
-    let curve_id = match curve {
-        PressureCurve::Linear => 0u8,
-        PressureCurve::Soft => 1u8,
-        PressureCurve::Hard => 2u8,
-        PressureCurve::SCurve => 3u8,
-    };
-    state
-        .pressure_curve
-        .store(curve_id, std::sync::atomic::Ordering::Relaxed);
 
     let requested_backend = backend.unwrap_or(BackendType::Auto);
     state.backend_type = requested_backend;
 
+    // Synthetic backend is only used when explicitly requested
+    if requested_backend == BackendType::Synthetic {
+        let mut synthetic = crate::input::SyntheticBackend::default();
+        synthetic.init(&state.config)?;
+        state.synthetic = Some(synthetic);
+        tracing::info!("[Tablet] Initialized Synthetic backend");
+    }
+
     // Try WinTab first if requested or auto
     if matches!(requested_backend, BackendType::WinTab | BackendType::Auto) {
         let mut wintab = crate::input::WinTabBackend::new();
@@ -280,8 +362,24 @@ pub fn init_tablet(
         }
     }
 
+    // Linux: read the pen from /dev/input
+    if state.wintab.is_none() && matches!(requested_backend, BackendType::Evdev | BackendType::Auto)
+    {
+        let mut evdev = crate::input::EvdevBackend::new();
+        match evdev.init(&state.config) {
+            Ok(()) => {
+                state.evdev = Some(evdev);
+                state.backend_type = BackendType::Evdev;
+                tracing::info!("[Tablet] Initialized evdev backend");
+            }
+            Err(e) => tracing::debug!("[Tablet] evdev unavailable: {}", e),
+        }
+    }
+
     // Fall back to PointerEvent if WinTab not available or specifically requested
-    if state.wintab.is_none() || matches!(requested_backend, BackendType::PointerEvent) {
+    if (state.wintab.is_none() && state.evdev.is_none() && state.synthetic.is_none())
+        || matches!(requested_backend, BackendType::PointerEvent)
+    {
         let mut pointer = crate::input::PointerEventBackend::new();
         if pointer.init(&state.config).is_ok() {
             state.pointer = Some(pointer);
@@ -303,6 +401,26 @@ pub fn init_tablet(
         return Err("No tablet backend available".to_string());
     };
 
+    // Select the device's mapping
+    if let Ok(mut mapper) = state.mapper.lock() {
+        mapper.device = info.as_ref().map(|info| info.name.clone());
+    }
+
+    // Load the device's pressure calibration, if any
+    if let Ok(mut calibration) = state.calibration.lock() {
+        if let Some(path) = state.calibration_file.as_ref() {
+            match CalibrationStore::load(path) {
+                Ok(store) => calibration.store = store,
+                Err(e) => tracing::warn!("[Tablet] {}", e),
+            }
+        }
+        calibration.device = info.as_ref().map(|info| info.name.clone());
+        if let Some(profile) = calibration.profile() {
+            tracing::info!("[Tablet] Using pressure calibration for {}", profile.device);
+        }
+        state.calibration_curve.store(&calibration.curve());
+    }
+
     Ok(TabletStatusResponse {
         status,
         backend: backend_name,
@@ -310,6 +428,67 @@ pub fn init_tablet(
     })
 }
 
+/// Replace the tablet-level pressure curve (preset or custom spline)
+///
+/// Takes effect immediately on the emitter thread without reinitializing.
+#[tauri::command]
+pub fn set_pressure_curve(curve: PressureCurve) -> Result<(), String> {
+    let state = get_tablet_state();
+    let mut state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    state.pressure_curve.store(&curve);
+    state.config.pressure_curve = curve;
+    Ok(())
+}
+
+/// Update the monitor layout and the window's position on the desktop
+#[tauri::command]
+pub fn set_display_layout(monitors: Vec<Monitor>, window: WindowGeometry) -> Result<(), String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut mapper = state
+        .mapper
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    mapper.monitors = monitors;
+    mapper.window = window;
+    Ok(())
+}
+
+/// Update the canvas viewport (pan, zoom, rotation)
+#[tauri::command]
+pub fn set_viewport(viewport: Viewport) -> Result<(), String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut mapper = state
+        .mapper
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    mapper.viewport = viewport;
+    Ok(())
+}
+
+/// Set the tablet mapping for a device (or the default when `device` is None)
+#[tauri::command]
+pub fn set_tablet_mapping(device: Option<String>, mapping: TabletMapping) -> Result<(), String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut mapper = state
+        .mapper
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    match device {
+        Some(device) => {
+            mapper.mappings.devices.insert(device, mapping);
+        }
+        None => mapper.mappings.default = mapping,
+    }
+    Ok(())
+}
+
 /// Start tablet input streaming
 #[tauri::command]
 pub fn start_tablet() -> Result<(), String> {
@@ -322,16 +501,22 @@ pub fn start_tablet() -> Result<(), String> {
 
     // Start event emitter thread if not running
     if !state.emitter_running {
-        if let Some(app) = state.app_handle.clone() {
+        if let Some(emit) = state.event_sink.clone() {
             state.emitter_running = true;
             let state_clone = get_tablet_state();
             // Clone Arc handles for the emitter thread
             let pressure_smoother = state.pressure_smoother.clone();
             let is_drawing = state.is_drawing.clone();
+            let pressure_curve = state.pressure_curve.clone();
+            let calibration_curve = state.calibration_curve.clone();
+            let recorder = state.recorder.clone();
+            let mapper = state.mapper.clone();
+            let calibration = state.calibration.clone();
 
             std::thread::spawn(move || {
                 tracing::info!("[Tablet] Event emitter thread started");
                 let mut events = Vec::with_capacity(64);
+                let mut input_space = InputSpace::Canvas;
                 let mut events_to_emit = Vec::with_capacity(64);
 
                 loop {
@@ -349,6 +534,7 @@ pub fn start_tablet() -> Result<(), String> {
 
                         if let Some(backend) = state.active_backend() {
                             backend.poll(&mut events);
+                            input_space = backend.input_space();
                         }
                         true
                     };
@@ -360,16 +546,40 @@ pub fn start_tablet() -> Result<(), String> {
 
                     // Step 2: Process and emit events OUTSIDE the lock
                     if !events.is_empty() {
+                        // Record raw events so replays run the whole pipeline
+                        if let Ok(mut recorder) = recorder.lock() {
+                            if let Some(recorder) = recorder.as_mut() {
+                                for event in &events {
+                                    recorder.record(event);
+                                }
+                            }
+                        }
+
                         // Process events with pressure smoothing
                         for event in events.drain(..) {
                             let processed_event = match event {
                                 TabletEvent::Input(mut point) => {
+                                    // Map to canvas space before any processing
+                                    if let Ok(mapper) = mapper.lock() {
+                                        point = mapper.map_point(point, input_space);
+                                    }
+
+                                    // Calibration sees raw pressure
+                                    if let Ok(mut calibration) = calibration.lock() {
+                                        if let Some(recorder) = calibration.recorder.as_mut() {
+                                            recorder.record(point.pressure);
+                                        }
+                                    }
+
                                     let was_drawing =
                                         is_drawing.load(std::sync::atomic::Ordering::Relaxed);
                                     let now_drawing = point.pressure > 0.0;
+                                    is_drawing
+                                        .store(now_drawing, std::sync::atomic::Ordering::Relaxed);
 
-                                    // Apply pressure smoothing when drawing
-                                    // Note: Pressure curve is applied in frontend, not here
+                                    // Apply pressure smoothing when drawing, then the device
+                                    // calibration and the tablet-level curve (brush curves
+                                    // are applied later)
                                     if now_drawing {
                                         if let Ok(mut smoother) = pressure_smoother.lock() {
                                             if !was_drawing {
@@ -377,10 +587,10 @@ pub fn start_tablet() -> Result<(), String> {
                                             }
                                             point.pressure = smoother.smooth(point.pressure);
                                         }
+                                        point.pressure = pressure_curve
+                                            .apply(calibration_curve.apply(point.pressure));
                                     }
 
-                                    is_drawing
-                                        .store(now_drawing, std::sync::atomic::Ordering::Relaxed);
                                     TabletEvent::Input(point)
                                 }
                                 TabletEvent::ProximityLeave => {
@@ -391,6 +601,14 @@ pub fn start_tablet() -> Result<(), String> {
                                     }
                                     TabletEvent::ProximityLeave
                                 }
+                                TabletEvent::ToolChanged(tool) => {
+                                    // Switch to the pen's own calibration (or none)
+                                    if let Ok(mut calibration) = calibration.lock() {
+                                        calibration.serial = tool.serial;
+                                        calibration_curve.store(&calibration.curve());
+                                    }
+                                    TabletEvent::ToolChanged(tool)
+                                }
                                 other => other,
                             };
                             events_to_emit.push(processed_event);
@@ -398,9 +616,7 @@ pub fn start_tablet() -> Result<(), String> {
 
                         // Emit processed events
                         for event in events_to_emit.drain(..) {
-                            if let Err(e) = app.emit("tablet-event", &event) {
-                                tracing::error!("[Tablet] Failed to emit event: {}", e);
-                            }
+                            emit(&event);
                         }
                     }
 
@@ -415,6 +631,95 @@ pub fn start_tablet() -> Result<(), String> {
     Ok(())
 }
 
+/// Start recording raw tablet events (replaces any recording in progress)
+#[tauri::command]
+pub fn start_input_recording() -> Result<(), String> {
+    let state = get_tablet_state();
+    let mut state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let backend = state
+        .active_backend()
+        .map_or("none", |backend| backend.name());
+    let recorder = InputRecorder::new(backend);
+    *state
+        .recorder
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))? = Some(recorder);
+    tracing::info!("[Tablet] Input recording started ({})", backend);
+    Ok(())
+}
+
+/// Stop recording and save it to `path`; returns the number of events
+#[tauri::command]
+pub fn stop_input_recording(path: String) -> Result<usize, String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let recorder = state
+        .recorder
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .take()
+        .ok_or_else(|| "No input recording in progress".to_string())?;
+    let recording = recorder.finish();
+    recording.save(&path)?;
+    tracing::info!(
+        "[Tablet] Saved {} input events to {}",
+        recording.events.len(),
+        path
+    );
+    Ok(recording.events.len())
+}
+
+/// Start recording raw pressure for calibration (replaces any in progress)
+#[tauri::command]
+pub fn start_pressure_calibration() -> Result<(), String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut calibration = state
+        .calibration
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    calibration.recorder = Some(CalibrationRecorder::new());
+    tracing::info!("[Tablet] Pressure calibration started");
+    Ok(())
+}
+
+/// Fit, save and apply the calibration for the current device and pen
+#[tauri::command]
+pub fn finish_pressure_calibration() -> Result<CalibrationProfile, String> {
+    let state = get_tablet_state();
+    let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut calibration = state
+        .calibration
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let recorder = calibration
+        .recorder
+        .take()
+        .ok_or_else(|| "No pressure calibration in progress".to_string())?;
+    let device = calibration
+        .device
+        .clone()
+        .ok_or_else(|| "No tablet detected".to_string())?;
+    let profile = recorder.fit(&device, calibration.serial)?;
+
+    calibration.store.upsert(profile.clone());
+    if let Some(path) = state.calibration_file.as_ref() {
+        calibration.store.save(path)?;
+    }
+    state.calibration_curve.store(&calibration.curve());
+    tracing::info!(
+        "[Tablet] Calibrated {}: dead-zone {:.3}, max {:.3}",
+        profile.device,
+        profile.dead_zone,
+        profile.max_pressure
+    );
+    Ok(profile)
+}
+
 /// Stop tablet input streaming
 #[tauri::command]
 pub fn stop_tablet() -> Result<(), String> {
@@ -452,19 +757,47 @@ pub fn get_tablet_status() -> Result<TabletStatusResponse, String> {
 }
 
 /// Push pointer event from frontend (for PointerEvent backend)
+///
+/// `twist` and `tangential_pressure` are the PointerEvent fields of the
+/// same name, omitted by pens that do not report them. `timestamp` is the
+/// event's `timeStamp`, so coalesced events keep their sample spacing.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn push_pointer_event(
     x: f32,
     y: f32,
     pressure: f32,
     tilt_x: f32,
     tilt_y: f32,
+    twist: Option<f32>,
+    tangential_pressure: Option<f32>,
+    pointer_type: Option<PointerType>,
+    pointer_id: Option<u32>,
+    buttons: Option<u32>,
+    timestamp: Option<f64>,
 ) -> Result<(), String> {
     let state = get_tablet_state();
     let state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
 
     if let Some(pointer) = &state.pointer {
-        pointer.push_input(x, y, pressure, tilt_x, tilt_y);
+        if let Some(pointer_type) = pointer_type {
+            pointer.push_tool(ToolInfo {
+                pointer_type,
+                cursor_id: pointer_id.unwrap_or(0),
+                serial: None,
+            });
+        }
+        let point = RawInputPoint::with_tilt(x, y, pressure, tilt_x, tilt_y)
+            .with_rotation(twist)
+            .with_tangential_pressure(tangential_pressure)
+            .with_buttons(buttons.unwrap_or(0));
+        // PointerEvent.timeStamp: milliseconds on the page clock
+        match timestamp {
+            Some(ms) if ms.is_finite() && ms >= 0.0 => {
+                pointer.push_point_at(point, (ms * 1000.0) as u64)
+            }
+            _ => pointer.push_point(point),
+        }
     }
 
     Ok(())
@@ -474,7 +807,7 @@ pub fn push_pointer_event(
 // Soft Brush SIMD Rendering
 // ============================================================================
 
-use crate::brush::soft_dab::{render_soft_dab, GaussParams};
+use crate::brush::soft_dab::{render_soft_dab, DabMode, GaussParams};
 
 // ============================================================================
 // ABR Brush Import
@@ -503,6 +836,7 @@ pub fn stamp_soft_dab(
     color: (u8, u8, u8),
     flow: f32,
     dab_opacity: f32,
+    mode: Option<DabMode>,
 ) -> Result<SoftDabResult, String> {
     // Validate buffer size
     let expected_size = buffer_width * buffer_height * 4;
@@ -528,6 +862,7 @@ pub fn stamp_soft_dab(
         color,
         flow,
         dab_opacity,
+        mode.unwrap_or_default(),
     );
 
     Ok((buffer, dirty_rect))
@@ -593,4 +928,62 @@ mod tests {
         assert!(!info.platform.is_empty());
         assert!(!info.arch.is_empty());
     }
+
+    #[test]
+    fn test_synthetic_tablet_pipeline() {
+        let app = tauri::test::mock_app();
+        let Ok(status) = init_tablet(
+            app.handle().clone(),
+            Some(BackendType::Synthetic),
+            None,
+            None,
+        ) else {
+            panic!("synthetic backend should initialize");
+        };
+        assert_eq!(status.backend, "Synthetic");
+        assert_eq!(status.status, TabletStatus::Connected);
+
+        // Collect what the emitter thread sends to the frontend
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        if let Ok(mut state) = get_tablet_state().lock() {
+            state.event_sink = Some(Arc::new(move |event: &TabletEvent| {
+                if let Ok(mut events) = sink.lock() {
+                    events.push(event.clone());
+                }
+            }));
+        }
+
+        assert!(start_tablet().is_ok());
+        let stroke_done = || {
+            received
+                .lock()
+                .is_ok_and(|events| events.contains(&TabletEvent::ProximityLeave))
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !stroke_done() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(stop_tablet().is_ok());
+
+        let Ok(events) = received.lock() else {
+            panic!("event lock poisoned");
+        };
+        assert!(matches!(events.first(), Some(TabletEvent::ToolChanged(_))));
+        let points: Vec<RawInputPoint> = events
+            .iter()
+            .filter_map(|event| match event {
+                TabletEvent::Input(point) => Some(*point),
+                _ => None,
+            })
+            .collect();
+
+        // The first demo stroke went through the whole pipeline, pen-up included
+        assert!(points.len() > 10, "{}", points.len());
+        assert!(points.iter().all(|p| (0.0..=1.0).contains(&p.pressure)));
+        assert!(points
+            .windows(2)
+            .all(|w| w[1].timestamp_us >= w[0].timestamp_us));
+        assert_eq!(points.last().map(|p| p.pressure), Some(0.0));
+    }
 }
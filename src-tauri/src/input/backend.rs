@@ -3,7 +3,8 @@
 //! This module defines the common interface that all tablet backends must implement,
 //! allowing seamless switching between WinTab, PointerEvent, and other backends.
 
-use crate::input::RawInputPoint;
+use crate::brush::PressureCurve;
+use crate::input::{InputSpace, RawInputPoint};
 use serde::{Deserialize, Serialize};
 
 /// Tablet device information
@@ -32,8 +33,50 @@ pub enum TabletStatus {
     Error,
 }
 
+/// Kind of pointing device producing input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PointerType {
+    /// Pen tip
+    #[default]
+    Pen,
+    /// Eraser end of a pen
+    Eraser,
+    /// Mouse or tablet puck
+    Mouse,
+    /// Finger on a touch surface
+    Touch,
+}
+
+/// The tool currently in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    /// Pointer type
+    pub pointer_type: PointerType,
+    /// Backend cursor ID (WinTab cursor index, PointerEvent pointerId)
+    pub cursor_id: u32,
+    /// Physical tool serial number, when the backend can read it
+    pub serial: Option<u64>,
+}
+
+/// Button bits in `RawInputPoint::buttons`
+///
+/// Native backends set only these three bits. Tip and lower button match
+/// PointerEvent.buttons (1 and 2); the PointerEvent backend passes the
+/// browser's mask through unchanged, so it may also carry bits such as
+/// 32 (eraser button) that native backends report as `PointerType::Eraser`.
+pub mod buttons {
+    /// Tip contact
+    pub const TIP: u32 = 1 << 0;
+    /// Lower side (barrel) button
+    pub const LOWER: u32 = 1 << 1;
+    /// Upper side button
+    pub const UPPER: u32 = 1 << 2;
+}
+
 /// Events emitted by the tablet backend
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TabletEvent {
     /// Input point received
     Input(RawInputPoint),
@@ -43,6 +86,30 @@ pub enum TabletEvent {
     ProximityLeave,
     /// Status changed
     StatusChanged(TabletStatus),
+    /// A different tool came into use (e.g. the pen was flipped to the eraser)
+    ToolChanged(ToolInfo),
+}
+
+/// Detects tool transitions so backends emit `ToolChanged` only on change
+#[derive(Debug, Clone, Default)]
+pub struct ToolTracker {
+    current: Option<ToolInfo>,
+}
+
+impl ToolTracker {
+    /// Record the tool of the latest packet; returns an event if it changed
+    pub fn update(&mut self, tool: ToolInfo) -> Option<TabletEvent> {
+        if self.current == Some(tool) {
+            return None;
+        }
+        self.current = Some(tool);
+        Some(TabletEvent::ToolChanged(tool))
+    }
+
+    /// Tool currently in use
+    pub fn current(&self) -> Option<ToolInfo> {
+        self.current
+    }
 }
 
 /// Configuration for tablet backend
@@ -52,7 +119,7 @@ pub struct TabletConfig {
     pub polling_rate_hz: u32,
     /// Enable input prediction
     pub prediction_enabled: bool,
-    /// Pressure curve type
+    /// Tablet-level pressure curve (pen calibration), applied after smoothing
     pub pressure_curve: PressureCurve,
 }
 
@@ -66,35 +133,6 @@ impl Default for TabletConfig {
     }
 }
 
-/// Pressure curve types for mapping raw pressure to output
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum PressureCurve {
-    /// Linear mapping (1:1)
-    Linear,
-    /// Soft curve (easier light pressure)
-    Soft,
-    /// Hard curve (requires more pressure)
-    Hard,
-    /// S-curve (soft at extremes, linear in middle)
-    SCurve,
-}
-
-impl PressureCurve {
-    /// Apply the pressure curve to a normalized pressure value (0.0 - 1.0)
-    pub fn apply(&self, pressure: f32) -> f32 {
-        let p = pressure.clamp(0.0, 1.0);
-        match self {
-            PressureCurve::Linear => p,
-            PressureCurve::Soft => p.sqrt(),
-            PressureCurve::Hard => p * p,
-            PressureCurve::SCurve => {
-                // S-curve using smoothstep
-                p * p * (3.0 - 2.0 * p)
-            }
-        }
-    }
-}
-
 /// Trait that all tablet backends must implement
 pub trait TabletBackend: Send {
     /// Initialize the backend
@@ -123,6 +161,11 @@ pub trait TabletBackend: Send {
 
     /// Get the backend name
     fn name(&self) -> &'static str;
+
+    /// Coordinate space of emitted points (mapped to canvas before processing)
+    fn input_space(&self) -> InputSpace {
+        InputSpace::Canvas
+    }
 }
 
 #[cfg(test)]
@@ -130,33 +173,22 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_pressure_curve_linear() {
-        let curve = PressureCurve::Linear;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert_eq!(curve.apply(0.5), 0.5);
-        assert_eq!(curve.apply(1.0), 1.0);
-    }
-
-    #[test]
-    fn test_pressure_curve_soft() {
-        let curve = PressureCurve::Soft;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert!(curve.apply(0.25) > 0.25); // Soft makes low pressure easier
-        assert_eq!(curve.apply(1.0), 1.0);
-    }
-
-    #[test]
-    fn test_pressure_curve_hard() {
-        let curve = PressureCurve::Hard;
-        assert_eq!(curve.apply(0.0), 0.0);
-        assert!(curve.apply(0.5) < 0.5); // Hard makes low pressure harder
-        assert_eq!(curve.apply(1.0), 1.0);
-    }
-
-    #[test]
-    fn test_pressure_curve_clamping() {
-        let curve = PressureCurve::Linear;
-        assert_eq!(curve.apply(-0.5), 0.0);
-        assert_eq!(curve.apply(1.5), 1.0);
+    fn test_tool_tracker_emits_on_transition() {
+        let mut tracker = ToolTracker::default();
+        let pen = ToolInfo {
+            pointer_type: PointerType::Pen,
+            cursor_id: 1,
+            serial: Some(42),
+        };
+        let eraser = ToolInfo {
+            pointer_type: PointerType::Eraser,
+            cursor_id: 2,
+            ..pen
+        };
+
+        assert!(matches!(tracker.update(pen), Some(TabletEvent::ToolChanged(t)) if t == pen));
+        assert!(tracker.update(pen).is_none());
+        assert!(matches!(tracker.update(eraser), Some(TabletEvent::ToolChanged(t)) if t == eraser));
+        assert_eq!(tracker.current(), Some(eraser));
     }
 }
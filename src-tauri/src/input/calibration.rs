@@ -0,0 +1,279 @@
+//! Pressure calibration - per-device dead-zone and response curve
+//!
+//! A guided scribble records raw pen pressure. From it we fit:
+//! - Dead-zone: the lightest pressure the pen reports while touching
+//!   (worn or heavy pens register pressure at rest)
+//! - Ceiling: the pressure the artist actually reaches (some pens never
+//!   report 1.0)
+//! - Typical: the median pressure, mapped to 0.5 so everyday strokes sit in
+//!   the middle of the brush range
+//!
+//! The fit is baked into a custom `PressureCurve` and used as the
+//! tablet-level curve. Profiles are stored as JSON, keyed by device name and
+//! pen serial, and picked up automatically when the device is detected.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::brush::{CurvePoint, PressureCurve, SplineCurve};
+
+/// Minimum number of touching samples for a fit
+pub const MIN_CALIBRATION_SAMPLES: usize = 50;
+
+/// Smallest usable range between dead-zone and ceiling
+const MIN_PRESSURE_RANGE: f32 = 0.1;
+
+/// Percentiles used to reject outliers at both ends
+const LOW_PERCENTILE: f32 = 0.02;
+const HIGH_PERCENTILE: f32 = 0.98;
+
+/// Fitted calibration for one device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationProfile {
+    /// Tablet name as reported by the backend
+    pub device: String,
+    /// Pen serial (None matches any pen on the device)
+    #[serde(default)]
+    pub serial: Option<u64>,
+    /// Raw pressure treated as zero
+    pub dead_zone: f32,
+    /// Raw pressure treated as full
+    pub max_pressure: f32,
+    /// Median raw pressure while drawing (mapped to 0.5)
+    pub typical_pressure: f32,
+}
+
+impl CalibrationProfile {
+    /// Tablet-level curve for this profile
+    pub fn to_curve(&self) -> PressureCurve {
+        let dead_zone = self.dead_zone.clamp(0.0, 0.9);
+        let max = self.max_pressure.clamp(dead_zone + 0.05, 1.0);
+        let typical = self.typical_pressure.clamp(dead_zone + 0.01, max - 0.01);
+
+        let mut points = vec![CurvePoint::new(0.0, 0.0)];
+        if dead_zone > 0.0 {
+            points.push(CurvePoint::new(dead_zone, 0.0));
+        }
+        points.push(CurvePoint::new(typical, 0.5));
+        points.push(CurvePoint::new(max, 1.0));
+        if max < 1.0 {
+            points.push(CurvePoint::new(1.0, 1.0));
+        }
+
+        SplineCurve::new(points)
+            .map(PressureCurve::Custom)
+            .unwrap_or_default()
+    }
+
+    /// Whether this profile applies to a device and pen
+    pub fn matches(&self, device: &str, serial: Option<u64>) -> bool {
+        self.device == device && (self.serial.is_none() || self.serial == serial)
+    }
+}
+
+/// Collects raw pressure during a guided scribble
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationRecorder {
+    samples: Vec<f32>,
+}
+
+impl CalibrationRecorder {
+    /// Start a new calibration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a raw pressure sample (samples without contact are ignored)
+    pub fn record(&mut self, pressure: f32) {
+        if pressure > 0.0 && pressure.is_finite() {
+            self.samples.push(pressure.min(1.0));
+        }
+    }
+
+    /// Number of touching samples recorded
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether nothing has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Fit a profile for a device
+    pub fn fit(&self, device: &str, serial: Option<u64>) -> Result<CalibrationProfile, String> {
+        if self.samples.len() < MIN_CALIBRATION_SAMPLES {
+            return Err(format!(
+                "Not enough pressure samples ({} of {})",
+                self.samples.len(),
+                MIN_CALIBRATION_SAMPLES
+            ));
+        }
+
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(f32::total_cmp);
+        let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+
+        let dead_zone = percentile(LOW_PERCENTILE);
+        let max_pressure = percentile(HIGH_PERCENTILE);
+        if max_pressure - dead_zone < MIN_PRESSURE_RANGE {
+            return Err("Pressure range too small, press harder during calibration".to_string());
+        }
+
+        Ok(CalibrationProfile {
+            device: device.to_string(),
+            serial,
+            dead_zone,
+            max_pressure,
+            typical_pressure: percentile(0.5),
+        })
+    }
+}
+
+/// Saved calibration profiles
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationStore {
+    pub profiles: Vec<CalibrationProfile>,
+}
+
+impl CalibrationStore {
+    /// Best profile for a device: a serial-specific one wins over a generic one
+    pub fn find(&self, device: &str, serial: Option<u64>) -> Option<&CalibrationProfile> {
+        let matching = || self.profiles.iter().filter(|p| p.matches(device, serial));
+        matching()
+            .find(|p| p.serial.is_some())
+            .or_else(|| matching().next())
+    }
+
+    /// Add or replace the profile for the same device and serial
+    pub fn upsert(&mut self, profile: CalibrationProfile) {
+        match self
+            .profiles
+            .iter_mut()
+            .find(|p| p.device == profile.device && p.serial == profile.serial)
+        {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Load from a JSON file (a missing file is an empty store)
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read calibration: {}", e))?;
+        serde_json::from_str(&json).map_err(|e| format!("Invalid calibration file: {}", e))
+    }
+
+    /// Save to a JSON file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create calibration directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize calibration: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to save calibration: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scribble from a pen that rests at 0.05 and never passes 0.8
+    fn worn_pen_scribble() -> CalibrationRecorder {
+        let mut recorder = CalibrationRecorder::new();
+        for i in 0..200 {
+            let t = (i as f32 / 199.0 * std::f32::consts::PI).sin();
+            recorder.record(0.05 + 0.75 * t);
+        }
+        // Hover samples are ignored
+        for _ in 0..50 {
+            recorder.record(0.0);
+        }
+        recorder
+    }
+
+    #[test]
+    fn test_fit_dead_zone_and_ceiling() {
+        let recorder = worn_pen_scribble();
+        assert_eq!(recorder.len(), 200);
+
+        let Ok(profile) = recorder.fit("Wacom Intuos", Some(7)) else {
+            panic!("enough samples to fit");
+        };
+        assert!((profile.dead_zone - 0.05).abs() < 0.05, "{:?}", profile);
+        assert!((profile.max_pressure - 0.8).abs() < 0.01, "{:?}", profile);
+
+        let curve = profile.to_curve();
+        assert!(curve.apply(profile.dead_zone) < 1e-3);
+        assert!(curve.apply(0.04) < 0.01);
+        assert!((curve.apply(profile.max_pressure) - 1.0).abs() < 0.01);
+        assert!((curve.apply(profile.typical_pressure) - 0.5).abs() < 0.01);
+        assert_eq!(curve.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_fit_rejects_poor_data() {
+        let mut recorder = CalibrationRecorder::new();
+        for _ in 0..10 {
+            recorder.record(0.5);
+        }
+        assert!(recorder.fit("Pen", None).is_err());
+
+        for _ in 0..100 {
+            recorder.record(0.5);
+        }
+        assert!(matches!(recorder.fit("Pen", None), Err(e) if e.contains("too small")));
+    }
+
+    #[test]
+    fn test_store_lookup_and_persistence() -> Result<(), String> {
+        let generic = CalibrationProfile {
+            device: "Wacom Intuos".to_string(),
+            serial: None,
+            dead_zone: 0.0,
+            max_pressure: 1.0,
+            typical_pressure: 0.5,
+        };
+        let pen = CalibrationProfile {
+            serial: Some(42),
+            dead_zone: 0.05,
+            ..generic.clone()
+        };
+
+        let mut store = CalibrationStore::default();
+        store.upsert(generic.clone());
+        store.upsert(pen.clone());
+        store.upsert(CalibrationProfile {
+            max_pressure: 0.9,
+            ..pen.clone()
+        });
+        assert_eq!(store.profiles.len(), 2);
+
+        assert_eq!(
+            store.find("Wacom Intuos", Some(42)).map(|p| p.max_pressure),
+            Some(0.9)
+        );
+        assert_eq!(store.find("Wacom Intuos", Some(1)), Some(&generic));
+        assert_eq!(store.find("Wacom Intuos", None), Some(&generic));
+        assert_eq!(store.find("Huion", None), None);
+
+        let path = std::env::temp_dir()
+            .join(format!("paintboard-calibration-{}", std::process::id()))
+            .join("calibration.json");
+        store.save(&path)?;
+        let loaded = CalibrationStore::load(&path)?;
+        let _ = std::fs::remove_dir_all(path.parent().ok_or("no parent")?);
+        assert_eq!(loaded, store);
+
+        assert_eq!(CalibrationStore::load(&path)?, CalibrationStore::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Input clock - monotonic timestamps for input points
+//!
+//! All backends stamp points on one monotonic microsecond clock, anchored
+//! to Unix time when the process first reads it (so values stay comparable
+//! with wall-clock logs but never jump).
+//!
+//! Devices report sample times on their own clocks (WinTab `PK_TIME` is
+//! milliseconds since boot, evdev uses the kernel clock, PointerEvent uses
+//! the page's `performance.now()`). `DeviceClock` maps those onto the
+//! input clock using the smallest observed arrival delay, so batched
+//! packets keep their real spacing instead of the time they were read.
+
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use super::backend::TabletEvent;
+use super::RawInputPoint;
+
+/// Delay beyond which a sample counts as far off the device clock (µs)
+const MAX_LATENCY_US: i64 = 100_000;
+
+/// Consecutive far-off samples after which the device clock is assumed to
+/// have jumped (a single late batch only delays a few samples)
+const REANCHOR_SAMPLES: u32 = 8;
+
+/// Current time on the input clock (microseconds)
+pub fn monotonic_us() -> u64 {
+    static EPOCH: OnceLock<(Instant, u64)> = OnceLock::new();
+    let (start, unix_us) = EPOCH.get_or_init(|| {
+        let unix_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        (Instant::now(), unix_us)
+    });
+    unix_us + start.elapsed().as_micros() as u64
+}
+
+/// Maps device sample times onto the input clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeviceClock {
+    /// Input clock minus device clock (smallest seen)
+    offset_us: Option<i64>,
+    /// Last device time, to detect the device clock going backwards
+    last_device_us: u64,
+    /// Consecutive samples delayed by more than `MAX_LATENCY_US`
+    far_off: u32,
+    /// Last mapped time, to keep output monotonic
+    last_us: u64,
+}
+
+impl DeviceClock {
+    /// Create an unsynchronized clock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a device sample time to the input clock, given when it arrived
+    pub fn to_host(&mut self, device_us: u64, arrival_us: u64) -> u64 {
+        let observed = arrival_us as i64 - device_us as i64;
+        let went_back = device_us < self.last_device_us;
+        self.last_device_us = device_us;
+
+        // Samples arrive after they are taken, so the smallest delay is the
+        // best estimate. Re-anchor only if the device clock went backwards or
+        // stays far off, since late batches are normal.
+        let offset = match self.offset_us {
+            Some(offset) if !went_back && observed >= offset => {
+                if observed - offset > MAX_LATENCY_US {
+                    self.far_off += 1;
+                } else {
+                    self.far_off = 0;
+                }
+                if self.far_off >= REANCHOR_SAMPLES {
+                    self.far_off = 0;
+                    observed
+                } else {
+                    offset
+                }
+            }
+            _ => {
+                self.far_off = 0;
+                observed
+            }
+        };
+        self.offset_us = Some(offset);
+
+        let host = (device_us as i64 + offset).max(0) as u64;
+        self.last_us = host.max(self.last_us);
+        self.last_us
+    }
+
+    /// Move an input point's device time onto the input clock
+    pub fn stamp(&mut self, point: RawInputPoint, arrival_us: u64) -> RawInputPoint {
+        RawInputPoint {
+            arrival_us,
+            ..point.with_timestamp_us(self.to_host(point.timestamp_us, arrival_us))
+        }
+    }
+
+    /// Stamp every input point in a batch read at `arrival_us`
+    pub fn stamp_events(&mut self, events: &mut [TabletEvent], arrival_us: u64) {
+        for event in events {
+            if let TabletEvent::Input(point) = event {
+                *point = self.stamp(*point, arrival_us);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monotonic_clock() {
+        let a = monotonic_us();
+        let b = monotonic_us();
+        assert!(a > 0);
+        assert!(b >= a);
+    }
+
+    #[test]
+    fn test_batched_packets_keep_device_spacing() {
+        let mut clock = DeviceClock::new();
+        // First packet arrives 2 ms after it was sampled
+        assert_eq!(clock.to_host(10_000, 1_002_000), 1_002_000);
+        // A faster packet refines the offset
+        assert_eq!(clock.to_host(15_000, 1_006_000), 1_006_000);
+        // Three packets 5 ms apart read in one batch
+        let batch: Vec<u64> = [20_000, 25_000, 30_000]
+            .iter()
+            .map(|&device| clock.to_host(device, 1_030_000))
+            .collect();
+        assert_eq!(batch, vec![1_011_000, 1_016_000, 1_021_000]);
+    }
+
+    #[test]
+    fn test_single_late_batch_keeps_offset() {
+        let mut clock = DeviceClock::new();
+        assert_eq!(clock.to_host(10_000, 1_002_000), 1_002_000);
+        // Three packets read 150 ms late (e.g. the app was busy)
+        let batch: Vec<u64> = [15_000, 20_000, 25_000]
+            .iter()
+            .map(|&device| clock.to_host(device, 1_167_000))
+            .collect();
+        assert_eq!(batch, vec![1_007_000, 1_012_000, 1_017_000]);
+        // The stream continues on the device's timeline, without a jump
+        assert_eq!(clock.to_host(30_000, 1_168_000), 1_022_000);
+        assert_eq!(clock.to_host(35_000, 1_027_000), 1_027_000);
+    }
+
+    #[test]
+    fn test_reanchors_when_far_off_for_long() {
+        let mut clock = DeviceClock::new();
+        clock.to_host(0, 1_000_000);
+        // Device clock stalled for a while: every sample is 200 ms behind
+        let mapped: Vec<u64> = (1..=REANCHOR_SAMPLES as u64)
+            .map(|i| clock.to_host(i * 1_000, 1_200_000 + i * 1_000))
+            .collect();
+        assert_eq!(mapped[0], 1_001_000);
+        assert_eq!(
+            mapped[mapped.len() - 1],
+            1_200_000 + REANCHOR_SAMPLES as u64 * 1_000
+        );
+    }
+
+    #[test]
+    fn test_reanchors_on_device_clock_jump() {
+        let mut clock = DeviceClock::new();
+        clock.to_host(u32::MAX as u64 * 1000, 5_000_000);
+        // Device counter wrapped: time restarts near zero
+        let wrapped = clock.to_host(1_000, 5_010_000);
+        assert_eq!(wrapped, 5_010_000);
+        assert_eq!(clock.to_host(6_000, 5_015_500), 5_015_000);
+    }
+
+    #[test]
+    fn test_stamp_records_latency() {
+        let mut clock = DeviceClock::new();
+        let first = clock.stamp(
+            RawInputPoint::new(0.0, 0.0, 0.5).with_timestamp_us(0),
+            500_000,
+        );
+        let later = clock.stamp(
+            RawInputPoint::new(0.0, 0.0, 0.5).with_timestamp_us(8_000),
+            512_000,
+        );
+        assert_eq!(first.latency_us(), 0);
+        assert_eq!(later.timestamp_us, 508_000);
+        assert_eq!(later.timestamp_ms, 508);
+        assert_eq!(later.arrival_us, 512_000);
+        assert_eq!(later.latency_us(), 4_000);
+    }
+}
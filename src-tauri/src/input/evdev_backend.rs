@@ -0,0 +1,952 @@
+//! evdev backend implementation
+//!
+//! Reads pen input directly from Linux `/dev/input/event*` devices:
+//! `ABS_X/Y`, `ABS_PRESSURE`, `ABS_TILT_X/Y`, `BTN_TOOL_PEN/RUBBER`,
+//! `BTN_TOUCH` and `BTN_STYLUS/STYLUS2`. Axis values are normalized with
+//! the ranges reported by the kernel (`EVIOCGABS`).
+//!
+//! Decoding is separate from device I/O, so it can be tested with recorded
+//! event dumps (`cat /dev/input/eventN > pen.evdev`) on any platform.
+//! `EvdevBackend::open` also accepts a dump file and plays it to the end.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use super::backend::{
+    buttons, PointerType, TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus,
+    ToolInfo, ToolTracker,
+};
+use super::{InputSpace, RawInputPoint};
+
+// Event types
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const EV_MSC: u16 = 0x04;
+
+// Event codes
+const SYN_REPORT: u16 = 0;
+const SYN_DROPPED: u16 = 3;
+const MSC_SERIAL: u16 = 0x00;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_PRESSURE: u16 = 0x18;
+const ABS_TILT_X: u16 = 0x1a;
+const ABS_TILT_Y: u16 = 0x1b;
+const BTN_TOOL_PEN: u16 = 0x140;
+const BTN_TOOL_RUBBER: u16 = 0x141;
+const BTN_TOUCH: u16 = 0x14a;
+const BTN_STYLUS: u16 = 0x14b;
+const BTN_STYLUS2: u16 = 0x14c;
+
+/// Size of `struct timeval` in `struct input_event`
+#[cfg(target_pointer_width = "64")]
+const TIMEVAL_SIZE: usize = 16;
+#[cfg(not(target_pointer_width = "64"))]
+const TIMEVAL_SIZE: usize = 8;
+
+/// Size of `struct input_event`
+pub const INPUT_EVENT_SIZE: usize = TIMEVAL_SIZE + 8;
+
+/// One kernel input event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvdevEvent {
+    /// Kernel timestamp in microseconds
+    pub time_us: u64,
+    pub event_type: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl EvdevEvent {
+    /// Parse a native-endian `struct input_event`
+    pub fn from_bytes(bytes: &[u8; INPUT_EVENT_SIZE]) -> Self {
+        let word = TIMEVAL_SIZE / 2;
+        let int = |range: std::ops::Range<usize>| -> i64 {
+            let mut buf = [0u8; 8];
+            buf[..range.len()].copy_from_slice(&bytes[range.clone()]);
+            if cfg!(target_endian = "big") {
+                buf[..range.len()].reverse();
+            }
+            // Sign-extend 32-bit fields
+            let raw = i64::from_le_bytes(buf);
+            if range.len() == 4 {
+                raw as i32 as i64
+            } else {
+                raw
+            }
+        };
+        let sec = int(0..word);
+        let usec = int(word..TIMEVAL_SIZE);
+        let t = TIMEVAL_SIZE;
+
+        Self {
+            time_us: (sec.max(0) as u64) * 1_000_000 + usec.max(0) as u64,
+            event_type: u16::from_ne_bytes([bytes[t], bytes[t + 1]]),
+            code: u16::from_ne_bytes([bytes[t + 2], bytes[t + 3]]),
+            value: i32::from_ne_bytes([bytes[t + 4], bytes[t + 5], bytes[t + 6], bytes[t + 7]]),
+        }
+    }
+
+    /// Serialize as a native-endian `struct input_event`
+    pub fn to_bytes(&self) -> [u8; INPUT_EVENT_SIZE] {
+        let mut bytes = [0u8; INPUT_EVENT_SIZE];
+        let word = TIMEVAL_SIZE / 2;
+        let sec = (self.time_us / 1_000_000).to_ne_bytes();
+        let usec = (self.time_us % 1_000_000).to_ne_bytes();
+        let (sec, usec) = if cfg!(target_endian = "big") {
+            (&sec[8 - word..], &usec[8 - word..])
+        } else {
+            (&sec[..word], &usec[..word])
+        };
+        bytes[..word].copy_from_slice(sec);
+        bytes[word..TIMEVAL_SIZE].copy_from_slice(usec);
+
+        let t = TIMEVAL_SIZE;
+        bytes[t..t + 2].copy_from_slice(&self.event_type.to_ne_bytes());
+        bytes[t + 2..t + 4].copy_from_slice(&self.code.to_ne_bytes());
+        bytes[t + 4..t + 8].copy_from_slice(&self.value.to_ne_bytes());
+        bytes
+    }
+}
+
+/// Range of an absolute axis (`struct input_absinfo`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AxisRange {
+    pub min: i32,
+    pub max: i32,
+    /// Units per mm (X/Y) or per radian (tilt); 0 if unknown
+    pub resolution: i32,
+}
+
+impl AxisRange {
+    /// Axis range without resolution
+    pub fn new(min: i32, max: i32) -> Self {
+        Self {
+            min,
+            max,
+            resolution: 0,
+        }
+    }
+
+    /// Map a value to 0.0 - 1.0
+    pub fn normalize(&self, value: i32) -> f32 {
+        // i64: the span of a full i32 range does not fit in an i32
+        let span = self.span();
+        if span <= 0 {
+            return 0.0;
+        }
+        ((value as i64 - self.min as i64) as f64 / span as f64).clamp(0.0, 1.0) as f32
+    }
+
+    /// max - min, without overflow
+    pub fn span(&self) -> i64 {
+        self.max as i64 - self.min as i64
+    }
+
+    /// Tilt value in degrees (units are degrees when resolution is unknown)
+    pub fn degrees(&self, value: i32) -> f32 {
+        let degrees = if self.resolution > 0 {
+            (value as f32 / self.resolution as f32).to_degrees()
+        } else {
+            value as f32
+        };
+        degrees.clamp(-90.0, 90.0)
+    }
+}
+
+/// Axes of a pen device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvdevAxes {
+    pub x: AxisRange,
+    pub y: AxisRange,
+    pub pressure: AxisRange,
+    pub tilt_x: Option<AxisRange>,
+    pub tilt_y: Option<AxisRange>,
+}
+
+/// A discovered pen device
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvdevDevice {
+    /// Device node (or an event dump file)
+    pub path: PathBuf,
+    /// Kernel device name
+    pub name: String,
+    /// Axis ranges (None until queried from the device)
+    pub axes: Option<EvdevAxes>,
+}
+
+/// Turns kernel events into tablet events, one `SYN_REPORT` frame at a time
+#[derive(Debug, Clone)]
+pub struct EvdevDecoder {
+    axes: EvdevAxes,
+    /// Output extent for X/Y (device units are scaled to this size)
+    output: (f32, f32),
+    x: i32,
+    y: i32,
+    pressure: i32,
+    tilt_x: i32,
+    tilt_y: i32,
+    buttons: u32,
+    serial: Option<u64>,
+    /// Tool in proximity (BTN_TOOL_* code)
+    tool: Option<u16>,
+    /// Tool at the last frame
+    reported_tool: Option<u16>,
+    tracker: ToolTracker,
+    /// Events are being discarded until the next SYN_REPORT
+    dropping: bool,
+    /// A drop just ended; axis state must be re-read from the device
+    resync: bool,
+}
+
+impl EvdevDecoder {
+    /// Create a decoder; X/Y are in device units (0 to max - min) until
+    /// `set_output_size` is called
+    pub fn new(axes: EvdevAxes) -> Self {
+        Self {
+            axes,
+            output: (axes.x.span() as f32, axes.y.span() as f32),
+            x: axes.x.min,
+            y: axes.y.min,
+            pressure: axes.pressure.min,
+            tilt_x: 0,
+            tilt_y: 0,
+            buttons: 0,
+            serial: None,
+            tool: None,
+            reported_tool: None,
+            tracker: ToolTracker::default(),
+            dropping: false,
+            resync: false,
+        }
+    }
+
+    /// Scale X/Y from 0 to the given size instead of device units (e.g. 1x1
+    /// for the normalized tablet surface, or the mapped screen area)
+    pub fn set_output_size(&mut self, width: f32, height: f32) {
+        self.output = (width, height);
+    }
+
+    /// Feed one kernel event; completed frames are appended to `out`
+    pub fn feed(&mut self, event: EvdevEvent, out: &mut Vec<TabletEvent>) {
+        if self.dropping {
+            // Kernel buffer overran: skip to the next complete frame; axis
+            // changes in between were lost, so the state must be re-read
+            if event.event_type == EV_SYN && event.code == SYN_REPORT {
+                self.dropping = false;
+                self.resync = true;
+            }
+            return;
+        }
+
+        match (event.event_type, event.code) {
+            (EV_SYN, SYN_REPORT) => self.flush(event.time_us, out),
+            (EV_SYN, SYN_DROPPED) => self.dropping = true,
+            (EV_ABS, code) => self.set_abs(code, event.value),
+            (EV_MSC, MSC_SERIAL) => self.serial = Some(event.value as u32 as u64),
+            (EV_KEY, code @ (BTN_TOOL_PEN | BTN_TOOL_RUBBER)) => {
+                if event.value != 0 {
+                    self.tool = Some(code);
+                } else if self.tool == Some(code) {
+                    self.tool = None;
+                }
+            }
+            (EV_KEY, BTN_TOUCH) => self.set_button(buttons::TIP, event.value),
+            (EV_KEY, BTN_STYLUS) => self.set_button(buttons::LOWER, event.value),
+            (EV_KEY, BTN_STYLUS2) => self.set_button(buttons::UPPER, event.value),
+            _ => {}
+        }
+    }
+
+    /// Decode a raw event dump
+    pub fn feed_bytes(&mut self, bytes: &[u8], out: &mut Vec<TabletEvent>) {
+        self.feed_bytes_with(bytes, out, |_| {});
+    }
+
+    /// Decode raw events, calling `resync` when a `SYN_DROPPED` gap ends
+    /// so current axis values can be read back from the device (`set_abs`)
+    pub fn feed_bytes_with(
+        &mut self,
+        bytes: &[u8],
+        out: &mut Vec<TabletEvent>,
+        mut resync: impl FnMut(&mut Self),
+    ) {
+        for chunk in bytes.chunks_exact(INPUT_EVENT_SIZE) {
+            if let Ok(raw) = <&[u8; INPUT_EVENT_SIZE]>::try_from(chunk) {
+                self.feed(EvdevEvent::from_bytes(raw), out);
+                if std::mem::take(&mut self.resync) {
+                    resync(self);
+                }
+            }
+        }
+    }
+
+    /// Set the current value of an absolute axis
+    pub fn set_abs(&mut self, code: u16, value: i32) {
+        match code {
+            ABS_X => self.x = value,
+            ABS_Y => self.y = value,
+            ABS_PRESSURE => self.pressure = value,
+            ABS_TILT_X => self.tilt_x = value,
+            ABS_TILT_Y => self.tilt_y = value,
+            _ => {}
+        }
+    }
+
+    fn set_button(&mut self, bit: u32, value: i32) {
+        if value != 0 {
+            self.buttons |= bit;
+        } else {
+            self.buttons &= !bit;
+        }
+    }
+
+    fn flush(&mut self, time_us: u64, out: &mut Vec<TabletEvent>) {
+        let previous = std::mem::replace(&mut self.reported_tool, self.tool);
+
+        let Some(tool) = self.tool else {
+            if previous.is_some() {
+                out.push(TabletEvent::ProximityLeave);
+            }
+            return;
+        };
+
+        let info = ToolInfo {
+            pointer_type: if tool == BTN_TOOL_RUBBER {
+                PointerType::Eraser
+            } else {
+                PointerType::Pen
+            },
+            cursor_id: tool as u32,
+            serial: self.serial,
+        };
+        if let Some(changed) = self.tracker.update(info) {
+            out.push(changed);
+        }
+        if previous.is_none() {
+            out.push(TabletEvent::ProximityEnter);
+        }
+
+        let tilt = |axis: Option<AxisRange>, value| axis.map_or(0.0, |a| a.degrees(value));
+        // Kernel time; the reader moves it onto the input clock
+        let point = RawInputPoint::with_tilt(
+            self.axes.x.normalize(self.x) * self.output.0,
+            self.axes.y.normalize(self.y) * self.output.1,
+            self.axes.pressure.normalize(self.pressure),
+            tilt(self.axes.tilt_x, self.tilt_x),
+            tilt(self.axes.tilt_y, self.tilt_y),
+        )
+        .with_timestamp_us(time_us)
+        .with_buttons(self.buttons);
+        out.push(TabletEvent::Input(point));
+    }
+}
+
+/// Find pen devices under a sysfs input class directory
+///
+/// A pen has ABS_X, ABS_Y, ABS_PRESSURE and BTN_TOOL_PEN. Device paths
+/// are `/dev/input/<eventN>`; axes are not queried here.
+pub fn discover_in(sys_class_input: &Path) -> Vec<EvdevDevice> {
+    let Ok(entries) = std::fs::read_dir(sys_class_input) else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<EvdevDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let node = entry.file_name().to_string_lossy().into_owned();
+            if !node.starts_with("event") {
+                return None;
+            }
+            let device = entry.path().join("device");
+            let read = |file: &str| std::fs::read_to_string(device.join(file)).ok();
+
+            let abs = parse_bitmap(&read("capabilities/abs")?);
+            let key = parse_bitmap(&read("capabilities/key")?);
+            let is_pen = [ABS_X, ABS_Y, ABS_PRESSURE]
+                .iter()
+                .all(|&code| has_bit(&abs, code))
+                && has_bit(&key, BTN_TOOL_PEN);
+            if !is_pen {
+                return None;
+            }
+
+            Some(EvdevDevice {
+                path: Path::new("/dev/input").join(&node),
+                name: read("name").map_or_else(|| node.clone(), |n| n.trim().to_string()),
+                axes: None,
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    devices
+}
+
+/// Parse a sysfs capability bitmap ("1 0 3" = most significant word first)
+fn parse_bitmap(text: &str) -> Vec<u64> {
+    text.split_whitespace()
+        .rev()
+        .map(|word| u64::from_str_radix(word, 16).unwrap_or(0))
+        .collect()
+}
+
+fn has_bit(bitmap: &[u64], bit: u16) -> bool {
+    let (word, bit) = (bit as usize / 64, bit as usize % 64);
+    bitmap.get(word).is_some_and(|w| w & (1 << bit) != 0)
+}
+
+/// evdev backend for Linux pen tablets
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct EvdevBackend {
+    status: TabletStatus,
+    info: Option<TabletInfo>,
+    config: TabletConfig,
+    device: Option<EvdevDevice>,
+    output_size: Option<(f32, f32)>,
+    running: Arc<AtomicBool>,
+    events: Arc<Mutex<Vec<TabletEvent>>>,
+    read_thread: Option<JoinHandle<()>>,
+}
+
+impl EvdevBackend {
+    /// Create a backend that uses the first discovered pen
+    pub fn new() -> Self {
+        Self {
+            status: TabletStatus::Disconnected,
+            info: None,
+            config: TabletConfig::default(),
+            device: None,
+            output_size: None,
+            running: Arc::new(AtomicBool::new(false)),
+            events: Arc::new(Mutex::new(Vec::with_capacity(64))),
+            read_thread: None,
+        }
+    }
+
+    /// Create a backend for a specific device node or event dump
+    ///
+    /// Axes given here are used as-is; otherwise they are queried on init.
+    pub fn open(device: EvdevDevice) -> Self {
+        let mut backend = Self::new();
+        backend.device = Some(device);
+        backend
+    }
+
+    /// Scale X/Y to the given output size
+    ///
+    /// Without it, points are on the normalized tablet surface (0-1).
+    /// Only takes effect on the next `start`.
+    pub fn set_output_size(&mut self, width: f32, height: f32) {
+        self.output_size = Some((width, height));
+    }
+
+    /// Pen devices on this system
+    pub fn discover() -> Vec<EvdevDevice> {
+        discover_in(Path::new("/sys/class/input"))
+    }
+}
+
+impl Default for EvdevBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TabletBackend for EvdevBackend {
+    #[cfg(target_os = "linux")]
+    fn init(&mut self, config: &TabletConfig) -> Result<(), String> {
+        self.config = config.clone();
+
+        let mut device = match self.device.take() {
+            Some(device) => device,
+            None => Self::discover()
+                .into_iter()
+                .next()
+                .ok_or_else(|| "No evdev pen device found".to_string())?,
+        };
+        if device.axes.is_none() {
+            device.axes = Some(sys::query_axes(&device.path)?);
+        }
+        let axes = device.axes.ok_or("Missing axis ranges")?;
+
+        self.info = Some(TabletInfo {
+            name: device.name.clone(),
+            backend: "evdev".to_string(),
+            supports_pressure: axes.pressure.max > axes.pressure.min,
+            supports_tilt: axes.tilt_x.is_some() && axes.tilt_y.is_some(),
+            pressure_range: (axes.pressure.min, axes.pressure.max),
+        });
+        tracing::info!(
+            "[evdev] Using {} ({}), pressure {}-{}",
+            device.name,
+            device.path.display(),
+            axes.pressure.min,
+            axes.pressure.max
+        );
+        self.device = Some(device);
+        self.status = TabletStatus::Connected;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn init(&mut self, _config: &TabletConfig) -> Result<(), String> {
+        Err("evdev is only available on Linux".to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn start(&mut self) -> Result<(), String> {
+        if self.status != TabletStatus::Connected {
+            return Err("Backend not initialized".to_string());
+        }
+        if self.running.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let device = self.device.clone().ok_or("No device")?;
+        let axes = device.axes.ok_or("Missing axis ranges")?;
+        let mut decoder = EvdevDecoder::new(axes);
+        let (width, height) = self.output_size.unwrap_or((1.0, 1.0));
+        decoder.set_output_size(width, height);
+
+        self.running.store(true, Ordering::SeqCst);
+        let interval_ms = 1000 / self.config.polling_rate_hz.max(1) as u64;
+        self.read_thread = Some(sys::spawn_reader(
+            device.path,
+            decoder,
+            interval_ms,
+            self.running.clone(),
+            self.events.clone(),
+        )?);
+        tracing::info!("[evdev] Started");
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn start(&mut self) -> Result<(), String> {
+        Err("evdev is only available on Linux".to_string())
+    }
+
+    fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.read_thread.take() {
+            let _ = handle.join();
+        }
+        if let Ok(mut events) = self.events.lock() {
+            events.clear();
+        }
+    }
+
+    fn status(&self) -> TabletStatus {
+        self.status
+    }
+
+    fn info(&self) -> Option<&TabletInfo> {
+        self.info.as_ref()
+    }
+
+    fn poll(&mut self, events: &mut Vec<TabletEvent>) -> usize {
+        if let Ok(mut events_lock) = self.events.lock() {
+            let count = events_lock.len();
+            events.append(&mut events_lock);
+            count
+        } else {
+            0
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn is_available() -> bool {
+        !Self::discover().is_empty()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn is_available() -> bool {
+        false
+    }
+
+    fn name(&self) -> &'static str {
+        "evdev"
+    }
+
+    fn input_space(&self) -> InputSpace {
+        if self.output_size.is_some() {
+            InputSpace::Desktop
+        } else {
+            InputSpace::Tablet
+        }
+    }
+}
+
+impl Drop for EvdevBackend {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Device I/O (ioctl and non-blocking reads)
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::fs::{File, OpenOptions};
+    use std::io::{ErrorKind, Read};
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::input::clock::{monotonic_us, DeviceClock};
+
+    /// EVIOCSCLOCKID = _IOW('E', 0xa0, int)
+    const EVIOCSCLOCKID: libc::c_ulong =
+        (1 << 30) | (4 << 16) | ((b'E' as libc::c_ulong) << 8) | 0xa0;
+
+    /// EVIOCGABS(abs) = _IOR('E', 0x40 + abs, struct input_absinfo)
+    fn eviocgabs(abs: u16) -> libc::c_ulong {
+        let size = std::mem::size_of::<libc::input_absinfo>() as libc::c_ulong;
+        (2 << 30) | (size << 16) | ((b'E' as libc::c_ulong) << 8) | (0x40 + abs as libc::c_ulong)
+    }
+
+    fn query_absinfo(file: &File, abs: u16) -> Option<libc::input_absinfo> {
+        let mut info = libc::input_absinfo {
+            value: 0,
+            minimum: 0,
+            maximum: 0,
+            fuzz: 0,
+            flat: 0,
+            resolution: 0,
+        };
+        // SAFETY: EVIOCGABS writes one input_absinfo into `info`
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), eviocgabs(abs) as _, &mut info) };
+        (result >= 0).then_some(info)
+    }
+
+    fn query_axis(file: &File, abs: u16) -> Option<AxisRange> {
+        let info = query_absinfo(file, abs)?;
+        (info.maximum > info.minimum).then_some(AxisRange {
+            min: info.minimum,
+            max: info.maximum,
+            resolution: info.resolution,
+        })
+    }
+
+    /// Read axis ranges from a device node
+    pub(super) fn query_axes(path: &Path) -> Result<EvdevAxes, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let axis = |abs, name| {
+            query_axis(&file, abs).ok_or_else(|| format!("{} has no {} axis", path.display(), name))
+        };
+        Ok(EvdevAxes {
+            x: axis(ABS_X, "X")?,
+            y: axis(ABS_Y, "Y")?,
+            pressure: axis(ABS_PRESSURE, "pressure")?,
+            tilt_x: query_axis(&file, ABS_TILT_X),
+            tilt_y: query_axis(&file, ABS_TILT_Y),
+        })
+    }
+
+    /// Re-read current axis values after a SYN_DROPPED gap (no-op on dumps)
+    fn resync(file: &File, decoder: &mut EvdevDecoder) {
+        for abs in [ABS_X, ABS_Y, ABS_PRESSURE, ABS_TILT_X, ABS_TILT_Y] {
+            if let Some(info) = query_absinfo(file, abs) {
+                decoder.set_abs(abs, info.value);
+            }
+        }
+        tracing::warn!("[evdev] Events dropped, axis state re-read");
+    }
+
+    /// Read and decode events until stopped, unplugged or end of a dump
+    pub(super) fn spawn_reader(
+        path: PathBuf,
+        mut decoder: EvdevDecoder,
+        interval_ms: u64,
+        running: Arc<AtomicBool>,
+        events: Arc<Mutex<Vec<TabletEvent>>>,
+    ) -> Result<JoinHandle<()>, String> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+        // Event times default to the wall clock; ask for the monotonic one
+        // (fails harmlessly on dump files)
+        let clock_id: libc::c_int = libc::CLOCK_MONOTONIC;
+        // SAFETY: EVIOCSCLOCKID reads one int from `clock_id`
+        unsafe { libc::ioctl(file.as_raw_fd(), EVIOCSCLOCKID as _, &clock_id) };
+
+        Ok(thread::spawn(move || {
+            let mut buffer = [0u8; INPUT_EVENT_SIZE * 64];
+            let mut pending = Vec::with_capacity(buffer.len());
+            let mut decoded = Vec::with_capacity(64);
+            let mut device_clock = DeviceClock::new();
+
+            while running.load(Ordering::SeqCst) {
+                match file.read(&mut buffer) {
+                    Ok(0) => {
+                        tracing::info!("[evdev] End of input");
+                        break;
+                    }
+                    Ok(n) => {
+                        let arrival_us = monotonic_us();
+                        pending.extend_from_slice(&buffer[..n]);
+                        let complete = pending.len() / INPUT_EVENT_SIZE * INPUT_EVENT_SIZE;
+                        decoder.feed_bytes_with(&pending[..complete], &mut decoded, |d| {
+                            resync(&file, d)
+                        });
+                        pending.drain(..complete);
+                        device_clock.stamp_events(&mut decoded, arrival_us);
+
+                        if !decoded.is_empty() {
+                            if let Ok(mut events) = events.lock() {
+                                events.append(&mut decoded);
+                            }
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(interval_ms));
+                    }
+                    Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        tracing::error!("[evdev] Read failed: {}", e);
+                        if let Ok(mut events) = events.lock() {
+                            events.push(TabletEvent::StatusChanged(TabletStatus::Disconnected));
+                        }
+                        break;
+                    }
+                }
+            }
+            running.store(false, Ordering::SeqCst);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axes() -> EvdevAxes {
+        EvdevAxes {
+            x: AxisRange::new(0, 1000),
+            y: AxisRange::new(0, 500),
+            pressure: AxisRange::new(0, 4095),
+            tilt_x: Some(AxisRange::new(-64, 63)),
+            tilt_y: Some(AxisRange {
+                min: -64,
+                max: 63,
+                resolution: 57,
+            }),
+        }
+    }
+
+    fn ev(time_us: u64, event_type: u16, code: u16, value: i32) -> EvdevEvent {
+        EvdevEvent {
+            time_us,
+            event_type,
+            code,
+            value,
+        }
+    }
+
+    /// Hover in, touch down with the barrel button, lift, leave
+    fn pen_dump() -> Vec<u8> {
+        [
+            ev(1_000, EV_KEY, BTN_TOOL_PEN, 1),
+            ev(1_000, EV_MSC, MSC_SERIAL, 0x1234),
+            ev(1_000, EV_ABS, ABS_X, 500),
+            ev(1_000, EV_ABS, ABS_Y, 250),
+            ev(1_000, EV_SYN, SYN_REPORT, 0),
+            ev(6_000, EV_KEY, BTN_TOUCH, 1),
+            ev(6_000, EV_KEY, BTN_STYLUS, 1),
+            ev(6_000, EV_ABS, ABS_PRESSURE, 4095),
+            ev(6_000, EV_ABS, ABS_TILT_X, 30),
+            ev(6_000, EV_ABS, ABS_TILT_Y, 57),
+            ev(6_000, EV_SYN, SYN_REPORT, 0),
+            ev(11_000, EV_KEY, BTN_TOUCH, 0),
+            ev(11_000, EV_KEY, BTN_STYLUS, 0),
+            ev(11_000, EV_ABS, ABS_PRESSURE, 0),
+            ev(11_000, EV_SYN, SYN_REPORT, 0),
+            ev(16_000, EV_KEY, BTN_TOOL_PEN, 0),
+            ev(16_000, EV_SYN, SYN_REPORT, 0),
+        ]
+        .iter()
+        .flat_map(|e| e.to_bytes())
+        .collect()
+    }
+
+    #[test]
+    fn test_event_bytes_round_trip() {
+        let event = ev(1_234_567_890, EV_ABS, ABS_PRESSURE, -42);
+        assert_eq!(EvdevEvent::from_bytes(&event.to_bytes()), event);
+    }
+
+    #[test]
+    fn test_decode_pen_dump() {
+        let mut decoder = EvdevDecoder::new(axes());
+        decoder.set_output_size(1920.0, 1080.0);
+        let mut events = Vec::new();
+        decoder.feed_bytes(&pen_dump(), &mut events);
+
+        let [TabletEvent::ToolChanged(tool), TabletEvent::ProximityEnter, TabletEvent::Input(hover), TabletEvent::Input(down), TabletEvent::Input(up), TabletEvent::ProximityLeave] =
+            events.as_slice()
+        else {
+            panic!("unexpected events: {:?}", events);
+        };
+
+        assert_eq!(tool.pointer_type, PointerType::Pen);
+        assert_eq!(tool.serial, Some(0x1234));
+
+        assert_eq!((hover.x, hover.y), (960.0, 540.0));
+        assert_eq!(hover.pressure, 0.0);
+        assert_eq!(hover.timestamp_ms, 1);
+
+        assert_eq!(down.pressure, 1.0);
+        assert_eq!(down.buttons, buttons::TIP | buttons::LOWER);
+        // Degrees without resolution, radians via resolution
+        assert_eq!(down.tilt_x, 30.0);
+        assert!((down.tilt_y - 57.295_78).abs() < 1e-3);
+
+        assert_eq!(up.pressure, 0.0);
+        assert_eq!(up.buttons, 0);
+    }
+
+    #[test]
+    fn test_eraser_and_dropped_frames() {
+        let mut decoder = EvdevDecoder::new(axes());
+        let mut events = Vec::new();
+        for e in [
+            ev(0, EV_KEY, BTN_TOOL_RUBBER, 1),
+            ev(0, EV_SYN, SYN_REPORT, 0),
+            ev(1, EV_SYN, SYN_DROPPED, 0),
+            ev(1, EV_ABS, ABS_X, 1000),
+            ev(1, EV_SYN, SYN_REPORT, 0),
+            ev(2, EV_ABS, ABS_Y, 500),
+            ev(2, EV_SYN, SYN_REPORT, 0),
+        ] {
+            decoder.feed(e, &mut events);
+        }
+
+        assert!(matches!(
+            events[0],
+            TabletEvent::ToolChanged(ToolInfo {
+                pointer_type: PointerType::Eraser,
+                ..
+            })
+        ));
+        // The frame after SYN_DROPPED is discarded
+        let inputs: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                TabletEvent::Input(p) => Some((p.x, p.y)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(inputs, vec![(0.0, 0.0), (0.0, 500.0)]);
+    }
+
+    #[test]
+    fn test_resync_after_dropped_events() {
+        let mut decoder = EvdevDecoder::new(axes());
+        let mut bytes = Vec::new();
+        for e in [
+            ev(0, EV_KEY, BTN_TOOL_PEN, 1),
+            ev(0, EV_SYN, SYN_REPORT, 0),
+            ev(1, EV_SYN, SYN_DROPPED, 0),
+            ev(1, EV_ABS, ABS_X, 100),
+            ev(1, EV_SYN, SYN_REPORT, 0),
+            ev(2, EV_ABS, ABS_PRESSURE, 4095),
+            ev(2, EV_SYN, SYN_REPORT, 0),
+        ] {
+            bytes.extend_from_slice(&e.to_bytes());
+        }
+
+        // The device reports where the pen ended up during the gap
+        let mut resyncs = 0;
+        let mut events = Vec::new();
+        decoder.feed_bytes_with(&bytes, &mut events, |d| {
+            resyncs += 1;
+            d.set_abs(ABS_X, 750);
+            d.set_abs(ABS_Y, 250);
+        });
+
+        assert_eq!(resyncs, 1);
+        let Some(TabletEvent::Input(last)) = events.last() else {
+            panic!("unexpected events: {:?}", events);
+        };
+        assert_eq!((last.x, last.y, last.pressure), (750.0, 250.0, 1.0));
+    }
+
+    #[test]
+    fn test_normalize_full_i32_range() {
+        let axis = AxisRange::new(i32::MIN, i32::MAX);
+        assert_eq!(axis.span(), u32::MAX as i64);
+        assert_eq!(axis.normalize(i32::MIN), 0.0);
+        assert_eq!(axis.normalize(i32::MAX), 1.0);
+        assert!((axis.normalize(0) - 0.5).abs() < 1e-6);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_backend_reads_dump_file() -> Result<(), String> {
+        let path = std::env::temp_dir().join(format!("paintboard-{}.evdev", std::process::id()));
+        std::fs::write(&path, pen_dump()).map_err(|e| e.to_string())?;
+
+        let mut backend = EvdevBackend::open(EvdevDevice {
+            path: path.clone(),
+            name: "Recorded Pen".to_string(),
+            axes: Some(axes()),
+        });
+        backend.init(&TabletConfig::default())?;
+        backend.start()?;
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while backend.running.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        let mut events = Vec::new();
+        backend.poll(&mut events);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(backend.info().map(|i| i.pressure_range), Some((0, 4095)));
+        assert_eq!(events.len(), 6);
+        assert!(matches!(events[5], TabletEvent::ProximityLeave));
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_in_sysfs() -> Result<(), String> {
+        let root = std::env::temp_dir().join(format!("paintboard-evdev-{}", std::process::id()));
+        let write = |node: &str, name: &str, abs: &str, key: &str| -> std::io::Result<()> {
+            let dir = root.join(node).join("device");
+            std::fs::create_dir_all(dir.join("capabilities"))?;
+            std::fs::write(dir.join("name"), format!("{}\n", name))?;
+            std::fs::write(dir.join("capabilities/abs"), abs)?;
+            std::fs::write(dir.join("capabilities/key"), key)
+        };
+        // Pen: X, Y, pressure, tilt; BTN_TOOL_PEN is bit 0x140 (word 5)
+        write(
+            "event3",
+            "Wacom Intuos Pro Pen",
+            "1000d000003",
+            "1c03 0 0 0 0 0",
+        )
+        .map_err(|e| e.to_string())?;
+        // Keyboard: no absolute axes
+        write("event1", "AT Keyboard", "0", "ffff").map_err(|e| e.to_string())?;
+
+        let devices = discover_in(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].name, "Wacom Intuos Pro Pen");
+        assert_eq!(devices[0].path, Path::new("/dev/input/event3"));
+        Ok(())
+    }
+}
@@ -0,0 +1,507 @@
+//! Coordinate mapping - tablet surface to canvas space
+//!
+//! Tablet backends report positions on the tablet surface (normalized to
+//! 0-1). Mapping to canvas space runs through four transforms:
+//!
+//! 1. Tablet area: the active part of the tablet surface
+//! 2. Screen region: the monitor (or rectangle) the area maps to, in
+//!    virtual desktop pixels; with aspect locking, the tablet area is
+//!    trimmed so circles stay round
+//! 3. Window: desktop pixels to the window's client area (logical pixels)
+//! 4. Viewport: inverse of the canvas pan/zoom/rotation
+//!
+//! Mappings are configured per device, keyed by the tablet name. WinTab
+//! contexts already map the tablet onto the desktop, so WinTab enters at
+//! step 3.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::RawInputPoint;
+
+/// Axis-aligned rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// Create a rectangle
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The unit square (whole tablet surface)
+    pub fn unit() -> Self {
+        Self::new(0.0, 0.0, 1.0, 1.0)
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Rect::new(x, y, right - x, bottom - y)
+    }
+}
+
+/// A monitor in virtual desktop coordinates (physical pixels)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Monitor {
+    pub name: String,
+    pub bounds: Rect,
+}
+
+/// Part of the desktop the tablet area maps to
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ScreenRegion {
+    /// Bounding box of all monitors
+    #[default]
+    AllMonitors,
+    /// A single monitor by index
+    Monitor { index: usize },
+    /// An explicit desktop rectangle
+    Custom { rect: Rect },
+}
+
+impl ScreenRegion {
+    /// Desktop rectangle for a monitor layout (None if the monitor is unknown)
+    pub fn resolve(&self, monitors: &[Monitor]) -> Option<Rect> {
+        match *self {
+            Self::AllMonitors => monitors
+                .iter()
+                .map(|m| m.bounds)
+                .reduce(|acc, bounds| acc.union(&bounds)),
+            Self::Monitor { index } => monitors.get(index).map(|m| m.bounds),
+            Self::Custom { rect } => Some(rect),
+        }
+    }
+}
+
+/// Tablet-to-screen mapping for one device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletMapping {
+    /// Active tablet area (normalized 0-1)
+    pub tablet_area: Rect,
+    /// Physical tablet surface size (any unit, only the ratio matters)
+    pub tablet_size: (f32, f32),
+    /// Target screen region
+    pub screen: ScreenRegion,
+    /// Trim the tablet area to the screen region's aspect ratio
+    pub lock_aspect: bool,
+}
+
+impl Default for TabletMapping {
+    fn default() -> Self {
+        Self {
+            tablet_area: Rect::unit(),
+            tablet_size: (16.0, 10.0),
+            screen: ScreenRegion::AllMonitors,
+            lock_aspect: false,
+        }
+    }
+}
+
+impl TabletMapping {
+    /// Tablet area after aspect locking (centered inside the configured area)
+    pub fn effective_area(&self, region: &Rect) -> Rect {
+        let area = self.tablet_area;
+        if !self.lock_aspect || region.height <= 0.0 || area.height <= 0.0 {
+            return area;
+        }
+
+        let (tablet_w, tablet_h) = self.tablet_size;
+        let area_aspect = (area.width * tablet_w) / (area.height * tablet_h);
+        let region_aspect = region.width / region.height;
+
+        if area_aspect > region_aspect {
+            // Tablet area too wide: trim left and right
+            let width = area.width * region_aspect / area_aspect;
+            Rect::new(
+                area.x + (area.width - width) / 2.0,
+                area.y,
+                width,
+                area.height,
+            )
+        } else {
+            let height = area.height * area_aspect / region_aspect;
+            Rect::new(
+                area.x,
+                area.y + (area.height - height) / 2.0,
+                area.width,
+                height,
+            )
+        }
+    }
+}
+
+/// Window client area placement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    /// Client area origin in desktop pixels
+    pub origin_x: f32,
+    pub origin_y: f32,
+    /// Physical pixels per logical pixel
+    pub scale_factor: f32,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        Self {
+            origin_x: 0.0,
+            origin_y: 0.0,
+            scale_factor: 1.0,
+        }
+    }
+}
+
+/// Canvas viewport (window = offset + scale * rotate(canvas))
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewport {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub scale: f32,
+    /// Canvas rotation in radians (clockwise on screen)
+    pub rotation: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            scale: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Viewport {
+    /// Canvas point to window point
+    pub fn to_window(&self, x: f32, y: f32) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        (
+            self.offset_x + self.scale * (x * cos - y * sin),
+            self.offset_y + self.scale * (x * sin + y * cos),
+        )
+    }
+
+    /// Window point to canvas point
+    pub fn to_canvas(&self, x: f32, y: f32) -> (f32, f32) {
+        let scale = if self.scale.abs() > f32::EPSILON {
+            self.scale
+        } else {
+            1.0
+        };
+        let dx = (x - self.offset_x) / scale;
+        let dy = (y - self.offset_y) / scale;
+        let (sin, cos) = self.rotation.sin_cos();
+        (dx * cos + dy * sin, -dx * sin + dy * cos)
+    }
+}
+
+/// Coordinate space of points produced by a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InputSpace {
+    /// Normalized tablet surface (0-1)
+    Tablet,
+    /// Virtual desktop pixels
+    Desktop,
+    /// Window client area (logical pixels)
+    Window,
+    /// Already in canvas space
+    #[default]
+    Canvas,
+}
+
+/// Per-device tablet mappings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceMappings {
+    /// Mapping for devices without their own entry
+    pub default: TabletMapping,
+    /// Mappings keyed by device name
+    pub devices: HashMap<String, TabletMapping>,
+}
+
+impl DeviceMappings {
+    /// Mapping for a device
+    pub fn for_device(&self, name: Option<&str>) -> &TabletMapping {
+        name.and_then(|name| self.devices.get(name))
+            .unwrap_or(&self.default)
+    }
+}
+
+/// Maps backend coordinates to canvas space
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoordinateMapper {
+    pub monitors: Vec<Monitor>,
+    pub mappings: DeviceMappings,
+    /// Active device name (selects the mapping)
+    pub device: Option<String>,
+    pub window: WindowGeometry,
+    pub viewport: Viewport,
+}
+
+impl CoordinateMapper {
+    /// Active mapping
+    pub fn mapping(&self) -> &TabletMapping {
+        self.mappings.for_device(self.device.as_deref())
+    }
+
+    /// Tablet point (0-1) to desktop pixels
+    ///
+    /// Without a display layout (see `set_display_layout`) the point passes
+    /// through unchanged.
+    pub fn tablet_to_desktop(&self, u: f32, v: f32) -> (f32, f32) {
+        let mapping = self.mapping();
+        let Some(region) = mapping.screen.resolve(&self.monitors) else {
+            return (u, v);
+        };
+        let area = mapping.effective_area(&region);
+
+        let u = if area.width > 0.0 {
+            (u - area.x) / area.width
+        } else {
+            0.0
+        };
+        let v = if area.height > 0.0 {
+            (v - area.y) / area.height
+        } else {
+            0.0
+        };
+        (
+            region.x + u.clamp(0.0, 1.0) * region.width,
+            region.y + v.clamp(0.0, 1.0) * region.height,
+        )
+    }
+
+    /// Desktop pixels to window logical pixels
+    pub fn desktop_to_window(&self, x: f32, y: f32) -> (f32, f32) {
+        let scale = self.window.scale_factor.max(f32::EPSILON);
+        (
+            (x - self.window.origin_x) / scale,
+            (y - self.window.origin_y) / scale,
+        )
+    }
+
+    /// Map a position from `space` to canvas space
+    pub fn to_canvas(&self, x: f32, y: f32, space: InputSpace) -> (f32, f32) {
+        let (x, y) = match space {
+            InputSpace::Tablet => self.tablet_to_desktop(x, y),
+            _ => (x, y),
+        };
+        let (x, y) = match space {
+            InputSpace::Tablet | InputSpace::Desktop => self.desktop_to_window(x, y),
+            _ => (x, y),
+        };
+        match space {
+            InputSpace::Canvas => (x, y),
+            _ => self.viewport.to_canvas(x, y),
+        }
+    }
+
+    /// Map a point to canvas space (tilt follows the canvas rotation)
+    pub fn map_point(&self, point: RawInputPoint, space: InputSpace) -> RawInputPoint {
+        if space == InputSpace::Canvas {
+            return point;
+        }
+        let (x, y) = self.to_canvas(point.x, point.y, space);
+        let (sin, cos) = self.viewport.rotation.sin_cos();
+        RawInputPoint {
+            x,
+            y,
+            tilt_x: (point.tilt_x * cos + point.tilt_y * sin).clamp(-90.0, 90.0),
+            tilt_y: (-point.tilt_x * sin + point.tilt_y * cos).clamp(-90.0, 90.0),
+            ..point
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    /// 1080p primary with a 1440p monitor to its right, top-aligned
+    fn dual_monitors() -> Vec<Monitor> {
+        vec![
+            Monitor {
+                name: "Primary".to_string(),
+                bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+            },
+            Monitor {
+                name: "Cintiq".to_string(),
+                bounds: Rect::new(1920.0, 0.0, 2560.0, 1440.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_screen_regions() {
+        let monitors = dual_monitors();
+        assert_eq!(
+            ScreenRegion::AllMonitors.resolve(&monitors),
+            Some(Rect::new(0.0, 0.0, 4480.0, 1440.0))
+        );
+        assert_eq!(
+            ScreenRegion::Monitor { index: 1 }.resolve(&monitors),
+            Some(Rect::new(1920.0, 0.0, 2560.0, 1440.0))
+        );
+        assert_eq!(ScreenRegion::Monitor { index: 5 }.resolve(&monitors), None);
+        assert_eq!(ScreenRegion::AllMonitors.resolve(&[]), None);
+    }
+
+    #[test]
+    fn test_tablet_to_single_monitor() {
+        let mut mapper = CoordinateMapper {
+            monitors: dual_monitors(),
+            ..Default::default()
+        };
+        mapper.mappings.default.screen = ScreenRegion::Monitor { index: 1 };
+
+        assert!(close(mapper.tablet_to_desktop(0.0, 0.0), (1920.0, 0.0)));
+        assert!(close(mapper.tablet_to_desktop(0.5, 0.5), (3200.0, 720.0)));
+        assert!(close(mapper.tablet_to_desktop(1.0, 1.0), (4480.0, 1440.0)));
+    }
+
+    #[test]
+    fn test_tablet_area_and_aspect_lock() {
+        // 16:10 tablet on a 16:9 monitor
+        let mut mapper = CoordinateMapper {
+            monitors: dual_monitors(),
+            ..Default::default()
+        };
+        mapper.mappings.default = TabletMapping {
+            screen: ScreenRegion::Monitor { index: 0 },
+            lock_aspect: true,
+            ..Default::default()
+        };
+
+        let region = Rect::new(0.0, 0.0, 1920.0, 1080.0);
+        let area = mapper.mapping().effective_area(&region);
+        assert!((area.width - 1.0).abs() < 1e-6);
+        assert!((area.height - 0.9).abs() < 1e-4);
+        assert!((area.y - 0.05).abs() < 1e-4);
+
+        // Top of the trimmed area hits the top of the screen
+        assert!(close(mapper.tablet_to_desktop(0.5, 0.05), (960.0, 0.0)));
+        // Outside the area clamps to the screen edge
+        assert!(close(mapper.tablet_to_desktop(0.5, 0.0), (960.0, 0.0)));
+
+        // Custom area: left half of the tablet covers the whole screen
+        mapper.mappings.default.lock_aspect = false;
+        mapper.mappings.default.tablet_area = Rect::new(0.0, 0.0, 0.5, 1.0);
+        assert!(close(mapper.tablet_to_desktop(0.25, 0.5), (960.0, 540.0)));
+    }
+
+    #[test]
+    fn test_no_layout_passes_through() {
+        let mapper = CoordinateMapper::default();
+        assert!(close(mapper.tablet_to_desktop(0.25, 0.75), (0.25, 0.75)));
+
+        // Desktop pixels (WinTab) are unchanged until a layout arrives
+        let point = RawInputPoint::new(1234.0, 567.0, 0.5);
+        let mapped = mapper.map_point(point, InputSpace::Desktop);
+        assert!(close((mapped.x, mapped.y), (1234.0, 567.0)));
+        let mapped = mapper.map_point(point, InputSpace::Tablet);
+        assert!(close((mapped.x, mapped.y), (1234.0, 567.0)));
+    }
+
+    #[test]
+    fn test_per_device_mapping() {
+        let mut mapper = CoordinateMapper {
+            monitors: dual_monitors(),
+            ..Default::default()
+        };
+        mapper.mappings.devices.insert(
+            "Cintiq Pro".to_string(),
+            TabletMapping {
+                screen: ScreenRegion::Monitor { index: 1 },
+                ..Default::default()
+            },
+        );
+
+        assert!(close(mapper.tablet_to_desktop(0.0, 0.0), (0.0, 0.0)));
+        mapper.device = Some("Cintiq Pro".to_string());
+        assert!(close(mapper.tablet_to_desktop(0.0, 0.0), (1920.0, 0.0)));
+    }
+
+    #[test]
+    fn test_window_and_viewport() {
+        let mapper = CoordinateMapper {
+            monitors: dual_monitors(),
+            window: WindowGeometry {
+                origin_x: 2020.0,
+                origin_y: 100.0,
+                scale_factor: 2.0,
+            },
+            viewport: Viewport {
+                offset_x: 50.0,
+                offset_y: 20.0,
+                scale: 2.0,
+                rotation: FRAC_PI_2,
+            },
+            ..Default::default()
+        };
+
+        // Desktop (2120, 140) is window (50, 20): the canvas origin
+        assert!(close(
+            mapper.to_canvas(2120.0, 140.0, InputSpace::Desktop),
+            (0.0, 0.0)
+        ));
+
+        // Round trip through the viewport
+        let (wx, wy) = mapper.viewport.to_window(30.0, -12.0);
+        assert!(close(
+            mapper.to_canvas(wx, wy, InputSpace::Window),
+            (30.0, -12.0)
+        ));
+
+        // Rotated 90°: window +x is canvas -y
+        assert!(close(
+            mapper.to_canvas(52.0, 20.0, InputSpace::Window),
+            (0.0, -1.0)
+        ));
+    }
+
+    #[test]
+    fn test_map_point_rotates_tilt() {
+        let mapper = CoordinateMapper {
+            viewport: Viewport {
+                rotation: FRAC_PI_2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let point = RawInputPoint::with_tilt(10.0, 0.0, 0.5, 30.0, 0.0);
+
+        let mapped = mapper.map_point(point, InputSpace::Window);
+        assert!(close((mapped.x, mapped.y), (0.0, -10.0)));
+        assert!(close((mapped.tilt_x, mapped.tilt_y), (0.0, -30.0)));
+        assert_eq!(mapped.pressure, 0.5);
+
+        assert_eq!(mapper.map_point(point, InputSpace::Canvas), point);
+    }
+}
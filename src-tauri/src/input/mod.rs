@@ -1,24 +1,54 @@
 //! Input module - handles tablet/pen input processing
 
 mod backend;
+mod calibration;
+mod clock;
+mod evdev_backend;
+mod filter;
+mod mapping;
 mod pointer_backend;
 mod processor;
+mod recording;
+mod replay_backend;
+mod resampler;
+mod synthetic_backend;
 mod tablet;
 pub mod wintab_backend;
 pub mod wintab_spike;
 
 pub use backend::{
-    PressureCurve, TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus,
+    buttons, PointerType, TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus,
+    ToolInfo, ToolTracker,
+};
+pub use calibration::{
+    CalibrationProfile, CalibrationRecorder, CalibrationStore, MIN_CALIBRATION_SAMPLES,
+};
+pub use clock::{monotonic_us, DeviceClock};
+pub use evdev_backend::{
+    AxisRange, EvdevAxes, EvdevBackend, EvdevDecoder, EvdevDevice, EvdevEvent, INPUT_EVENT_SIZE,
+};
+pub use filter::{
+    InputFilter, KalmanConfig, KalmanFilter, OneEuroConfig, OneEuroFilter, OneEuroPointFilter,
+};
+pub use mapping::{
+    CoordinateMapper, DeviceMappings, InputSpace, Monitor, Rect, ScreenRegion, TabletMapping,
+    Viewport, WindowGeometry,
 };
 pub use pointer_backend::PointerEventBackend;
-pub use processor::{InputProcessor, PressureSmoother};
+pub use processor::{InputProcessor, InputProcessorConfig, PressureSmoother};
+pub use recording::{InputRecorder, InputRecording, RecordedEvent, RECORDING_VERSION};
+pub use replay_backend::{ReplayBackend, ReplaySpeed};
+pub use resampler::Resampler;
+pub use synthetic_backend::{
+    PressureProfile, SyntheticBackend, SyntheticConfig, SyntheticShape, SyntheticStroke,
+};
 pub use tablet::TabletManager;
 pub use wintab_backend::WinTabBackend;
 
 use serde::{Deserialize, Serialize};
 
 /// Raw input point from the tablet/pen
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct RawInputPoint {
     /// X coordinate in canvas space
     pub x: f32,
@@ -30,44 +60,92 @@ pub struct RawInputPoint {
     pub tilt_x: f32,
     /// Tilt Y angle in degrees (-90 to 90)
     pub tilt_y: f32,
-    /// Timestamp in milliseconds (high precision)
+    /// Sample time in milliseconds (`timestamp_us / 1000`)
     pub timestamp_ms: u64,
+    /// Sample time in microseconds on the input clock (see `monotonic_us`)
+    #[serde(default)]
+    pub timestamp_us: u64,
+    /// When the host received the sample (input clock microseconds)
+    #[serde(default)]
+    pub arrival_us: u64,
+    /// Barrel rotation in degrees (0 - 360), from pens that report twist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f32>,
+    /// Tangential pressure (-1.0 - 1.0), e.g. an airbrush finger wheel;
+    /// the Wheel control source maps it onto 0 - 1 as `(v + 1) / 2`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tangential_pressure: Option<f32>,
+    /// Pressed buttons (see `buttons`)
+    #[serde(default)]
+    pub buttons: u32,
 }
 
 impl RawInputPoint {
     /// Create a new input point
     pub fn new(x: f32, y: f32, pressure: f32) -> Self {
+        let now_us = monotonic_us();
         Self {
             x,
             y,
             pressure: pressure.clamp(0.0, 1.0),
             tilt_x: 0.0,
             tilt_y: 0.0,
-            timestamp_ms: current_time_ms(),
+            timestamp_ms: now_us / 1000,
+            timestamp_us: now_us,
+            arrival_us: now_us,
+            rotation: None,
+            tangential_pressure: None,
+            buttons: 0,
         }
     }
 
     /// Create with full parameters
     pub fn with_tilt(x: f32, y: f32, pressure: f32, tilt_x: f32, tilt_y: f32) -> Self {
+        let now_us = monotonic_us();
         Self {
             x,
             y,
             pressure: pressure.clamp(0.0, 1.0),
             tilt_x: tilt_x.clamp(-90.0, 90.0),
             tilt_y: tilt_y.clamp(-90.0, 90.0),
-            timestamp_ms: current_time_ms(),
+            timestamp_ms: now_us / 1000,
+            timestamp_us: now_us,
+            arrival_us: now_us,
+            rotation: None,
+            tangential_pressure: None,
+            buttons: 0,
         }
     }
-}
 
-/// Get current time in milliseconds
-fn current_time_ms() -> u64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
+    /// Set the sample time (microseconds; arrival time is kept)
+    pub fn with_timestamp_us(mut self, timestamp_us: u64) -> Self {
+        self.timestamp_us = timestamp_us;
+        self.timestamp_ms = timestamp_us / 1000;
+        self
+    }
+
+    /// Delay between sampling and arrival at the host (microseconds)
+    pub fn latency_us(&self) -> u64 {
+        self.arrival_us.saturating_sub(self.timestamp_us)
+    }
+
+    /// Set barrel rotation (degrees, wrapped to 0 - 360)
+    pub fn with_rotation(mut self, rotation: Option<f32>) -> Self {
+        self.rotation = rotation.map(|r| r.rem_euclid(360.0));
+        self
+    }
 
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
+    /// Set pressed buttons (see `buttons`)
+    pub fn with_buttons(mut self, buttons: u32) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// Set tangential pressure (clamped to -1.0 - 1.0)
+    pub fn with_tangential_pressure(mut self, tangential_pressure: Option<f32>) -> Self {
+        self.tangential_pressure = tangential_pressure.map(|t| t.clamp(-1.0, 1.0));
+        self
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,10 @@
 //! This is the fallback backend when WinTab is not available.
 //! Input is received from the frontend via Tauri commands.
 
-use super::backend::{TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus};
+use super::backend::{
+    TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus, ToolInfo, ToolTracker,
+};
+use super::clock::{monotonic_us, DeviceClock};
 use super::RawInputPoint;
 use std::sync::{Arc, Mutex};
 
@@ -14,7 +17,9 @@ pub struct PointerEventBackend {
     info: Option<TabletInfo>,
     config: TabletConfig,
     events: Arc<Mutex<Vec<TabletEvent>>>,
-    pressure_curve: super::backend::PressureCurve,
+    tool: Mutex<ToolTracker>,
+    /// Maps `PointerEvent.timeStamp` onto the input clock
+    clock: Mutex<DeviceClock>,
 }
 
 impl PointerEventBackend {
@@ -25,29 +30,44 @@ impl PointerEventBackend {
             info: None,
             config: TabletConfig::default(),
             events: Arc::new(Mutex::new(Vec::with_capacity(64))),
-            pressure_curve: super::backend::PressureCurve::Linear,
+            tool: Mutex::new(ToolTracker::default()),
+            clock: Mutex::new(DeviceClock::new()),
         }
     }
 
     /// Push input from frontend PointerEvent
     /// Called by Tauri command when frontend receives pointer events
     pub fn push_input(&self, x: f32, y: f32, pressure: f32, tilt_x: f32, tilt_y: f32) {
-        let adjusted_pressure = self.pressure_curve.apply(pressure);
-
-        let point = RawInputPoint {
-            x,
-            y,
-            pressure: adjusted_pressure,
-            tilt_x: tilt_x.clamp(-90.0, 90.0),
-            tilt_y: tilt_y.clamp(-90.0, 90.0),
-            timestamp_ms: super::current_time_ms(),
-        };
+        self.push_point(RawInputPoint::with_tilt(x, y, pressure, tilt_x, tilt_y));
+    }
 
+    /// Push a full input point (rotation, tangential pressure)
+    pub fn push_point(&self, point: RawInputPoint) {
         if let Ok(mut events) = self.events.lock() {
             events.push(TabletEvent::Input(point));
         }
     }
 
+    /// Push a point sampled at `device_time_us` on the page clock
+    pub fn push_point_at(&self, point: RawInputPoint, device_time_us: u64) {
+        let arrival_us = monotonic_us();
+        let point = match self.clock.lock() {
+            Ok(mut clock) => clock.stamp(point.with_timestamp_us(device_time_us), arrival_us),
+            Err(_) => point,
+        };
+        self.push_point(point);
+    }
+
+    /// Report the tool of the next points (emits `ToolChanged` on change)
+    pub fn push_tool(&self, tool: ToolInfo) {
+        let Some(event) = self.tool.lock().ok().and_then(|mut t| t.update(tool)) else {
+            return;
+        };
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+
     /// Push proximity enter event
     pub fn push_proximity_enter(&self) {
         if let Ok(mut events) = self.events.lock() {
@@ -77,7 +97,6 @@ impl Default for PointerEventBackend {
 impl TabletBackend for PointerEventBackend {
     fn init(&mut self, config: &TabletConfig) -> Result<(), String> {
         self.config = config.clone();
-        self.pressure_curve = config.pressure_curve;
 
         self.info = Some(TabletInfo {
             name: "PointerEvent".to_string(),
@@ -178,4 +197,81 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_pointer_backend_keeps_page_clock_spacing() {
+        let backend = PointerEventBackend::new();
+        // Second event sampled 4 ms after the first but delivered late
+        backend.push_point_at(RawInputPoint::new(0.0, 0.0, 0.5), 1_000_000);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        backend.push_point_at(RawInputPoint::new(1.0, 0.0, 0.5), 1_004_000);
+
+        let Ok(events) = backend.events.lock() else {
+            panic!("events lock");
+        };
+        let [TabletEvent::Input(first), TabletEvent::Input(second)] = events.as_slice() else {
+            panic!("Expected two Input events");
+        };
+        assert_eq!(second.timestamp_us - first.timestamp_us, 4_000);
+        assert!(second.latency_us() >= 6_000);
+    }
+
+    #[test]
+    fn test_pointer_backend_push_point_with_pen_axes() -> Result<(), String> {
+        let mut backend = PointerEventBackend::new();
+        backend.init(&TabletConfig::default())?;
+        backend.start()?;
+
+        backend.push_point(
+            RawInputPoint::new(1.0, 2.0, 0.5)
+                .with_rotation(Some(-90.0))
+                .with_tangential_pressure(Some(0.3)),
+        );
+        backend.push_input(3.0, 4.0, 0.5, 0.0, 0.0);
+
+        let mut events = Vec::new();
+        backend.poll(&mut events);
+
+        let [TabletEvent::Input(art_pen), TabletEvent::Input(plain)] = events.as_slice() else {
+            panic!("Expected two Input events");
+        };
+        assert_eq!(art_pen.rotation, Some(270.0));
+        assert_eq!(art_pen.tangential_pressure, Some(0.3));
+        assert_eq!(plain.rotation, None);
+        assert_eq!(plain.tangential_pressure, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pointer_backend_tool_changes() -> Result<(), String> {
+        use crate::input::PointerType;
+
+        let mut backend = PointerEventBackend::new();
+        backend.init(&TabletConfig::default())?;
+        backend.start()?;
+
+        let pen = ToolInfo {
+            pointer_type: PointerType::Pen,
+            cursor_id: 1,
+            serial: None,
+        };
+        let eraser = ToolInfo {
+            pointer_type: PointerType::Eraser,
+            ..pen
+        };
+        backend.push_tool(pen);
+        backend.push_tool(pen);
+        backend.push_tool(eraser);
+
+        let mut events = Vec::new();
+        backend.poll(&mut events);
+
+        let [TabletEvent::ToolChanged(first), TabletEvent::ToolChanged(second)] = events.as_slice()
+        else {
+            panic!("Expected two ToolChanged events, got {:?}", events);
+        };
+        assert_eq!(first.pointer_type, PointerType::Pen);
+        assert_eq!(second.pointer_type, PointerType::Eraser);
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@
 
 use std::collections::VecDeque;
 
-use super::RawInputPoint;
+use super::{InputFilter, KalmanFilter, OneEuroPointFilter, RawInputPoint, Resampler};
 
 /// Pressure smoother - smooths pressure values using a sliding window average.
 ///
@@ -92,6 +92,10 @@ pub struct InputProcessorConfig {
     pub prediction_points: usize,
     /// Window size for pressure smoothing (0 to disable)
     pub pressure_smoothing_window: usize,
+    /// Adaptive filter applied after pressure smoothing
+    pub filter: InputFilter,
+    /// Resample to a uniform rate (Hz) before any other processing
+    pub resample_hz: Option<u32>,
 }
 
 impl Default for InputProcessorConfig {
@@ -101,6 +105,8 @@ impl Default for InputProcessorConfig {
             prediction_enabled: true,
             prediction_points: 3,
             pressure_smoothing_window: 3,
+            filter: InputFilter::Off,
+            resample_hz: None,
         }
     }
 }
@@ -111,6 +117,10 @@ pub struct InputProcessor {
     history: Vec<RawInputPoint>,
     last_point: Option<RawInputPoint>,
     pressure_smoother: Option<PressureSmoother>,
+    one_euro: Option<OneEuroPointFilter>,
+    /// Always tracks accepted motion; drives prediction
+    kalman: KalmanFilter,
+    resampler: Option<Resampler>,
 }
 
 impl InputProcessor {
@@ -127,7 +137,21 @@ impl InputProcessor {
             None
         };
 
+        let one_euro = match config.filter {
+            InputFilter::OneEuro { position, pressure } => {
+                Some(OneEuroPointFilter::new(position, pressure))
+            }
+            _ => None,
+        };
+        let kalman = match config.filter {
+            InputFilter::Kalman(kalman) => KalmanFilter::new(kalman),
+            _ => KalmanFilter::default(),
+        };
+
         Self {
+            one_euro,
+            kalman,
+            resampler: config.resample_hz.map(Resampler::new),
             config,
             history: Vec::with_capacity(16),
             last_point: None,
@@ -135,14 +159,36 @@ impl InputProcessor {
         }
     }
 
-    /// Process a new input point
+    /// Process a new input point, through the resampler when enabled
+    /// Returns every point to use, in order (none if filtered out)
+    pub fn process(&mut self, point: RawInputPoint) -> Vec<RawInputPoint> {
+        match self.resampler.as_mut() {
+            Some(resampler) => resampler
+                .process(point)
+                .into_iter()
+                .filter_map(|point| self.process_point(point))
+                .collect(),
+            None => self.process_point(point).into_iter().collect(),
+        }
+    }
+
+    /// Filter one (already resampled) point
     /// Returns Some if the point should be used, None if filtered out
-    pub fn process(&mut self, mut point: RawInputPoint) -> Option<RawInputPoint> {
+    fn process_point(&mut self, mut point: RawInputPoint) -> Option<RawInputPoint> {
         // Apply pressure smoothing first
         if let Some(ref mut smoother) = self.pressure_smoother {
             point.pressure = smoother.smooth(point.pressure);
         }
 
+        if let Some(ref mut one_euro) = self.one_euro {
+            point = one_euro.filter(point);
+        }
+
+        let filtered = self.kalman.update(point);
+        if matches!(self.config.filter, InputFilter::Kalman(_)) {
+            point = filtered;
+        }
+
         // Check minimum distance
         if let Some(last) = self.last_point {
             let dx = point.x - last.x;
@@ -164,34 +210,40 @@ impl InputProcessor {
         Some(point)
     }
 
-    /// Get a predicted next point based on velocity
+    /// End the stroke: returns the rest of the resampled stroke, then
+    /// resets the processor
+    pub fn finish_stroke(&mut self) -> Vec<RawInputPoint> {
+        let tail = self.resampler.as_mut().map(Resampler::finish);
+        let points: Vec<RawInputPoint> = tail
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|point| self.process_point(point))
+            .collect();
+        self.reset();
+        points
+    }
+
+    /// Get a predicted next point based on velocity and acceleration
     pub fn predict_next(&self) -> Option<RawInputPoint> {
         if !self.config.prediction_enabled || self.history.len() < 2 {
             return None;
         }
 
-        let len = self.history.len();
-        let p1 = &self.history[len - 2];
-        let p2 = &self.history[len - 1];
-
-        // Simple linear prediction
-        let vx = p2.x - p1.x;
-        let vy = p2.y - p1.y;
-
-        Some(RawInputPoint {
-            x: p2.x + vx,
-            y: p2.y + vy,
-            pressure: p2.pressure,
-            tilt_x: p2.tilt_x,
-            tilt_y: p2.tilt_y,
-            timestamp_ms: p2.timestamp_ms + 8, // Assume ~8ms ahead
-        })
+        // Kalman velocity + acceleration, one measured report interval ahead
+        self.kalman.predict_next()
     }
 
     /// Reset the processor state (call when stroke ends)
     pub fn reset(&mut self) {
         self.history.clear();
         self.last_point = None;
+        self.kalman.reset();
+        if let Some(ref mut one_euro) = self.one_euro {
+            one_euro.reset();
+        }
+        if let Some(ref mut resampler) = self.resampler {
+            resampler.reset();
+        }
         if let Some(ref mut smoother) = self.pressure_smoother {
             smoother.reset();
         }
@@ -296,23 +348,24 @@ mod tests {
 
         // First point always passes
         let p1 = RawInputPoint::new(0.0, 0.0, 0.5);
-        assert!(processor.process(p1).is_some());
+        assert!(processor.process_point(p1).is_some());
 
         // Too close - should be filtered
         let p2 = RawInputPoint::new(1.0, 1.0, 0.5);
-        assert!(processor.process(p2).is_none());
+        assert!(processor.process_point(p2).is_none());
 
         // Far enough - should pass
         let p3 = RawInputPoint::new(10.0, 10.0, 0.5);
-        assert!(processor.process(p3).is_some());
+        assert!(processor.process_point(p3).is_some());
     }
 
     #[test]
     fn test_prediction() {
         let mut processor = InputProcessor::new();
+        let at = |x: f32, t: u64| RawInputPoint::new(x, x, 0.5).with_timestamp_us(t * 1000);
 
-        processor.process(RawInputPoint::new(0.0, 0.0, 0.5));
-        processor.process(RawInputPoint::new(10.0, 10.0, 0.5));
+        processor.process_point(at(0.0, 0));
+        processor.process_point(at(10.0, 10));
 
         let predicted = processor.predict_next();
         assert!(predicted.is_some());
@@ -320,8 +373,10 @@ mod tests {
         let Some(p) = predicted else {
             panic!("prediction should exist");
         };
-        assert_eq!(p.x, 20.0);
-        assert_eq!(p.y, 20.0);
+        assert!((p.x - 20.0).abs() < 0.1);
+        assert!((p.y - 20.0).abs() < 0.1);
+        // One measured report interval ahead
+        assert_eq!(p.timestamp_ms, 20);
     }
 
     #[test]
@@ -334,17 +389,17 @@ mod tests {
 
         // First point: initializes buffer, returns 0.3
         let p1 = RawInputPoint::new(0.0, 0.0, 0.3);
-        let result1 = processor.process(p1).unwrap();
+        let result1 = processor.process_point(p1).unwrap();
         assert!((result1.pressure - 0.3).abs() < 0.01);
 
         // Second point: buffer = [0.3, 0.3, 0.6], avg = 0.4
         let p2 = RawInputPoint::new(1.0, 0.0, 0.6);
-        let result2 = processor.process(p2).unwrap();
+        let result2 = processor.process_point(p2).unwrap();
         assert!((result2.pressure - 0.4).abs() < 0.01);
 
         // Third point: buffer = [0.3, 0.6, 0.9], avg = 0.6
         let p3 = RawInputPoint::new(2.0, 0.0, 0.9);
-        let result3 = processor.process(p3).unwrap();
+        let result3 = processor.process_point(p3).unwrap();
         assert!((result3.pressure - 0.6).abs() < 0.01);
     }
 
@@ -352,14 +407,14 @@ mod tests {
     fn test_processor_reset_clears_pressure_smoother() {
         let mut processor = InputProcessor::new();
 
-        processor.process(RawInputPoint::new(0.0, 0.0, 0.5));
-        processor.process(RawInputPoint::new(10.0, 10.0, 0.8));
+        processor.process_point(RawInputPoint::new(0.0, 0.0, 0.5));
+        processor.process_point(RawInputPoint::new(10.0, 10.0, 0.8));
 
         processor.reset();
 
         // After reset, first point initializes buffer again
         let p = RawInputPoint::new(0.0, 0.0, 0.2);
-        let result = processor.process(p).unwrap();
+        let result = processor.process_point(p).unwrap();
         assert!((result.pressure - 0.2).abs() < 0.01);
     }
 
@@ -373,11 +428,99 @@ mod tests {
 
         // Pressure should pass through unchanged
         let p1 = RawInputPoint::new(0.0, 0.0, 0.3);
-        let result1 = processor.process(p1).unwrap();
+        let result1 = processor.process_point(p1).unwrap();
         assert_eq!(result1.pressure, 0.3);
 
         let p2 = RawInputPoint::new(1.0, 0.0, 0.9);
-        let result2 = processor.process(p2).unwrap();
+        let result2 = processor.process_point(p2).unwrap();
         assert_eq!(result2.pressure, 0.9);
     }
+
+    #[test]
+    fn test_processor_resamples_bursts() {
+        let mut processor = InputProcessor::with_config(InputProcessorConfig {
+            min_distance: 0.0,
+            pressure_smoothing_window: 0,
+            resample_hz: Some(250),
+            ..Default::default()
+        });
+
+        // 1 px/ms, delivered in bursts of three
+        let mut points = Vec::new();
+        for burst in 0..5u64 {
+            for i in 0..3u64 {
+                let t = burst * 12_000 + i * 1_000;
+                points.extend(
+                    processor.process(
+                        RawInputPoint::new(t as f32 / 1000.0, 0.0, 0.5).with_timestamp_us(t),
+                    ),
+                );
+            }
+        }
+        points.extend(processor.finish_stroke());
+
+        let Some(last) = points.last() else {
+            panic!("stroke should produce points");
+        };
+        assert_eq!(last.timestamp_us, 50_000);
+        assert!(points
+            .windows(2)
+            .take(points.len() - 2)
+            .all(|w| w[1].timestamp_us - w[0].timestamp_us == 4_000));
+        assert!(points
+            .iter()
+            .all(|p| (p.x - p.timestamp_ms as f32).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_processor_one_euro_smooths_jitter() {
+        use crate::input::filter::tests::jittered_line;
+        use crate::input::OneEuroConfig;
+
+        let mut processor = InputProcessor::with_config(InputProcessorConfig {
+            min_distance: 0.0,
+            pressure_smoothing_window: 0,
+            filter: InputFilter::OneEuro {
+                position: OneEuroConfig::position(),
+                pressure: OneEuroConfig::pressure(),
+            },
+            ..Default::default()
+        });
+
+        let ys: Vec<f32> = jittered_line(0.0, 100)
+            .into_iter()
+            .filter_map(|(p, _)| processor.process_point(p))
+            .skip(20)
+            .map(|p| p.y)
+            .collect();
+        let max = ys.iter().copied().fold(f32::MIN, f32::max);
+        let min = ys.iter().copied().fold(f32::MAX, f32::min);
+        assert!(max - min < 0.5, "spread {}", max - min);
+    }
+
+    #[test]
+    fn test_processor_kalman_prediction_bounded_error() {
+        use crate::input::filter::tests::jittered_line;
+        use crate::input::KalmanConfig;
+
+        let mut processor = InputProcessor::with_config(InputProcessorConfig {
+            min_distance: 0.0,
+            filter: InputFilter::Kalman(KalmanConfig::default()),
+            ..Default::default()
+        });
+
+        let trace = jittered_line(500.0, 80);
+        for (point, _) in &trace {
+            processor.process_point(*point);
+        }
+
+        let Some(predicted) = processor.predict_next() else {
+            panic!("prediction should exist");
+        };
+        // Truth one interval (8 ms) past the last sample
+        let truth = 500.0 * 80.0 * 0.008;
+        assert!((predicted.x - truth).abs() < 2.0, "{}", predicted.x);
+        assert_eq!(predicted.timestamp_ms, 79 * 8 + 8);
+        assert_eq!(predicted.timestamp_us, (79 * 8 + 8) * 1000);
+    }
 }
@@ -0,0 +1,469 @@
+//! Input recording - capture tablet events for deterministic replay
+//!
+//! Recordings store every `TabletEvent` as it came out of the backend
+//! (before mapping and smoothing), so replaying one runs the full
+//! input pipeline again. Use with `ReplayBackend`.
+//!
+//! File format (little-endian):
+//! - Header: magic `PBIR`, version (u16), backend name (u8 length + UTF-8)
+//! - Records until end of file: time since the previous record in
+//!   microseconds (u32), event tag (u8), event payload
+//!
+//! Version 2 stores input sample and arrival times in microseconds;
+//! version 1 files (millisecond timestamps) still load.
+
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use super::backend::{PointerType, TabletEvent, TabletStatus, ToolInfo};
+use super::RawInputPoint;
+
+/// File magic
+const MAGIC: &[u8; 4] = b"PBIR";
+
+/// Current format version
+pub const RECORDING_VERSION: u16 = 2;
+
+// Event tags
+const TAG_INPUT: u8 = 0;
+const TAG_PROXIMITY_ENTER: u8 = 1;
+const TAG_PROXIMITY_LEAVE: u8 = 2;
+const TAG_STATUS: u8 = 3;
+const TAG_TOOL: u8 = 4;
+
+// Optional field flags for input points
+const HAS_ROTATION: u8 = 1 << 0;
+const HAS_TANGENTIAL: u8 = 1 << 1;
+
+/// A recorded event with its monotonic time
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// Microseconds since the recording started
+    pub time_us: u64,
+    /// The event as emitted by the backend
+    pub event: TabletEvent,
+}
+
+/// A complete input recording
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    /// Name of the backend that produced the events
+    pub backend: String,
+    /// Events in time order
+    pub events: Vec<RecordedEvent>,
+}
+
+impl InputRecording {
+    /// Duration from the first to the last event (microseconds)
+    pub fn duration_us(&self) -> u64 {
+        self.events.last().map_or(0, |e| e.time_us)
+    }
+
+    /// Serialize to the binary format
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), String> {
+        write_recording(self, writer).map_err(|e| format!("Failed to write recording: {}", e))
+    }
+
+    /// Parse the binary format
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, String> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read recording: {}", e))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(16 + self.events.len() * 40);
+        // Writing to a Vec cannot fail
+        let _ = self.write_to(&mut data);
+        data
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut cursor = Cursor::new(data);
+
+        let mut magic = [0u8; 4];
+        cursor
+            .read_exact(&mut magic)
+            .map_err(|_| "Not an input recording".to_string())?;
+        if &magic != MAGIC {
+            return Err("Not an input recording".to_string());
+        }
+
+        let version = cursor
+            .read_u16::<LittleEndian>()
+            .map_err(|e| format!("Truncated header: {}", e))?;
+        if !(1..=RECORDING_VERSION).contains(&version) {
+            return Err(format!("Unsupported recording version {}", version));
+        }
+
+        let backend = read_name(&mut cursor).map_err(|e| format!("Truncated header: {}", e))?;
+
+        let mut events = Vec::new();
+        let mut time_us = 0u64;
+        while (cursor.position() as usize) < data.len() {
+            let delta = cursor
+                .read_u32::<LittleEndian>()
+                .map_err(|e| format!("Truncated event {}: {}", events.len(), e))?;
+            time_us += delta as u64;
+            let event = read_event(&mut cursor, version)
+                .map_err(|e| format!("Invalid event {}: {}", events.len(), e))?;
+            events.push(RecordedEvent { time_us, event });
+        }
+
+        Ok(Self { backend, events })
+    }
+
+    /// Save to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| format!("Failed to save recording: {}", e))
+    }
+
+    /// Load from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("Failed to load recording: {}", e))?;
+        Self::from_bytes(&data)
+    }
+}
+
+/// Captures events with monotonic timestamps
+#[derive(Debug, Clone)]
+pub struct InputRecorder {
+    start: Instant,
+    recording: InputRecording,
+}
+
+impl InputRecorder {
+    /// Start recording events from the named backend
+    pub fn new(backend: &str) -> Self {
+        Self {
+            start: Instant::now(),
+            recording: InputRecording {
+                backend: backend.to_string(),
+                events: Vec::with_capacity(1024),
+            },
+        }
+    }
+
+    /// Record an event at the current time
+    pub fn record(&mut self, event: &TabletEvent) {
+        let time_us = self.start.elapsed().as_micros() as u64;
+        self.record_at(time_us, event);
+    }
+
+    /// Record an event at an explicit time (clamped to stay monotonic)
+    pub fn record_at(&mut self, time_us: u64, event: &TabletEvent) {
+        let time_us = time_us.max(self.recording.duration_us());
+        self.recording.events.push(RecordedEvent {
+            time_us,
+            event: event.clone(),
+        });
+    }
+
+    /// Number of events recorded so far
+    pub fn len(&self) -> usize {
+        self.recording.events.len()
+    }
+
+    /// Whether nothing has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.recording.events.is_empty()
+    }
+
+    /// Stop recording
+    pub fn finish(self) -> InputRecording {
+        self.recording
+    }
+}
+
+fn write_recording<W: Write>(recording: &InputRecording, w: &mut W) -> std::io::Result<()> {
+    w.write_all(MAGIC)?;
+    w.write_u16::<LittleEndian>(RECORDING_VERSION)?;
+    let name = recording.backend.as_bytes();
+    let len = name.len().min(u8::MAX as usize);
+    w.write_u8(len as u8)?;
+    w.write_all(&name[..len])?;
+
+    let mut previous = 0u64;
+    for recorded in &recording.events {
+        let delta = recorded
+            .time_us
+            .saturating_sub(previous)
+            .min(u32::MAX as u64);
+        previous += delta;
+        w.write_u32::<LittleEndian>(delta as u32)?;
+        write_event(&recorded.event, w)?;
+    }
+    Ok(())
+}
+
+fn write_event<W: Write>(event: &TabletEvent, w: &mut W) -> std::io::Result<()> {
+    match event {
+        TabletEvent::Input(p) => {
+            w.write_u8(TAG_INPUT)?;
+            for value in [p.x, p.y, p.pressure, p.tilt_x, p.tilt_y] {
+                w.write_f32::<LittleEndian>(value)?;
+            }
+            w.write_u64::<LittleEndian>(p.timestamp_us)?;
+            w.write_u64::<LittleEndian>(p.arrival_us)?;
+            w.write_u32::<LittleEndian>(p.buttons)?;
+
+            let mut flags = 0;
+            if p.rotation.is_some() {
+                flags |= HAS_ROTATION;
+            }
+            if p.tangential_pressure.is_some() {
+                flags |= HAS_TANGENTIAL;
+            }
+            w.write_u8(flags)?;
+            for value in [p.rotation, p.tangential_pressure].into_iter().flatten() {
+                w.write_f32::<LittleEndian>(value)?;
+            }
+        }
+        TabletEvent::ProximityEnter => w.write_u8(TAG_PROXIMITY_ENTER)?,
+        TabletEvent::ProximityLeave => w.write_u8(TAG_PROXIMITY_LEAVE)?,
+        TabletEvent::StatusChanged(status) => {
+            w.write_u8(TAG_STATUS)?;
+            w.write_u8(match status {
+                TabletStatus::Disconnected => 0,
+                TabletStatus::Connected => 1,
+                TabletStatus::Error => 2,
+            })?;
+        }
+        TabletEvent::ToolChanged(tool) => {
+            w.write_u8(TAG_TOOL)?;
+            w.write_u8(match tool.pointer_type {
+                PointerType::Pen => 0,
+                PointerType::Eraser => 1,
+                PointerType::Mouse => 2,
+                PointerType::Touch => 3,
+            })?;
+            w.write_u32::<LittleEndian>(tool.cursor_id)?;
+            match tool.serial {
+                Some(serial) => {
+                    w.write_u8(1)?;
+                    w.write_u64::<LittleEndian>(serial)?;
+                }
+                None => w.write_u8(0)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_name(cursor: &mut Cursor<&[u8]>) -> std::io::Result<String> {
+    let len = cursor.read_u8()? as usize;
+    let mut bytes = vec![0u8; len];
+    cursor.read_exact(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn read_event(cursor: &mut Cursor<&[u8]>, version: u16) -> Result<TabletEvent, String> {
+    let io = |e: std::io::Error| e.to_string();
+
+    match cursor.read_u8().map_err(io)? {
+        TAG_INPUT => {
+            let mut values = [0f32; 5];
+            for value in &mut values {
+                *value = cursor.read_f32::<LittleEndian>().map_err(io)?;
+            }
+            let [x, y, pressure, tilt_x, tilt_y] = values;
+            let (timestamp_us, arrival_us) = if version == 1 {
+                // Millisecond sample time, no arrival time
+                let timestamp_ms = cursor.read_u64::<LittleEndian>().map_err(io)?;
+                let timestamp_us = timestamp_ms
+                    .checked_mul(1000)
+                    .ok_or_else(|| format!("timestamp {} ms out of range", timestamp_ms))?;
+                (timestamp_us, timestamp_us)
+            } else {
+                (
+                    cursor.read_u64::<LittleEndian>().map_err(io)?,
+                    cursor.read_u64::<LittleEndian>().map_err(io)?,
+                )
+            };
+            let buttons = cursor.read_u32::<LittleEndian>().map_err(io)?;
+            let flags = cursor.read_u8().map_err(io)?;
+
+            let mut optional = |flag: u8| -> Result<Option<f32>, String> {
+                if flags & flag != 0 {
+                    cursor.read_f32::<LittleEndian>().map(Some).map_err(io)
+                } else {
+                    Ok(None)
+                }
+            };
+            let rotation = optional(HAS_ROTATION)?;
+            let tangential_pressure = optional(HAS_TANGENTIAL)?;
+
+            Ok(TabletEvent::Input(RawInputPoint {
+                x,
+                y,
+                pressure,
+                tilt_x,
+                tilt_y,
+                timestamp_ms: timestamp_us / 1000,
+                timestamp_us,
+                arrival_us,
+                rotation,
+                tangential_pressure,
+                buttons,
+            }))
+        }
+        TAG_PROXIMITY_ENTER => Ok(TabletEvent::ProximityEnter),
+        TAG_PROXIMITY_LEAVE => Ok(TabletEvent::ProximityLeave),
+        TAG_STATUS => Ok(TabletEvent::StatusChanged(
+            match cursor.read_u8().map_err(io)? {
+                0 => TabletStatus::Disconnected,
+                1 => TabletStatus::Connected,
+                2 => TabletStatus::Error,
+                other => return Err(format!("unknown status {}", other)),
+            },
+        )),
+        TAG_TOOL => {
+            let pointer_type = match cursor.read_u8().map_err(io)? {
+                0 => PointerType::Pen,
+                1 => PointerType::Eraser,
+                2 => PointerType::Mouse,
+                3 => PointerType::Touch,
+                other => return Err(format!("unknown pointer type {}", other)),
+            };
+            let cursor_id = cursor.read_u32::<LittleEndian>().map_err(io)?;
+            let serial = match cursor.read_u8().map_err(io)? {
+                0 => None,
+                _ => Some(cursor.read_u64::<LittleEndian>().map_err(io)?),
+            };
+            Ok(TabletEvent::ToolChanged(ToolInfo {
+                pointer_type,
+                cursor_id,
+                serial,
+            }))
+        }
+        tag => Err(format!("unknown event tag {}", tag)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A short stroke with every event kind, 5 ms apart
+    pub(crate) fn sample_recording() -> InputRecording {
+        let mut recorder = InputRecorder::new("Test");
+        let mut events = vec![
+            TabletEvent::StatusChanged(TabletStatus::Connected),
+            TabletEvent::ToolChanged(ToolInfo {
+                pointer_type: PointerType::Pen,
+                cursor_id: 1,
+                serial: Some(0x1234_5678_9abc),
+            }),
+            TabletEvent::ProximityEnter,
+        ];
+        for i in 0..10 {
+            let point = RawInputPoint::with_tilt(i as f32 * 3.0, 10.0, i as f32 / 10.0, 20.0, -5.0)
+                .with_timestamp_us(i * 5_000);
+            let point = if i % 2 == 0 {
+                point
+                    .with_rotation(Some(i as f32 * 10.0))
+                    .with_tangential_pressure(Some(0.5))
+                    .with_buttons(1)
+            } else {
+                point
+            };
+            events.push(TabletEvent::Input(point));
+        }
+        events.push(TabletEvent::ProximityLeave);
+
+        for (i, event) in events.iter().enumerate() {
+            recorder.record_at(i as u64 * 5_000, event);
+        }
+        recorder.finish()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let recording = sample_recording();
+        let bytes = recording.to_bytes();
+
+        let Ok(parsed) = InputRecording::from_bytes(&bytes) else {
+            panic!("recording should parse");
+        };
+        assert_eq!(parsed, recording);
+        assert_eq!(parsed.backend, "Test");
+        assert_eq!(parsed.duration_us(), 13 * 5_000);
+
+        // Compact: well under 64 bytes per event
+        assert!(bytes.len() < recording.events.len() * 64, "{}", bytes.len());
+    }
+
+    #[test]
+    fn test_recorder_is_monotonic() {
+        let mut recorder = InputRecorder::new("Test");
+        recorder.record_at(1_000, &TabletEvent::ProximityEnter);
+        recorder.record_at(500, &TabletEvent::ProximityLeave);
+        recorder.record(&TabletEvent::ProximityEnter);
+
+        let recording = recorder.finish();
+        let times: Vec<u64> = recording.events.iter().map(|e| e.time_us).collect();
+        assert_eq!(times[1], 1_000);
+        assert!(times[2] >= times[1]);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(InputRecording::from_bytes(b"nope").is_err());
+
+        let mut bytes = sample_recording().to_bytes();
+        bytes[4] = 99;
+        assert!(matches!(
+            InputRecording::from_bytes(&bytes),
+            Err(e) if e.contains("version 99")
+        ));
+
+        let mut truncated = sample_recording().to_bytes();
+        truncated.truncate(truncated.len() - 3);
+        assert!(InputRecording::from_bytes(&truncated).is_err());
+    }
+
+    /// Version 1 recording with one input event at `timestamp_ms`
+    fn version_1(timestamp_ms: u64) -> std::io::Result<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.write_u16::<LittleEndian>(1)?;
+        bytes.write_u8(3)?;
+        bytes.extend_from_slice(b"Old");
+        bytes.write_u32::<LittleEndian>(0)?;
+        bytes.write_u8(TAG_INPUT)?;
+        for value in [1.0, 2.0, 0.5, 0.0, 0.0] {
+            bytes.write_f32::<LittleEndian>(value)?;
+        }
+        bytes.write_u64::<LittleEndian>(timestamp_ms)?;
+        bytes.write_u32::<LittleEndian>(0)?;
+        bytes.write_u8(0)?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn test_reads_version_1() -> std::io::Result<()> {
+        let Ok(recording) = InputRecording::from_bytes(&version_1(42)?) else {
+            panic!("version 1 recording should parse");
+        };
+        let Some(TabletEvent::Input(point)) = recording.events.first().map(|e| &e.event) else {
+            panic!("expected an input event");
+        };
+        assert_eq!(point.timestamp_ms, 42);
+        assert_eq!(point.timestamp_us, 42_000);
+        assert_eq!(point.latency_us(), 0);
+
+        // Millisecond times that overflow in microseconds are rejected
+        assert!(matches!(
+            InputRecording::from_bytes(&version_1(u64::MAX / 999)?),
+            Err(e) if e.contains("out of range")
+        ));
+        Ok(())
+    }
+}
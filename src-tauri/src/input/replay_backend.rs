@@ -0,0 +1,194 @@
+//! Replay backend implementation
+//!
+//! Plays an `InputRecording` back through the `TabletBackend` interface,
+//! either in real time (events are released as their recorded time passes)
+//! or as fast as possible (every remaining event on each poll).
+//! Works on every platform, so recordings double as CI fixtures.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::backend::{TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus};
+use super::recording::InputRecording;
+use super::InputSpace;
+
+/// Replay pacing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplaySpeed {
+    /// Release events at their recorded times
+    #[default]
+    RealTime,
+    /// Release all remaining events on each poll
+    AsFastAsPossible,
+}
+
+/// Replay backend for recorded input
+pub struct ReplayBackend {
+    status: TabletStatus,
+    info: Option<TabletInfo>,
+    recording: InputRecording,
+    speed: ReplaySpeed,
+    /// Index of the next event to release
+    position: usize,
+    /// Playback start (real-time mode)
+    started: Option<Instant>,
+}
+
+impl ReplayBackend {
+    /// Create a replay backend for a recording
+    pub fn new(recording: InputRecording, speed: ReplaySpeed) -> Self {
+        Self {
+            status: TabletStatus::Disconnected,
+            info: None,
+            recording,
+            speed,
+            position: 0,
+            started: None,
+        }
+    }
+
+    /// Whether every event has been released
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.recording.events.len()
+    }
+
+    /// Restart playback from the first event
+    pub fn rewind(&mut self) {
+        self.position = 0;
+        if self.started.is_some() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Release events up to `elapsed_us` into the recording
+    pub fn poll_until(&mut self, elapsed_us: u64, events: &mut Vec<TabletEvent>) -> usize {
+        let pending = &self.recording.events[self.position..];
+        let count = pending
+            .iter()
+            .take_while(|e| e.time_us <= elapsed_us)
+            .count();
+        events.extend(pending[..count].iter().map(|e| e.event.clone()));
+        self.position += count;
+        count
+    }
+}
+
+impl TabletBackend for ReplayBackend {
+    fn init(&mut self, _config: &TabletConfig) -> Result<(), String> {
+        let has_pressure = self
+            .recording
+            .events
+            .iter()
+            .any(|e| matches!(&e.event, TabletEvent::Input(p) if p.pressure > 0.0));
+        let has_tilt = self.recording.events.iter().any(
+            |e| matches!(&e.event, TabletEvent::Input(p) if p.tilt_x != 0.0 || p.tilt_y != 0.0),
+        );
+
+        self.info = Some(TabletInfo {
+            name: format!("Replay ({})", self.recording.backend),
+            backend: "Replay".to_string(),
+            supports_pressure: has_pressure,
+            supports_tilt: has_tilt,
+            pressure_range: (0, 1),
+        });
+        self.status = TabletStatus::Connected;
+        tracing::info!(
+            "[Replay] Loaded {} events ({} ms)",
+            self.recording.events.len(),
+            self.recording.duration_us() / 1000
+        );
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), String> {
+        if self.status != TabletStatus::Connected {
+            return Err("Backend not initialized".to_string());
+        }
+        self.started = Some(Instant::now());
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.started = None;
+    }
+
+    fn status(&self) -> TabletStatus {
+        self.status
+    }
+
+    fn info(&self) -> Option<&TabletInfo> {
+        self.info.as_ref()
+    }
+
+    fn poll(&mut self, events: &mut Vec<TabletEvent>) -> usize {
+        let Some(started) = self.started else {
+            return 0;
+        };
+        let elapsed_us = match self.speed {
+            ReplaySpeed::RealTime => started.elapsed().as_micros() as u64,
+            ReplaySpeed::AsFastAsPossible => u64::MAX,
+        };
+        self.poll_until(elapsed_us, events)
+    }
+
+    fn is_available() -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "Replay"
+    }
+
+    fn input_space(&self) -> InputSpace {
+        // Same space as the backend that was recorded
+        match self.recording.backend.as_str() {
+            "WinTab" => InputSpace::Desktop,
+            "evdev" => InputSpace::Tablet,
+            _ => InputSpace::Canvas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::recording::tests::sample_recording;
+
+    #[test]
+    fn test_replay_as_fast_as_possible() -> Result<(), String> {
+        let recording = sample_recording();
+        let mut backend = ReplayBackend::new(recording.clone(), ReplaySpeed::AsFastAsPossible);
+
+        let mut events = Vec::new();
+        assert_eq!(backend.poll(&mut events), 0, "nothing before start");
+
+        backend.init(&TabletConfig::default())?;
+        backend.start()?;
+        assert_eq!(backend.poll(&mut events), recording.events.len());
+        assert!(backend.is_finished());
+        assert_eq!(backend.poll(&mut events), 0);
+
+        let expected: Vec<_> = recording.events.iter().map(|e| e.event.clone()).collect();
+        assert_eq!(events, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_follows_recorded_times() {
+        let mut backend = ReplayBackend::new(sample_recording(), ReplaySpeed::RealTime);
+        let mut events = Vec::new();
+
+        // Events are 5 ms apart, starting at 0
+        assert_eq!(backend.poll_until(0, &mut events), 1);
+        assert_eq!(backend.poll_until(12_000, &mut events), 2);
+        assert_eq!(backend.poll_until(12_000, &mut events), 0);
+        assert!(matches!(events[2], TabletEvent::ProximityEnter));
+
+        backend.rewind();
+        assert!(!backend.is_finished());
+        assert_eq!(backend.poll_until(u64::MAX, &mut events), 14);
+        assert!(backend.is_finished());
+    }
+}
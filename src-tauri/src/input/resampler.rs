@@ -0,0 +1,235 @@
+//! Input resampler - rebuilds a uniform-rate point stream
+//!
+//! Backends deliver points at hardware-dependent rates and in bursts
+//! (WinTab packet queues, coalesced PointerEvents). The resampler places
+//! points on a fixed time grid by evaluating a Catmull-Rom spline over the
+//! sample times, so downstream filters see the same density everywhere.
+//!
+//! Each segment needs the sample after it, so output lags one sample;
+//! `finish` flushes the last segment at the end of a stroke.
+
+use std::collections::VecDeque;
+
+use super::RawInputPoint;
+
+/// Longer pauses are not filled in (the grid restarts at the next sample)
+const MAX_GAP_US: u64 = 100_000;
+
+/// Resamples input points to a fixed rate
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    interval_us: u64,
+    /// Last (up to) four samples, oldest first
+    samples: VecDeque<RawInputPoint>,
+    /// Time of the next output point
+    next_tick_us: u64,
+}
+
+impl Resampler {
+    /// Create a resampler producing `rate_hz` points per second
+    pub fn new(rate_hz: u32) -> Self {
+        Self {
+            interval_us: (1_000_000 / rate_hz.max(1) as u64).max(1),
+            samples: VecDeque::with_capacity(4),
+            next_tick_us: 0,
+        }
+    }
+
+    /// Output rate in Hz
+    pub fn rate_hz(&self) -> u32 {
+        (1_000_000 / self.interval_us) as u32
+    }
+
+    /// Add a sample; returns the grid points it completed
+    pub fn process(&mut self, point: RawInputPoint) -> Vec<RawInputPoint> {
+        let Some(last) = self.samples.back_mut() else {
+            // The stroke starts at the first sample, without lag
+            self.next_tick_us = point.timestamp_us + self.interval_us;
+            self.samples.push_back(point);
+            return vec![point];
+        };
+        if point.timestamp_us <= last.timestamp_us {
+            // No time has passed (e.g. coalesced events without timestamps)
+            *last = point.with_timestamp_us(last.timestamp_us);
+            return Vec::new();
+        }
+
+        self.samples.push_back(point);
+        if self.samples.len() > 4 {
+            self.samples.pop_front();
+        }
+
+        let mut out = Vec::new();
+        if self.samples.len() >= 3 {
+            self.emit_segment(self.samples.len() - 3, &mut out);
+        }
+        out
+    }
+
+    /// End the stroke: returns the rest of the stroke, ending exactly at
+    /// the last sample, and resets
+    pub fn finish(&mut self) -> Vec<RawInputPoint> {
+        let mut out = Vec::new();
+        if self.samples.len() >= 2 {
+            self.emit_segment(self.samples.len() - 2, &mut out);
+            if let Some(&last) = self.samples.back() {
+                if out.last().map(|p| p.timestamp_us) != Some(last.timestamp_us) {
+                    out.push(last);
+                }
+            }
+        }
+        self.reset();
+        out
+    }
+
+    /// Reset for a new stroke
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.next_tick_us = 0;
+    }
+
+    /// Emit grid points after sample `i`, up to and including sample `i + 1`
+    fn emit_segment(&mut self, i: usize, out: &mut Vec<RawInputPoint>) {
+        let p0 = self.samples[i.saturating_sub(1)];
+        let p1 = self.samples[i];
+        let p2 = self.samples[i + 1];
+        let p3 = self.samples.get(i + 2).copied().unwrap_or(p2);
+
+        let span = p2.timestamp_us - p1.timestamp_us;
+        if span > MAX_GAP_US {
+            self.next_tick_us = p2.timestamp_us;
+            return;
+        }
+
+        // Times relative to p1 (microseconds)
+        let t0 = p0.timestamp_us as f32 - p1.timestamp_us as f32;
+        let t2 = span as f32;
+        let t3 = p3.timestamp_us.saturating_sub(p1.timestamp_us) as f32;
+
+        while self.next_tick_us <= p2.timestamp_us {
+            let tick = self.next_tick_us;
+            let s = tick.saturating_sub(p1.timestamp_us) as f32 / t2;
+            let spline = |value: fn(&RawInputPoint) -> f32| {
+                // Tangents from the neighbouring samples, scaled by their times
+                let m1 = (value(&p2) - value(&p0)) / (t2 - t0);
+                let m2 = (value(&p3) - value(&p1)) / t3;
+                hermite(s, t2, value(&p1), value(&p2), m1, m2)
+            };
+            let lerp = |a: f32, b: f32| a + (b - a) * s;
+
+            // Keep pressure between its endpoints so a lifted pen stays at 0
+            let (low, high) = if p1.pressure < p2.pressure {
+                (p1.pressure, p2.pressure)
+            } else {
+                (p2.pressure, p1.pressure)
+            };
+            let nearest = if s < 0.5 { p1 } else { p2 };
+
+            out.push(
+                RawInputPoint {
+                    x: spline(|p| p.x),
+                    y: spline(|p| p.y),
+                    pressure: spline(|p| p.pressure).clamp(low, high),
+                    tilt_x: lerp(p1.tilt_x, p2.tilt_x),
+                    tilt_y: lerp(p1.tilt_y, p2.tilt_y),
+                    // Computable once the newest sample arrived
+                    arrival_us: p3.arrival_us.max(p2.arrival_us),
+                    ..nearest
+                }
+                .with_timestamp_us(tick),
+            );
+            self.next_tick_us += self.interval_us;
+        }
+    }
+}
+
+/// Cubic Hermite between `p1` (s = 0) and `p2` (s = 1) over a span `h`
+fn hermite(s: f32, h: f32, p1: f32, p2: f32, m1: f32, m2: f32) -> f32 {
+    let s2 = s * s;
+    let s3 = s2 * s;
+    (2.0 * s3 - 3.0 * s2 + 1.0) * p1
+        + (s3 - 2.0 * s2 + s) * h * m1
+        + (-2.0 * s3 + 3.0 * s2) * p2
+        + (s3 - s2) * h * m2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(x: f32, pressure: f32, time_us: u64) -> RawInputPoint {
+        RawInputPoint::new(x, x * 0.5, pressure).with_timestamp_us(time_us)
+    }
+
+    /// Bursty line at 1 px/ms: irregular sample times
+    const BURSTY_TIMES_US: [u64; 10] = [
+        0, 3_000, 4_000, 4_500, 12_000, 13_000, 20_000, 21_000, 22_000, 30_000,
+    ];
+
+    #[test]
+    fn test_uniform_grid_from_bursts() {
+        let mut resampler = Resampler::new(250);
+        let mut out = Vec::new();
+        for &t in &BURSTY_TIMES_US {
+            out.extend(resampler.process(sample(t as f32 / 1000.0, 0.5, t)));
+        }
+        out.extend(resampler.finish());
+
+        // Every 4 ms from 0 to 28 ms, then the final sample
+        let times: Vec<u64> = out.iter().map(|p| p.timestamp_us).collect();
+        let mut expected: Vec<u64> = (0..=7).map(|i| i * 4_000).collect();
+        expected.push(30_000);
+        assert_eq!(times, expected);
+
+        // A straight line at constant speed is reproduced exactly
+        for p in &out {
+            let truth = p.timestamp_us as f32 / 1000.0;
+            assert!((p.x - truth).abs() < 1e-3, "{} vs {}", p.x, truth);
+            assert!((p.y - truth * 0.5).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_pressure_interpolation_stays_in_range() {
+        let mut resampler = Resampler::new(1000);
+        let pressures = [0.0, 0.0, 0.8, 0.2, 0.6, 0.0];
+        let mut out = Vec::new();
+        for (i, &pressure) in pressures.iter().enumerate() {
+            out.extend(resampler.process(sample(i as f32, pressure, i as u64 * 5_000)));
+        }
+        out.extend(resampler.finish());
+
+        assert_eq!(out.len(), 26);
+        for p in &out {
+            assert!((0.0..=0.8).contains(&p.pressure), "{}", p.pressure);
+        }
+        // Before the pen lands, pressure stays exactly zero
+        assert!(out[..5].iter().all(|p| p.pressure == 0.0));
+        // Halfway up the ramp
+        assert!(out[7].pressure > 0.2 && out[7].pressure < 0.6);
+        assert_eq!(out.last().map(|p| p.pressure), Some(0.0));
+    }
+
+    #[test]
+    fn test_duplicate_times_and_gaps() {
+        let mut resampler = Resampler::new(100);
+        assert_eq!(resampler.rate_hz(), 100);
+
+        assert_eq!(resampler.process(sample(0.0, 0.5, 0)).len(), 1);
+        // Same time: replaces the sample, nothing new on the grid
+        assert!(resampler.process(sample(1.0, 0.5, 0)).is_empty());
+        assert!(resampler.process(sample(2.0, 0.5, 10_000)).is_empty());
+        let before_pause = resampler.process(sample(3.0, 0.5, 500_000));
+        let times: Vec<u64> = before_pause.iter().map(|p| p.timestamp_us).collect();
+        assert_eq!(times, vec![10_000]);
+        assert_eq!(before_pause[0].x, 2.0);
+        // A long pause is not filled in
+        let resumed = resampler.process(sample(4.0, 0.5, 510_000));
+        assert!(resumed.is_empty(), "{:?}", resumed);
+
+        let tail = resampler.finish();
+        let times: Vec<u64> = tail.iter().map(|p| p.timestamp_us).collect();
+        assert_eq!(times, vec![500_000, 510_000]);
+        assert!(resampler.finish().is_empty());
+    }
+}
@@ -0,0 +1,572 @@
+//! Synthetic backend implementation
+//!
+//! Generates parametric pen strokes from a background thread, so the whole
+//! input pipeline can run without hardware (Linux CI, demos, benchmarks).
+//!
+//! Each stroke is a shape (line, circle, spiral) traced over a duration with
+//! a pressure profile and tilt sweep, optionally disturbed by position
+//! jitter and dropped packets. Generation is deterministic for a given seed.
+
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::backend::{
+    PointerType, TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus, ToolInfo,
+};
+use super::clock::monotonic_us;
+use super::recording::{InputRecording, RecordedEvent};
+use super::RawInputPoint;
+use crate::brush::JitterRng;
+
+/// Path traced by a synthetic stroke
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "shape")]
+pub enum SyntheticShape {
+    /// Straight line
+    Line { from: (f32, f32), to: (f32, f32) },
+    /// Full circle starting at angle 0
+    Circle { center: (f32, f32), radius: f32 },
+    /// Archimedean spiral
+    Spiral {
+        center: (f32, f32),
+        start_radius: f32,
+        end_radius: f32,
+        turns: f32,
+    },
+}
+
+impl SyntheticShape {
+    /// Position at progress `t` (0-1)
+    pub fn position(&self, t: f32) -> (f32, f32) {
+        match *self {
+            Self::Line { from, to } => (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t),
+            Self::Circle { center, radius } => {
+                let angle = t * TAU;
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            }
+            Self::Spiral {
+                center,
+                start_radius,
+                end_radius,
+                turns,
+            } => {
+                let angle = t * turns * TAU;
+                let radius = start_radius + (end_radius - start_radius) * t;
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            }
+        }
+    }
+}
+
+/// Pressure over the course of a stroke
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum PressureProfile {
+    /// Fixed pressure
+    Constant { value: f32 },
+    /// Linear ramp
+    Ramp { from: f32, to: f32 },
+    /// Rises from 0 to `peak` at mid-stroke and back (tapered ends)
+    Bell { peak: f32 },
+}
+
+impl PressureProfile {
+    /// Pressure at progress `t` (0-1)
+    pub fn pressure(&self, t: f32) -> f32 {
+        let p = match *self {
+            Self::Constant { value } => value,
+            Self::Ramp { from, to } => from + (to - from) * t,
+            Self::Bell { peak } => peak * (t * std::f32::consts::PI).sin(),
+        };
+        p.clamp(0.0, 1.0)
+    }
+}
+
+/// A single synthetic stroke
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntheticStroke {
+    /// Traced path
+    pub shape: SyntheticShape,
+    /// Time from pen-down to pen-up
+    pub duration_ms: u32,
+    /// Pressure profile
+    pub pressure: PressureProfile,
+    /// Tilt (x, y) at the start of the stroke, degrees
+    pub tilt_from: (f32, f32),
+    /// Tilt (x, y) at the end of the stroke, degrees
+    pub tilt_to: (f32, f32),
+    /// Position noise amplitude in pixels
+    pub jitter: f32,
+    /// Probability (0-1) that a packet is dropped
+    pub drop_rate: f32,
+}
+
+impl SyntheticStroke {
+    /// Plain stroke along a shape: constant half pressure, no tilt or noise
+    pub fn new(shape: SyntheticShape, duration_ms: u32) -> Self {
+        Self {
+            shape,
+            duration_ms,
+            pressure: PressureProfile::Constant { value: 0.5 },
+            tilt_from: (0.0, 0.0),
+            tilt_to: (0.0, 0.0),
+            jitter: 0.0,
+            drop_rate: 0.0,
+        }
+    }
+}
+
+/// Synthetic backend configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntheticConfig {
+    /// Strokes drawn in order
+    pub strokes: Vec<SyntheticStroke>,
+    /// Packet rate in Hz
+    pub rate_hz: u32,
+    /// Pen-up time between strokes
+    pub gap_ms: u32,
+    /// Seed for jitter and packet drops
+    pub seed: u64,
+    /// Start over after the last stroke
+    pub repeat: bool,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self::demo()
+    }
+}
+
+impl SyntheticConfig {
+    /// One stroke of each kind: line with a pressure ramp, circle with a
+    /// tilt sweep, noisy spiral with dropped packets
+    pub fn demo() -> Self {
+        Self {
+            strokes: vec![
+                SyntheticStroke {
+                    pressure: PressureProfile::Ramp { from: 0.1, to: 1.0 },
+                    ..SyntheticStroke::new(
+                        SyntheticShape::Line {
+                            from: (100.0, 100.0),
+                            to: (600.0, 150.0),
+                        },
+                        600,
+                    )
+                },
+                SyntheticStroke {
+                    pressure: PressureProfile::Bell { peak: 0.8 },
+                    tilt_from: (-50.0, 0.0),
+                    tilt_to: (50.0, 30.0),
+                    ..SyntheticStroke::new(
+                        SyntheticShape::Circle {
+                            center: (300.0, 400.0),
+                            radius: 120.0,
+                        },
+                        800,
+                    )
+                },
+                SyntheticStroke {
+                    jitter: 1.5,
+                    drop_rate: 0.05,
+                    ..SyntheticStroke::new(
+                        SyntheticShape::Spiral {
+                            center: (700.0, 400.0),
+                            start_radius: 10.0,
+                            end_radius: 150.0,
+                            turns: 3.0,
+                        },
+                        1200,
+                    )
+                },
+            ],
+            rate_hz: 200,
+            gap_ms: 200,
+            seed: 1,
+            repeat: false,
+        }
+    }
+
+    /// Generate the full event sequence (one pass over all strokes)
+    pub fn generate(&self) -> InputRecording {
+        let mut rng = JitterRng::new(self.seed);
+        let interval_us = (1_000_000 / self.rate_hz.max(1) as u64).max(1);
+        let mut events = Vec::new();
+        let mut time_us = 0u64;
+
+        let mut push = |time_us: u64, event: TabletEvent| {
+            events.push(RecordedEvent { time_us, event });
+        };
+
+        push(
+            0,
+            TabletEvent::ToolChanged(ToolInfo {
+                pointer_type: PointerType::Pen,
+                cursor_id: 1,
+                serial: None,
+            }),
+        );
+
+        for stroke in &self.strokes {
+            push(time_us, TabletEvent::ProximityEnter);
+
+            let duration_us = stroke.duration_ms as u64 * 1000;
+            let samples = (duration_us / interval_us).max(1);
+            for i in 0..=samples {
+                let t = i as f32 / samples as f32;
+                let sample_us = time_us + i * interval_us;
+                let is_end = i == 0 || i == samples;
+                if !is_end && rng.next_f32() < stroke.drop_rate {
+                    continue;
+                }
+
+                let (mut x, mut y) = stroke.shape.position(t);
+                if stroke.jitter > 0.0 {
+                    x += rng.next_signed() * stroke.jitter;
+                    y += rng.next_signed() * stroke.jitter;
+                }
+                let tilt_x = stroke.tilt_from.0 + (stroke.tilt_to.0 - stroke.tilt_from.0) * t;
+                let tilt_y = stroke.tilt_from.1 + (stroke.tilt_to.1 - stroke.tilt_from.1) * t;
+                // Last sample lifts the pen
+                let pressure = if i == samples {
+                    0.0
+                } else {
+                    stroke.pressure.pressure(t).max(f32::EPSILON)
+                };
+
+                let point = RawInputPoint {
+                    arrival_us: sample_us,
+                    ..RawInputPoint::with_tilt(x, y, pressure, tilt_x, tilt_y)
+                        .with_timestamp_us(sample_us)
+                };
+                push(sample_us, TabletEvent::Input(point));
+            }
+
+            time_us += samples * interval_us;
+            push(time_us, TabletEvent::ProximityLeave);
+            time_us += self.gap_ms as u64 * 1000;
+        }
+
+        InputRecording {
+            backend: "Synthetic".to_string(),
+            events,
+        }
+    }
+}
+
+/// Synthetic backend generating strokes in real time
+pub struct SyntheticBackend {
+    status: TabletStatus,
+    info: Option<TabletInfo>,
+    config: SyntheticConfig,
+    running: Arc<AtomicBool>,
+    events: Arc<Mutex<Vec<TabletEvent>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SyntheticBackend {
+    /// Create a synthetic backend
+    pub fn new(config: SyntheticConfig) -> Self {
+        Self {
+            status: TabletStatus::Disconnected,
+            info: None,
+            config,
+            running: Arc::new(AtomicBool::new(false)),
+            events: Arc::new(Mutex::new(Vec::with_capacity(64))),
+            thread: None,
+        }
+    }
+
+    /// Whether the generator thread is still producing events
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for SyntheticBackend {
+    fn default() -> Self {
+        Self::new(SyntheticConfig::default())
+    }
+}
+
+impl TabletBackend for SyntheticBackend {
+    fn init(&mut self, _config: &TabletConfig) -> Result<(), String> {
+        if self.config.strokes.is_empty() {
+            return Err("Synthetic backend has no strokes".to_string());
+        }
+
+        self.info = Some(TabletInfo {
+            name: "Synthetic Pen".to_string(),
+            backend: "Synthetic".to_string(),
+            supports_pressure: true,
+            supports_tilt: true,
+            pressure_range: (0, 1),
+        });
+        self.status = TabletStatus::Connected;
+        tracing::info!(
+            "[Synthetic] Initialized with {} strokes at {}Hz",
+            self.config.strokes.len(),
+            self.config.rate_hz
+        );
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), String> {
+        if self.status != TabletStatus::Connected {
+            return Err("Backend not initialized".to_string());
+        }
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let recording = self.config.generate();
+        let repeat = self.config.repeat;
+        // Start the next pass one packet after the previous one ended
+        let period_us = recording.duration_us() + 1_000_000 / self.config.rate_hz.max(1) as u64;
+        let running = self.running.clone();
+        let events = self.events.clone();
+
+        self.thread = Some(thread::spawn(move || {
+            let start = Instant::now();
+            let start_us = monotonic_us();
+            let mut pass = 0u64;
+            'outer: loop {
+                for recorded in &recording.events {
+                    let due_us = pass * period_us + recorded.time_us;
+                    let due = Duration::from_micros(due_us);
+                    while start.elapsed() < due {
+                        if !running.load(Ordering::SeqCst) {
+                            break 'outer;
+                        }
+                        thread::sleep((due - start.elapsed()).min(Duration::from_millis(1)));
+                    }
+                    // Sampled on schedule, delivered whenever the thread woke up
+                    let event = match &recorded.event {
+                        TabletEvent::Input(point) => TabletEvent::Input(RawInputPoint {
+                            arrival_us: monotonic_us(),
+                            ..point.with_timestamp_us(start_us + due_us)
+                        }),
+                        other => other.clone(),
+                    };
+                    if let Ok(mut events) = events.lock() {
+                        events.push(event);
+                    }
+                }
+                if !repeat {
+                    break;
+                }
+                pass += 1;
+            }
+            running.store(false, Ordering::SeqCst);
+            tracing::info!("[Synthetic] Generator thread stopped");
+        }));
+
+        tracing::info!("[Synthetic] Started");
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+        if let Ok(mut events) = self.events.lock() {
+            events.clear();
+        }
+    }
+
+    fn status(&self) -> TabletStatus {
+        self.status
+    }
+
+    fn info(&self) -> Option<&TabletInfo> {
+        self.info.as_ref()
+    }
+
+    fn poll(&mut self, events: &mut Vec<TabletEvent>) -> usize {
+        if let Ok(mut events_lock) = self.events.lock() {
+            let count = events_lock.len();
+            events.append(&mut events_lock);
+            count
+        } else {
+            0
+        }
+    }
+
+    fn is_available() -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "Synthetic"
+    }
+}
+
+impl Drop for SyntheticBackend {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(recording: &InputRecording) -> Vec<RawInputPoint> {
+        recording
+            .events
+            .iter()
+            .filter_map(|e| match e.event {
+                TabletEvent::Input(p) => Some(p),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn single(stroke: SyntheticStroke) -> SyntheticConfig {
+        SyntheticConfig {
+            strokes: vec![stroke],
+            rate_hz: 100,
+            ..SyntheticConfig::demo()
+        }
+    }
+
+    #[test]
+    fn test_line_with_pressure_ramp() {
+        let config = single(SyntheticStroke {
+            pressure: PressureProfile::Ramp { from: 0.0, to: 1.0 },
+            tilt_from: (-40.0, 0.0),
+            tilt_to: (40.0, 0.0),
+            ..SyntheticStroke::new(
+                SyntheticShape::Line {
+                    from: (0.0, 0.0),
+                    to: (100.0, 0.0),
+                },
+                100,
+            )
+        });
+        let recording = config.generate();
+        let points = inputs(&recording);
+
+        // 10 ms interval over 100 ms, plus the pen-up sample
+        assert_eq!(points.len(), 11);
+        assert_eq!((points[5].x, points[5].y), (50.0, 0.0));
+        assert!((points[5].pressure - 0.5).abs() < 1e-6);
+        assert_eq!(points[5].tilt_x, 0.0);
+        assert_eq!(points[10].pressure, 0.0);
+        assert_eq!(points[10].timestamp_ms, 100);
+
+        assert!(matches!(
+            recording.events.last(),
+            Some(RecordedEvent {
+                event: TabletEvent::ProximityLeave,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_rate_above_one_megahertz() {
+        let config = SyntheticConfig {
+            rate_hz: 5_000_000,
+            ..single(SyntheticStroke::new(
+                SyntheticShape::Line {
+                    from: (0.0, 0.0),
+                    to: (1.0, 0.0),
+                },
+                1,
+            ))
+        };
+        // Capped at one sample per microsecond
+        let points = inputs(&config.generate());
+        assert_eq!(points.len(), 1001);
+        assert_eq!(points[1000].timestamp_us, 1000);
+    }
+
+    #[test]
+    fn test_circle_and_spiral_geometry() {
+        let circle = SyntheticShape::Circle {
+            center: (10.0, 10.0),
+            radius: 5.0,
+        };
+        let (x, y) = circle.position(0.25);
+        assert!((x - 10.0).abs() < 1e-4 && (y - 15.0).abs() < 1e-4);
+
+        let spiral = SyntheticShape::Spiral {
+            center: (0.0, 0.0),
+            start_radius: 0.0,
+            end_radius: 10.0,
+            turns: 2.0,
+        };
+        let (x, y) = spiral.position(1.0);
+        assert!((x - 10.0).abs() < 1e-3 && y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_jitter_and_drops_are_deterministic() {
+        let config = single(SyntheticStroke {
+            jitter: 2.0,
+            drop_rate: 0.3,
+            ..SyntheticStroke::new(
+                SyntheticShape::Line {
+                    from: (0.0, 0.0),
+                    to: (500.0, 0.0),
+                },
+                1000,
+            )
+        });
+        let first = inputs(&config.generate());
+        assert_eq!(first, inputs(&config.generate()));
+
+        // Roughly 30% of the 101 samples dropped, ends always kept
+        assert!(first.len() > 55 && first.len() < 85, "{}", first.len());
+        assert!(first[0].x.abs() <= 2.0);
+        assert!(first.iter().all(|p| p.y.abs() <= 2.0));
+        assert!(first.iter().any(|p| p.y != 0.0));
+    }
+
+    #[test]
+    fn test_backend_streams_events() -> Result<(), String> {
+        let mut backend = SyntheticBackend::new(SyntheticConfig {
+            rate_hz: 1000,
+            ..single(SyntheticStroke::new(
+                SyntheticShape::Line {
+                    from: (0.0, 0.0),
+                    to: (10.0, 0.0),
+                },
+                20,
+            ))
+        });
+        assert!(backend.start().is_err(), "start before init");
+        backend.init(&TabletConfig::default())?;
+        backend.start()?;
+
+        let mut events = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while backend.is_running() && Instant::now() < deadline {
+            backend.poll(&mut events);
+            thread::sleep(Duration::from_millis(2));
+        }
+        backend.poll(&mut events);
+
+        // Tool, proximity enter, 21 samples, proximity leave
+        assert_eq!(events.len(), 24);
+        assert!(matches!(events[0], TabletEvent::ToolChanged(_)));
+        assert!(matches!(events[1], TabletEvent::ProximityEnter));
+        assert!(matches!(events[23], TabletEvent::ProximityLeave));
+        backend.stop();
+        Ok(())
+    }
+}
@@ -3,7 +3,14 @@
 //! Provides low-latency tablet input on Windows via the WinTab API.
 //! This is the preferred backend for Wacom tablets.
 
+#[cfg(any(target_os = "windows", test))]
+use super::backend::PointerType;
 use super::backend::{TabletBackend, TabletConfig, TabletEvent, TabletInfo, TabletStatus};
+#[cfg(target_os = "windows")]
+use super::backend::{ToolInfo, ToolTracker};
+#[cfg(target_os = "windows")]
+use super::clock::{monotonic_us, DeviceClock};
+use super::InputSpace;
 use super::RawInputPoint;
 use std::ffi::c_void;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -21,6 +28,9 @@ use wintab_lite::{cast_void, Packet, AXIS, CXO, DVC, HCTX, INT, LOGCONTEXT, LPVO
 // Function pointer types for WinTab API
 #[cfg(target_os = "windows")]
 type WTInfoFn = unsafe extern "C" fn(WTI, u32, LPVOID) -> u32;
+/// WTInfoA with a raw category, for indexed categories like WTI_CURSORS + n
+#[cfg(target_os = "windows")]
+type WTInfoRawFn = unsafe extern "C" fn(u32, u32, LPVOID) -> u32;
 #[cfg(target_os = "windows")]
 type WTOpenFn = unsafe extern "C" fn(HWND, *mut LOGCONTEXT, i32) -> *mut HCTX;
 #[cfg(target_os = "windows")]
@@ -32,6 +42,24 @@ type WTEnableFn = unsafe extern "C" fn(*mut HCTX, i32) -> i32;
 #[cfg(target_os = "windows")]
 type WTOverlapFn = unsafe extern "C" fn(*mut HCTX, i32) -> i32;
 
+/// WinTab cursor info category base (WTI_CURSORS) and physical ID index
+#[cfg(target_os = "windows")]
+const WTI_CURSORS: u32 = 200;
+#[cfg(target_os = "windows")]
+const CSR_PHYSID: u32 = 15;
+
+/// Pointer type of a WinTab cursor index
+///
+/// Wacom drivers expose three cursors per tool: puck, pen tip, eraser.
+#[cfg(any(target_os = "windows", test))]
+fn pointer_type_from_cursor(cursor: u32) -> PointerType {
+    match cursor % 3 {
+        0 => PointerType::Mouse,
+        1 => PointerType::Pen,
+        _ => PointerType::Eraser,
+    }
+}
+
 /// WinTab backend for Windows tablet input
 pub struct WinTabBackend {
     status: TabletStatus,
@@ -233,12 +261,14 @@ impl TabletBackend for WinTabBackend {
 
         let handle = thread::spawn(move || {
             // Load WinTab functions in this thread
-            let Ok((_lib, wt_info, wt_open, wt_close, wt_packets_get, wt_enable, wt_overlap)) =
+            let Ok((lib, wt_info, wt_open, wt_close, wt_packets_get, wt_enable, wt_overlap)) =
                 WinTabBackend::load_wintab_functions()
             else {
                 tracing::error!("[WinTab] Failed to load functions in poll thread");
                 return;
             };
+            let wt_info_raw: Option<WTInfoRawFn> =
+                unsafe { lib.get::<WTInfoRawFn>(b"WTInfoA").ok().map(|f| *f) };
 
             // Get default context (DEFCONTEXT for digitizer-relative coordinates)
             let mut log_context = LOGCONTEXT::default();
@@ -263,6 +293,33 @@ impl TabletBackend for WinTabBackend {
                 tablet_y.axMax
             );
 
+            // Optional axes: tangential pressure (airbrush wheel) and
+            // orientation twist (Art Pen barrel rotation)
+            let mut tangent_axis = AXIS::default();
+            let mut orientation_axes: [AXIS; 3] = core::array::from_fn(|_| AXIS::default());
+            let (tangent_result, orientation_result) = unsafe {
+                (
+                    wt_info(
+                        WTI::DEVICES,
+                        DVC::TPRESSURE as u32,
+                        cast_void!(tangent_axis),
+                    ),
+                    wt_info(
+                        WTI::DEVICES,
+                        DVC::ORIENTATION as u32,
+                        cast_void!(orientation_axes),
+                    ),
+                )
+            };
+            let tangent_range = (tangent_result > 0 && tangent_axis.axMax > tangent_axis.axMin)
+                .then_some((tangent_axis.axMin as f32, tangent_axis.axMax as f32));
+            let has_twist = orientation_result > 0 && orientation_axes[2].axMax > 0;
+            tracing::info!(
+                "[WinTab] Tangential pressure: {:?}, twist: {}",
+                tangent_range,
+                has_twist
+            );
+
             // Configure context for our needs
             // CXO::SYSTEM is required for WinTab to receive packet data
             // When Windows Ink is disabled in Wacom driver, this won't interfere
@@ -314,6 +371,9 @@ impl TabletBackend for WinTabBackend {
 
             let mut was_in_proximity = false;
             let mut loop_count: u64 = 0;
+            let mut tool_tracker = ToolTracker::default();
+            // PK_TIME is milliseconds since boot
+            let mut device_clock = DeviceClock::new();
 
             while running.load(Ordering::SeqCst) {
                 loop_count += 1;
@@ -341,6 +401,7 @@ impl TabletBackend for WinTabBackend {
                 };
 
                 if count > 0 {
+                    let arrival_us = monotonic_us();
                     let mut new_events = Vec::with_capacity(count as usize);
 
                     for packet in packets.iter().take(count as usize) {
@@ -355,11 +416,35 @@ impl TabletBackend for WinTabBackend {
                         }
                         was_in_proximity = in_proximity;
 
+                        // Tool identity (pen tip, eraser, puck) and its physical serial
+                        let cursor_id = packet.pkCursor as u32;
+                        if tool_tracker.current().map(|t| t.cursor_id) != Some(cursor_id) {
+                            let serial = wt_info_raw.and_then(|wt_info_raw| {
+                                let mut physid: u32 = 0;
+                                let size = unsafe {
+                                    wt_info_raw(
+                                        WTI_CURSORS + cursor_id,
+                                        CSR_PHYSID,
+                                        &mut physid as *mut u32 as LPVOID,
+                                    )
+                                };
+                                (size > 0).then_some(physid as u64)
+                            });
+                            let tool = ToolInfo {
+                                pointer_type: pointer_type_from_cursor(cursor_id),
+                                cursor_id,
+                                serial,
+                            };
+                            if let Some(event) = tool_tracker.update(tool) {
+                                new_events.push(event);
+                            }
+                        }
+
                         // Convert to normalized pressure (raw value, curve applied later after smoothing)
                         let pressure = packet.pkNormalPressure as f32 / pressure_max;
 
-                        // Convert tablet coordinates to screen coordinates
-                        // pkXYZ contains output coordinates (already scaled by context)
+                        // pkXYZ contains desktop pixels (the context maps the tablet
+                        // onto the screen); mapped to canvas later
                         let x = packet.pkXYZ.x as f32;
                         let y = packet.pkXYZ.y as f32;
 
@@ -369,6 +454,14 @@ impl TabletBackend for WinTabBackend {
                         let tilt_y =
                             (packet.pkOrientation.orAltitude as f32 / 10.0).clamp(-90.0, 90.0);
 
+                        // Twist is in tenths of a degree
+                        let rotation = has_twist.then(|| {
+                            (packet.pkOrientation.orTwist as f32 / 10.0).rem_euclid(360.0)
+                        });
+                        let tangential_pressure = tangent_range.map(|(min, max)| {
+                            ((packet.pkTangentPressure as f32 - min) / (max - min)).clamp(0.0, 1.0)
+                        });
+
                         // Always emit input events so frontend can track current tablet position
                         // Frontend will use pressure value to determine if pen is touching
                         let point = RawInputPoint {
@@ -378,10 +471,17 @@ impl TabletBackend for WinTabBackend {
                             tilt_x,
                             tilt_y,
                             timestamp_ms: packet.pkTime as u64,
+                            timestamp_us: packet.pkTime as u64 * 1000,
+                            arrival_us,
+                            rotation,
+                            tangential_pressure,
+                            // Absolute mode: bit n set while button n is down
+                            buttons: packet.pkButtons as u32,
                         };
 
                         new_events.push(TabletEvent::Input(point));
                     }
+                    device_clock.stamp_events(&mut new_events, arrival_us);
 
                     // Use try_lock to avoid blocking if the lock is held
                     // If lock fails, events will be picked up in the next iteration
@@ -468,6 +568,10 @@ impl TabletBackend for WinTabBackend {
     fn name(&self) -> &'static str {
         "WinTab"
     }
+
+    fn input_space(&self) -> InputSpace {
+        InputSpace::Desktop
+    }
 }
 
 impl Drop for WinTabBackend {
@@ -486,4 +590,14 @@ mod tests {
         assert_eq!(backend.status(), TabletStatus::Disconnected);
         assert!(backend.info().is_none());
     }
+
+    #[test]
+    fn test_pointer_type_from_cursor() {
+        assert_eq!(pointer_type_from_cursor(0), PointerType::Mouse);
+        assert_eq!(pointer_type_from_cursor(1), PointerType::Pen);
+        assert_eq!(pointer_type_from_cursor(2), PointerType::Eraser);
+        // Second tool set (e.g. dual-track)
+        assert_eq!(pointer_type_from_cursor(4), PointerType::Pen);
+        assert_eq!(pointer_type_from_cursor(5), PointerType::Eraser);
+    }
 }
@@ -41,8 +41,16 @@ pub fn run() {
             commands::run_wintab_spike,
             commands::check_wintab_available,
             commands::init_tablet,
+            commands::set_pressure_curve,
+            commands::set_display_layout,
+            commands::set_viewport,
+            commands::set_tablet_mapping,
             commands::start_tablet,
             commands::stop_tablet,
+            commands::start_input_recording,
+            commands::stop_input_recording,
+            commands::start_pressure_calibration,
+            commands::finish_pressure_calibration,
             commands::get_tablet_status,
             commands::push_pointer_event,
             commands::stamp_soft_dab,
@@ -5,6 +5,7 @@ import { useViewportStore } from '@/stores/viewport';
 import { useHistoryStore } from '@/stores/history';
 import { useTabletStore, drainPointBuffer, clearPointBuffer } from '@/stores/tablet';
 import { StrokeBuffer, Point } from '@/utils/interpolation';
+import { Stabilizer } from '@/utils/stabilizer';
 import { LayerRenderer } from '@/utils/layerRenderer';
 import './Canvas.css';
 
@@ -78,6 +79,8 @@ export function Canvas() {
   const isZoomingRef = useRef(false);
   const zoomStartRef = useRef<{ x: number; y: number; startScale: number } | null>(null);
   const strokeBufferRef = useRef<StrokeBuffer>(new StrokeBuffer(2));
+  // Stroke stabilizer for the current tool (positions come from PointerEvents)
+  const stabilizerRef = useRef(new Stabilizer<{ x: number; y: number; pressure: number }>());
   const panStartRef = useRef<{ x: number; y: number } | null>(null);
   const layerRendererRef = useRef<LayerRenderer | null>(null);
   const historyInitializedRef = useRef(false);
@@ -198,6 +201,7 @@ export function Canvas() {
     pendingPointsRef,
     inputQueueRef,
     pointIndexRef,
+    stabilizerRef,
     latencyProfiler: latencyProfilerRef.current,
     onPointBuffered: () => window.__strokeDiagnostics?.onPointBuffered(),
   });
@@ -853,8 +857,15 @@ export function Canvas() {
     // 清理 WinTab 缓冲区
     clearPointBuffer();
 
+    // Stabilized strokes lag behind the pen: catch up to where it lifted
+    const catchUp = stabilizerRef.current.finish();
+
     // For brush tool, composite stroke buffer to layer with opacity ceiling
     if (currentTool === 'brush') {
+      for (const p of catchUp) {
+        inputQueueRef.current.push({ ...p, pointIndex: pointIndexRef.current++ });
+      }
+
       // Process any remaining points in queue before finalizing
       const remainingQueue = inputQueueRef.current;
       if (remainingQueue.length > 0) {
@@ -871,6 +882,12 @@ export function Canvas() {
       compositeAndRender();
     } else {
       // For eraser, use the legacy stroke buffer
+      for (const p of catchUp) {
+        const interpolatedPoints = strokeBufferRef.current.addPoint(p);
+        if (interpolatedPoints.length > 0) {
+          drawPoints(interpolatedPoints);
+        }
+      }
       const remainingPoints = strokeBufferRef.current.finish();
       if (remainingPoints.length > 0) {
         drawPoints(remainingPoints);
@@ -1010,6 +1027,8 @@ export function Canvas() {
       canvas.setPointerCapture(e.pointerId);
       isDrawingRef.current = true;
       strokeBufferRef.current.reset();
+      stabilizerRef.current.reset(useToolStore.getState().getCurrentStabilizer());
+      stabilizerRef.current.process({ x: canvasX, y: canvasY, pressure }, e.timeStamp);
 
       // Capture layer state before stroke starts (for undo)
       captureBeforeImage();
@@ -1120,10 +1139,6 @@ export function Canvas() {
 
       // 遍历所有合并事件，恢复完整输入轨迹
       for (const evt of coalescedEvents) {
-        // 始终使用 PointerEvent 的坐标（它们是准确的屏幕坐标）
-        const canvasX = (evt.clientX - rect.left) / scale;
-        const canvasY = (evt.clientY - rect.top) / scale;
-
         // Resolve input pressure/tilt (handling WinTab buffering if active)
         const { pressure, tiltX, tiltY } = getEffectiveInputData(
           evt,
@@ -1132,6 +1147,16 @@ export function Canvas() {
           tabletState.currentPoint
         );
 
+        // 始终使用 PointerEvent 的坐标（它们是准确的屏幕坐标）
+        const { x: canvasX, y: canvasY } = stabilizerRef.current.process(
+          {
+            x: (evt.clientX - rect.left) / scale,
+            y: (evt.clientY - rect.top) / scale,
+            pressure,
+          },
+          evt.timeStamp
+        );
+
         const idx = pointIndexRef.current++;
         // Note: evt is PointerEvent here
         latencyProfilerRef.current.markInputReceived(idx, evt as PointerEvent);
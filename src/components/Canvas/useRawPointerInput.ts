@@ -1,5 +1,6 @@
 import { useEffect, useRef, MutableRefObject } from 'react';
 import { useTabletStore, drainPointBuffer } from '@/stores/tablet';
+import { Stabilizer } from '@/utils/stabilizer';
 import { getEffectiveInputData } from './inputUtils';
 
 /**
@@ -27,6 +28,7 @@ interface RawPointerInputConfig {
   pendingPointsRef: MutableRefObject<QueuedPoint[]>;
   inputQueueRef: MutableRefObject<QueuedPoint[]>;
   pointIndexRef: MutableRefObject<number>;
+  stabilizerRef: MutableRefObject<Stabilizer<{ x: number; y: number; pressure: number }>>;
   latencyProfiler: { markInputReceived: (idx: number, evt: PointerEvent) => void };
   onPointBuffered?: () => void;
 }
@@ -45,6 +47,7 @@ export function useRawPointerInput({
   pendingPointsRef,
   inputQueueRef,
   pointIndexRef,
+  stabilizerRef,
   latencyProfiler,
   onPointBuffered,
 }: RawPointerInputConfig) {
@@ -86,9 +89,6 @@ export function useRawPointerInput({
       const bufferedPoints = isWinTabActive ? drainPointBuffer() : [];
 
       for (const evt of coalescedEvents) {
-        const canvasX = (evt.clientX - rect.left) / scale;
-        const canvasY = (evt.clientY - rect.top) / scale;
-
         // Resolve pressure/tilt from WinTab or PointerEvent
         const { pressure } = getEffectiveInputData(
           evt,
@@ -97,10 +97,19 @@ export function useRawPointerInput({
           tabletState.currentPoint
         );
 
+        const stabilized = stabilizerRef.current.process(
+          {
+            x: (evt.clientX - rect.left) / scale,
+            y: (evt.clientY - rect.top) / scale,
+            pressure,
+          },
+          evt.timeStamp
+        );
+
         const idx = pointIndexRef.current++;
         latencyProfiler.markInputReceived(idx, evt);
 
-        const point = { x: canvasX, y: canvasY, pressure, pointIndex: idx };
+        const point = { ...stabilized, pointIndex: idx };
 
         if (state === 'starting') {
           pendingPointsRef.current.push(point);
@@ -129,6 +138,7 @@ export function useRawPointerInput({
     pendingPointsRef,
     inputQueueRef,
     pointIndexRef,
+    stabilizerRef,
     latencyProfiler,
     onPointBuffered,
   ]);
@@ -1,10 +1,11 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { useToolStore, type ToolType } from './tool';
 
 // Types matching Rust backend
 export type TabletStatus = 'Disconnected' | 'Connected' | 'Error';
-export type BackendType = 'wintab' | 'pointerevent' | 'auto';
+export type BackendType = 'wintab' | 'pointerevent' | 'evdev' | 'synthetic' | 'auto';
 
 export interface TabletInfo {
   name: string;
@@ -27,13 +28,54 @@ export interface RawInputPoint {
   tilt_x: number;
   tilt_y: number;
   timestamp_ms: number;
+  /** Sample time in microseconds on the backend's monotonic input clock */
+  timestamp_us?: number;
+  /** When the backend received the sample (same clock); latency = arrival - sample */
+  arrival_us?: number;
+  /** Barrel rotation in degrees (0-360), only from pens that report twist */
+  rotation?: number;
+  /** Tangential pressure (-1 to 1), e.g. an airbrush wheel */
+  tangential_pressure?: number;
+  /** Pressed buttons bitmask (1 = tip, 2 = lower barrel, 4 = upper barrel) */
+  buttons?: number;
+}
+
+export type PointerType = 'pen' | 'eraser' | 'mouse' | 'touch';
+
+export interface ToolInfo {
+  pointerType: PointerType;
+  cursorId: number;
+  serial: number | null;
 }
 
 export type TabletEvent =
   | { Input: RawInputPoint }
   | 'ProximityEnter'
   | 'ProximityLeave'
-  | { StatusChanged: TabletStatus };
+  | { StatusChanged: TabletStatus }
+  | { ToolChanged: ToolInfo };
+
+// Tool selected before the pen was flipped to the eraser
+let toolBeforeEraser: ToolType | null = null;
+
+/**
+ * Switch to the eraser when the pen is flipped, and back when it returns
+ */
+function handleToolChanged(tool: ToolInfo): void {
+  const toolStore = useToolStore.getState();
+  if (tool.pointerType === 'eraser') {
+    if (toolStore.currentTool !== 'eraser') {
+      toolBeforeEraser = toolStore.currentTool;
+      toolStore.setTool('eraser');
+    }
+  } else if (toolBeforeEraser !== null) {
+    // Only restore if the user didn't pick another tool meanwhile
+    if (toolStore.currentTool === 'eraser') {
+      toolStore.setTool(toolBeforeEraser);
+    }
+    toolBeforeEraser = null;
+  }
+}
 
 // Ring buffer size - stores recent WinTab points for matching
 const POINT_BUFFER_SIZE = 128;
@@ -185,6 +227,8 @@ export const useTabletStore = create<TabletState>((set, get) => ({
           set({ currentPoint: null });
         } else if (typeof payload === 'object' && 'StatusChanged' in payload) {
           set({ status: payload.StatusChanged });
+        } else if (typeof payload === 'object' && 'ToolChanged' in payload) {
+          handleToolChanged(payload.ToolChanged);
         }
       });
 
@@ -265,7 +309,13 @@ export async function pushPointerEvent(
   y: number,
   pressure: number,
   tiltX: number,
-  tiltY: number
+  tiltY: number,
+  twist?: number,
+  tangentialPressure?: number,
+  pointerType?: PointerType,
+  pointerId?: number,
+  buttons?: number,
+  timeStamp?: number
 ): Promise<void> {
   try {
     await invoke('push_pointer_event', {
@@ -274,8 +324,89 @@ export async function pushPointerEvent(
       pressure,
       tiltX,
       tiltY,
+      twist,
+      tangentialPressure,
+      pointerType,
+      pointerId,
+      buttons,
+      timestamp: timeStamp,
     });
   } catch (error) {
     console.error('[Tablet] Push pointer event failed:', error);
   }
 }
+
+// Record raw tablet events for bug reports and replay fixtures
+export async function startInputRecording(): Promise<void> {
+  await invoke('start_input_recording');
+}
+
+// Stop recording and save it; resolves to the number of recorded events
+export async function stopInputRecording(path: string): Promise<number> {
+  return invoke<number>('stop_input_recording', { path });
+}
+
+// Pressure calibration fitted from a guided scribble
+export interface CalibrationProfile {
+  device: string;
+  serial: number | null;
+  deadZone: number;
+  maxPressure: number;
+  typicalPressure: number;
+}
+
+// Start recording raw pressure for calibration
+export async function startPressureCalibration(): Promise<void> {
+  await invoke('start_pressure_calibration');
+}
+
+// Fit, save and apply the calibration for the current tablet and pen
+export async function finishPressureCalibration(): Promise<CalibrationProfile> {
+  return invoke<CalibrationProfile>('finish_pressure_calibration');
+}
+
+// Coordinate mapping (tablet area -> screen region -> window -> canvas)
+export interface MappingRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface MonitorInfo {
+  name: string;
+  bounds: MappingRect;
+}
+
+export type ScreenRegion =
+  | { kind: 'allMonitors' }
+  | { kind: 'monitor'; index: number }
+  | { kind: 'custom'; rect: MappingRect };
+
+export interface TabletMapping {
+  tabletArea: MappingRect;
+  tabletSize: [number, number];
+  screen: ScreenRegion;
+  lockAspect: boolean;
+}
+
+export async function setDisplayLayout(
+  monitors: MonitorInfo[],
+  window: { originX: number; originY: number; scaleFactor: number }
+): Promise<void> {
+  await invoke('set_display_layout', { monitors, window });
+}
+
+export async function setInputViewport(viewport: {
+  offsetX: number;
+  offsetY: number;
+  scale: number;
+  rotation: number;
+}): Promise<void> {
+  await invoke('set_viewport', { viewport });
+}
+
+// Per-device mapping; omit `device` to change the default mapping
+export async function setTabletMapping(mapping: TabletMapping, device?: string): Promise<void> {
+  await invoke('set_tablet_mapping', { device, mapping });
+}
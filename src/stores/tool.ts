@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import { STABILIZER_OFF, type StabilizerMode } from '@/utils/stabilizer';
 
 export type ToolType = 'brush' | 'eraser' | 'eyedropper' | 'move' | 'select' | 'lasso' | 'zoom';
 
@@ -97,6 +98,10 @@ interface ToolState {
   // Eraser settings (independent from brush)
   eraserSize: number;
 
+  // Stroke stabilizer, per tool
+  brushStabilizer: StabilizerMode;
+  eraserStabilizer: StabilizerMode;
+
   // Pressure sensitivity settings
   pressureSizeEnabled: boolean;
   pressureFlowEnabled: boolean; // Pressure affects flow (per-dab)
@@ -130,6 +135,10 @@ interface ToolState {
   setBrushTexture: (texture: BrushTexture | null) => void;
   clearBrushTexture: () => void;
   setEraserSize: (size: number) => void;
+  setBrushStabilizer: (mode: StabilizerMode) => void;
+  setEraserStabilizer: (mode: StabilizerMode) => void;
+  // Get current tool's stabilizer (brush or eraser)
+  getCurrentStabilizer: () => StabilizerMode;
   // Get current tool's size (brush or eraser)
   getCurrentSize: () => number;
   // Set current tool's size (brush or eraser)
@@ -163,6 +172,8 @@ export const useToolStore = create<ToolState>()(
       backgroundColor: '#ffffff',
       brushTexture: null, // No texture by default (procedural brush)
       eraserSize: 20,
+      brushStabilizer: STABILIZER_OFF,
+      eraserStabilizer: STABILIZER_OFF,
       pressureSizeEnabled: false,
       pressureFlowEnabled: false,
       pressureOpacityEnabled: true, // Only opacity affected by pressure by default
@@ -202,6 +213,15 @@ export const useToolStore = create<ToolState>()(
 
       setEraserSize: (size) => set({ eraserSize: clampSize(size) }),
 
+      setBrushStabilizer: (mode) => set({ brushStabilizer: mode }),
+
+      setEraserStabilizer: (mode) => set({ eraserStabilizer: mode }),
+
+      getCurrentStabilizer: () => {
+        const state = get();
+        return state.currentTool === 'eraser' ? state.eraserStabilizer : state.brushStabilizer;
+      },
+
       getCurrentSize: () => {
         const state = get();
         return state.currentTool === 'eraser' ? state.eraserSize : state.brushSize;
@@ -262,6 +282,8 @@ export const useToolStore = create<ToolState>()(
         brushColor: state.brushColor,
         backgroundColor: state.backgroundColor,
         eraserSize: state.eraserSize,
+        brushStabilizer: state.brushStabilizer,
+        eraserStabilizer: state.eraserStabilizer,
         pressureSizeEnabled: state.pressureSizeEnabled,
         pressureFlowEnabled: state.pressureFlowEnabled,
         pressureOpacityEnabled: state.pressureOpacityEnabled,
@@ -0,0 +1,78 @@
+import { describe, it, expect } from 'vitest';
+import { Stabilizer, type StabilizerMode } from '../stabilizer';
+
+// Zig-zag line along x, 8 ms apart
+function run(stabilizer: Stabilizer) {
+  const out = [];
+  for (let i = 0; i < 40; i++) {
+    out.push(stabilizer.process({ x: i * 2, y: i % 2 === 0 ? 2 : -2 }, i * 8));
+  }
+  return out;
+}
+
+function yRange(points: { y: number }[]): number {
+  const ys = points.slice(points.length / 4).map((p) => p.y);
+  return Math.max(...ys) - Math.min(...ys);
+}
+
+describe('Stabilizer', () => {
+  it('should pass positions through when off', () => {
+    const stabilizer = new Stabilizer();
+    const out = run(stabilizer);
+    expect(out).toHaveLength(40);
+    expect(yRange(out)).toBe(4);
+    expect(stabilizer.finish()).toEqual([]);
+  });
+
+  it('should reduce jitter with a weighted average', () => {
+    const stabilizer = new Stabilizer();
+    stabilizer.reset({ mode: 'weightedAverage', samples: 8 });
+    const out = run(stabilizer);
+    expect(out).toHaveLength(40);
+    expect(yRange(out)).toBeLessThan(1);
+  });
+
+  it('should hold the position while the pulled string is slack', () => {
+    const stabilizer = new Stabilizer<{ x: number; y: number; pressure: number }>();
+    stabilizer.reset({ mode: 'pulledString', radius: 10 });
+    stabilizer.process({ x: 0, y: 0, pressure: 0.5 }, 0);
+
+    // Slack string: position is held, the pressure sample still comes through
+    expect(stabilizer.process({ x: 6, y: 0, pressure: 0.9 }, 8)).toEqual({
+      x: 0,
+      y: 0,
+      pressure: 0.9,
+    });
+    expect(stabilizer.process({ x: 15, y: 0, pressure: 0.9 }, 16).x).toBeCloseTo(5);
+  });
+
+  it('should follow fast strokes with dynamic smoothing', () => {
+    const stabilizer = new Stabilizer();
+    stabilizer.reset({ mode: 'dynamic', strength: 1 });
+    stabilizer.process({ x: 0, y: 0 }, 0);
+    expect(stabilizer.process({ x: 1, y: 0 }, 100).x).toBeLessThan(0.2);
+    expect(stabilizer.process({ x: 100, y: 0 }, 110).x).toBeCloseTo(100);
+  });
+
+  it('should catch up to the pen on finish', () => {
+    const modes: StabilizerMode[] = [
+      { mode: 'weightedAverage', samples: 8 },
+      { mode: 'pulledString', radius: 10 },
+      { mode: 'dynamic', strength: 0.8 },
+    ];
+    for (const mode of modes) {
+      const stabilizer = new Stabilizer();
+      stabilizer.reset(mode);
+      const out = run(stabilizer);
+      const catchUp = stabilizer.finish();
+      expect(catchUp[catchUp.length - 1]).toEqual({ x: 78, y: -2 });
+
+      let previous = out[out.length - 1]!;
+      for (const p of catchUp) {
+        expect(Math.hypot(p.x - previous.x, p.y - previous.y)).toBeLessThanOrEqual(2 + 1e-3);
+        previous = p;
+      }
+      expect(stabilizer.finish()).toEqual([]);
+    }
+  });
+});
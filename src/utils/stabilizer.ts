@@ -0,0 +1,150 @@
+/**
+ * Stroke stabilizer - smooths pen position before it reaches the brush
+ *
+ * Runs in the canvas, which takes positions from PointerEvents. Only
+ * positions change; every input sample still produces a point so no
+ * pressure sample is lost.
+ */
+
+/** Stabilizer mode, persisted per tool */
+export type StabilizerMode =
+  | { mode: 'off' }
+  | { mode: 'weightedAverage'; samples: number }
+  | { mode: 'pulledString'; radius: number }
+  | { mode: 'dynamic'; strength: number };
+
+export const STABILIZER_OFF: StabilizerMode = { mode: 'off' };
+
+interface Position {
+  x: number;
+  y: number;
+}
+
+// Spacing of catch-up points emitted on pen-up (pixels)
+const CATCH_UP_STEP_PX = 2;
+
+// Pen speed (pixels per millisecond) at which dynamic smoothing is off
+const DYNAMIC_REFERENCE_PX_PER_MS = 3;
+
+export class Stabilizer<T extends Position = Position> {
+  private mode: StabilizerMode = STABILIZER_OFF;
+  private history: Position[] = [];
+  private current: Position | null = null;
+  private lastRaw: T | null = null;
+  private lastTimeMs = 0;
+
+  /**
+   * Change mode and reset for a new stroke
+   */
+  reset(mode: StabilizerMode = this.mode): void {
+    this.mode = mode;
+    this.history = [];
+    this.current = null;
+    this.lastRaw = null;
+  }
+
+  /**
+   * Stabilize a point sampled at `timeMs`; only x and y change
+   * While the pulled string is slack the previous position is kept.
+   */
+  process(point: T, timeMs: number): T {
+    const { x, y } = point;
+    const previousRaw = this.lastRaw;
+    const previousTimeMs = this.lastTimeMs;
+    this.lastRaw = point;
+    this.lastTimeMs = timeMs;
+
+    const current = this.current;
+    if (!current) {
+      this.history.push({ x, y });
+      this.current = { x, y };
+      return point;
+    }
+
+    const mode = this.mode;
+    let out: Position;
+    switch (mode.mode) {
+      case 'weightedAverage': {
+        this.history.push({ x, y });
+        const samples = Math.max(1, mode.samples);
+        if (this.history.length > samples) {
+          this.history.splice(0, this.history.length - samples);
+        }
+        out = weightedAverage(this.history);
+        break;
+      }
+      case 'pulledString': {
+        const dx = x - current.x;
+        const dy = y - current.y;
+        const dist = Math.hypot(dx, dy);
+        const radius = Math.max(0, mode.radius);
+        if (dist <= radius) {
+          out = { ...current };
+        } else {
+          const pull = (dist - radius) / dist;
+          out = { x: current.x + dx * pull, y: current.y + dy * pull };
+        }
+        break;
+      }
+      case 'dynamic': {
+        let speed = 0;
+        if (previousRaw) {
+          const dt = Math.max(1, timeMs - previousTimeMs);
+          speed = Math.hypot(x - previousRaw.x, y - previousRaw.y) / dt;
+        }
+        const strength = Math.max(0, Math.min(1, mode.strength));
+        const minFollow = 1 - strength * 0.95;
+        const fast = Math.min(1, speed / DYNAMIC_REFERENCE_PX_PER_MS);
+        const follow = minFollow + (1 - minFollow) * fast;
+        out = {
+          x: current.x + (x - current.x) * follow,
+          y: current.y + (y - current.y) * follow,
+        };
+        break;
+      }
+      default:
+        out = { x, y };
+    }
+
+    this.current = out;
+    return { ...point, x: out.x, y: out.y };
+  }
+
+  /**
+   * End the stroke: catch-up points from the stabilized position to where
+   * the pen lifted (the last one is exactly the pen position)
+   */
+  finish(): T[] {
+    const current = this.current;
+    const target = this.lastRaw;
+    this.reset();
+    if (!current || !target) return [];
+
+    const dx = target.x - current.x;
+    const dy = target.y - current.y;
+    const dist = Math.hypot(dx, dy);
+    if (dist < 1e-3) return [];
+
+    const steps = Math.max(1, Math.ceil(dist / CATCH_UP_STEP_PX));
+    const points: T[] = [];
+    for (let i = 1; i <= steps; i++) {
+      const t = i / steps;
+      points.push({ ...target, x: current.x + dx * t, y: current.y + dy * t });
+    }
+    return points;
+  }
+}
+
+// Linearly weighted mean position (newest sample has the highest weight)
+function weightedAverage(history: Position[]): Position {
+  let sumX = 0;
+  let sumY = 0;
+  let total = 0;
+  history.forEach((p, i) => {
+    const weight = i + 1;
+    sumX += p.x * weight;
+    sumY += p.y * weight;
+    total += weight;
+  });
+  return { x: sumX / total, y: sumY / total };
+}